@@ -0,0 +1,62 @@
+//! How long parsing takes against a big workspace, with and without compiling it first.
+//!
+//! Run with `cargo bench --bench workspace`. This uses no benchmarking framework, it just
+//! reports the average time per parse.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use axotag::{Package, TagParser, Version};
+
+const PACKAGES: usize = 1000;
+const ITERATIONS: u32 = 2000;
+
+fn main() {
+    let packages = (0..PACKAGES)
+        .map(|i| Package {
+            name: format!("package-{i}"),
+            version: Some(Version::new(1, 0, (i % 7) as u64)),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let tags = [
+        "package-0-v1.0.0",
+        "package-500-v1.0.3",
+        "package-999/v1.0.5",
+        "releases/package-123-v1.0.4",
+        "v1.0.0",
+    ];
+    let parser = TagParser::default();
+
+    let plain = time(|| {
+        for tag in tags {
+            let _ = black_box(parser.parse_tag(black_box(&packages), black_box(tag)));
+        }
+    });
+    report("TagParser::parse_tag", plain, tags.len());
+
+    let workspace = parser.compile(&packages);
+    let compiled = time(|| {
+        for tag in tags {
+            let _ = black_box(workspace.parse_tag(black_box(tag)));
+        }
+    });
+    report("CompiledWorkspace::parse_tag", compiled, tags.len());
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    // Warm up
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration, tags: usize) {
+    let per_parse = elapsed / (ITERATIONS * tags as u32);
+    println!("{name}: {per_parse:?} per parse ({PACKAGES} packages)");
+}
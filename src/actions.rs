@@ -0,0 +1,112 @@
+//! Moving `vN` and `vN.N` tags, which GitHub Actions repos keep pointed at the newest
+//! release in each series (alongside the full `vN.N.N` tag).
+
+use std::fmt;
+
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagParser, VPrefixPolicy, Version};
+
+/// The series a moving tag follows, like `v1` (every `1.x.y`) or `v1.2` (every `1.2.y`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MovingAlias {
+    /// The major version of the series
+    pub major: u64,
+    /// The minor version of the series, if this is a `vN.N` alias
+    pub minor: Option<u64>,
+}
+
+impl MovingAlias {
+    /// Parse a version component like `1` or `1.2` (without any `v`)
+    pub(crate) fn parse(input: &str) -> Option<MovingAlias> {
+        let number = |part: &str| {
+            let canonical = part == "0" || !part.starts_with('0');
+            let digits = !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            (canonical && digits).then(|| part.parse().ok()).flatten()
+        };
+        let (major, minor) = match input.split_once('.') {
+            Some((major, minor)) => (major, Some(number(minor)?)),
+            None => (input, None),
+        };
+        Some(MovingAlias {
+            major: number(major)?,
+            minor,
+        })
+    }
+
+    /// Whether this alias should point at the given version
+    ///
+    /// Prereleases never move aliases.
+    pub fn covers(&self, version: &Version) -> bool {
+        version.pre.is_empty()
+            && version.major == self.major
+            && self.minor.is_none_or(|minor| minor == version.minor)
+    }
+}
+
+impl fmt::Display for MovingAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.minor {
+            Some(minor) => write!(f, "{}.{minor}", self.major),
+            None => write!(f, "{}", self.major),
+        }
+    }
+}
+
+impl PartialAnnouncementTag {
+    /// If this tag is a moving alias like `v1` or `my-action-v1.2`, get its series
+    ///
+    /// See [`TagParser::moving_aliases`][].
+    pub fn moving_alias(&self) -> Option<MovingAlias> {
+        match &self.release {
+            AnnouncementKind::Channel { channel, .. } => {
+                MovingAlias::parse(channel.strip_prefix('v').unwrap_or(channel))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TagParser {
+    /// Get the moving tags that should be updated to point at an announcement
+    /// (`v1` and `v1.2` for `v1.2.3`)
+    ///
+    /// Prereleases (and announcements without a version) don't move any tags. Tags are
+    /// formatted like [`TagParser::format_tag`][], so package releases get package-scoped
+    /// aliases (`my-action-v1`).
+    pub fn moving_alias_tags(
+        &self,
+        packages: &[Package],
+        announcing: &PartialAnnouncementTag,
+    ) -> Vec<String> {
+        let (package, version) = match &announcing.release {
+            AnnouncementKind::Unified(version) => (None, version),
+            AnnouncementKind::Package { idx, version } => (packages.get(*idx), version),
+            _ => return vec![],
+        };
+        let major = MovingAlias {
+            major: version.major,
+            minor: None,
+        };
+        let minor = MovingAlias {
+            minor: Some(version.minor),
+            ..major
+        };
+        [major, minor]
+            .into_iter()
+            .filter(|alias| alias.covers(version))
+            .map(|alias| self.format_tag_component(package, &alias.to_string()))
+            .collect()
+    }
+
+    /// Check if the version component of a tag is a moving alias, if we recognize those
+    pub(crate) fn parse_moving_alias(&self, version: &str) -> Option<MovingAlias> {
+        if !self.moving_aliases {
+            return None;
+        }
+        match (self.v_prefix, version.strip_prefix('v')) {
+            (VPrefixPolicy::Require, None) | (VPrefixPolicy::Forbid, Some(_)) => None,
+            (_, stripped) => MovingAlias::parse(stripped.unwrap_or(version)),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Release policies that limit which versions can be announced.
+
+use crate::errors::{TagError, TagResult};
+use crate::{Package, TagParser, Version};
+
+/// The range of versions a package (or unified releases) may announce
+///
+/// Both bounds compare by semver precedence, so build metadata doesn't matter. Freezing
+/// the 2.x series of a package is a floor of `3.0.0`, and keeping a package pre-1.0 is a
+/// ceiling of `1.0.0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionBounds {
+    /// The package these bounds apply to, or None for unified releases
+    pub package_name: Option<String>,
+    /// The lowest version that can be announced
+    pub floor: Option<Version>,
+    /// The version announcements have to stay below
+    pub ceiling: Option<Version>,
+}
+
+impl TagParser {
+    /// Check that an announced version is within all of our [`TagParser::version_bounds`][]
+    ///
+    /// A unified release has to satisfy the unified bounds, and the bounds of every
+    /// package it would release.
+    pub(crate) fn check_version_bounds(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: Option<usize>,
+        version: &Version,
+    ) -> TagResult<()> {
+        let names = match package.and_then(|idx| packages.get(idx)) {
+            Some(package) => vec![Some(package.name.as_str())],
+            None => std::iter::once(None)
+                .chain(
+                    packages
+                        .iter()
+                        .filter(|p| {
+                            p.version
+                                .as_ref()
+                                .is_some_and(|v| self.versions_match(v, version))
+                        })
+                        .map(|p| Some(p.name.as_str())),
+                )
+                .collect(),
+        };
+        for bounds in &self.version_bounds {
+            if !names.contains(&bounds.package_name.as_deref()) {
+                continue;
+            }
+            if let Some(floor) = &bounds.floor {
+                if version.cmp_precedence(floor).is_lt() {
+                    return Err(TagError::VersionBelowFloor {
+                        tag: tag.to_owned(),
+                        package_name: bounds.package_name.clone(),
+                        version: version.clone(),
+                        floor: floor.clone(),
+                    });
+                }
+            }
+            if let Some(ceiling) = &bounds.ceiling {
+                if version.cmp_precedence(ceiling).is_ge() {
+                    return Err(TagError::VersionAboveCeiling {
+                        tag: tag.to_owned(),
+                        package_name: bounds.package_name.clone(),
+                        version: version.clone(),
+                        ceiling: ceiling.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
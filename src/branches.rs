@@ -0,0 +1,64 @@
+//! Tags made on maintenance branches, like `v1.2.4-lts` or `release-1.x/my-app-v1.2.4`.
+
+use semver::{Prerelease, Version};
+
+use crate::actions::MovingAlias;
+use crate::TagParser;
+
+/// The maintenance branch a tag was made on
+///
+/// See [`TagParser::branch_qualifiers`][] and [`TagParser::series_branches`][].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MaintenanceBranch {
+    /// The name of the branch, like `lts` or `release-1.x`
+    pub name: String,
+    /// The series the branch maintains, if its name says (`1.x` for `release-1.x`)
+    pub series: Option<MovingAlias>,
+}
+
+impl MaintenanceBranch {
+    /// Read a tag prefix that names a series, like `release-1.x`, `1.2.x`, or `maint/1.x`
+    pub(crate) fn from_prefix(prefix: &str) -> Option<MaintenanceBranch> {
+        let last = prefix.rsplit('/').next()?;
+        let series = last.rsplit('-').next()?.strip_suffix(".x")?;
+        Some(MaintenanceBranch {
+            name: prefix.to_owned(),
+            series: Some(MovingAlias::parse(series)?),
+        })
+    }
+
+    /// Whether a version belongs on this branch (prereleases included)
+    pub fn covers(&self, version: &Version) -> bool {
+        self.series.is_none_or(|series| {
+            series.major == version.major && series.minor.is_none_or(|m| m == version.minor)
+        })
+    }
+}
+
+impl TagParser {
+    /// Take a [`TagParser::branch_qualifiers`][] qualifier off the end of a version's
+    /// prerelease (`1.2.4-lts` => `1.2.4`, `1.2.4-rc.1.lts` => `1.2.4-rc.1`)
+    pub(crate) fn split_branch_qualifier(
+        &self,
+        mut version: Version,
+    ) -> (Version, Option<MaintenanceBranch>) {
+        let pre = version.pre.as_str();
+        let Some((rest, qualifier)) = self.branch_qualifiers.iter().find_map(|qualifier| {
+            let rest = pre.strip_suffix(qualifier.as_str())?;
+            match rest.strip_suffix('.') {
+                Some(rest) => Some((rest, qualifier)),
+                None => rest.is_empty().then_some((rest, qualifier)),
+            }
+        }) else {
+            return (version, None);
+        };
+        let branch = MaintenanceBranch {
+            name: qualifier.clone(),
+            series: None,
+        };
+        version.pre = Prerelease::new(rest).expect("a prefix of a prerelease is a prerelease");
+        (version, Some(branch))
+    }
+}
@@ -0,0 +1,337 @@
+//! Working out what the next version (and tag) should be.
+
+use semver::Prerelease;
+
+use crate::errors::{TagError, TagResult};
+use crate::history::release_series;
+use crate::{Package, TagParser, Version};
+
+/// What kind of version bump to make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BumpLevel {
+    /// `1.2.3` => `2.0.0` (or graduate `2.0.0-rc.1` => `2.0.0`)
+    Major,
+    /// `1.2.3` => `1.3.0` (or graduate `1.3.0-rc.1` => `1.3.0`)
+    Minor,
+    /// `1.2.3` => `1.2.4` (or graduate `1.2.4-rc.1` => `1.2.4`)
+    Patch,
+    /// `1.2.3-rc.1` => `1.2.3-rc.2`, or start a prerelease of the next patch
+    /// (`1.2.3` => `1.2.4-rc.1`)
+    Prerelease {
+        /// The kind of prerelease to make, like `rc` or `beta`
+        ///
+        /// If this differs from the current prerelease's label, the counter restarts
+        /// (`1.2.3-beta.2` => `1.2.3-rc.1`). If that would sort below the current
+        /// prerelease, a prerelease of the next patch is started instead (`1.2.3-rc.1` =>
+        /// `1.2.4-beta.1`). Defaults to the current label, or `rc`.
+        label: Option<String>,
+    },
+}
+
+/// What kind of version bump happened between two releases, see [`TagParser::bump_between`][]
+///
+/// Prereleases count as the bump they lead up to, so `1.2.3` => `2.0.0-rc.1` is a major
+/// bump, and so is graduating `2.0.0-rc.2` => `2.0.0` (like [`BumpLevel`][] would make it).
+/// Only moving between prereleases of the same version is [`BumpKind::Prerelease`][].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BumpKind {
+    /// The same release (build metadata and build numbers may differ)
+    None,
+    /// `1.2.3-rc.1` => `1.2.3-rc.2` (or `1.2.3-beta.2` => `1.2.3-rc.1`)
+    Prerelease,
+    /// `1.2.3` => `1.2.4`, `1.2.4-rc.1`, or graduating `1.2.4-rc.1` => `1.2.4`
+    Patch,
+    /// `1.2.3` => `1.3.0`, `1.3.0-rc.1`, or graduating `1.3.0-rc.1` => `1.3.0`
+    Minor,
+    /// `1.2.3` => `2.0.0`, `2.0.0-rc.1`, or graduating `2.0.0-rc.1` => `2.0.0` (or a
+    /// bigger epoch)
+    Major,
+    /// The new release is older than the old one
+    Downgrade,
+}
+
+/// The suggested next tag for a package (or unified release)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NextTag {
+    /// The version to release
+    pub version: Version,
+    /// The tag to create for it
+    pub tag: String,
+    /// The existing tag this was bumped from, if the bump was based on one
+    pub previous_tag: Option<String>,
+}
+
+impl TagParser {
+    /// Suggest the next tag to create for a package (or a unified release, if None)
+    ///
+    /// The bump starts from the newest version among `existing_tags` for that package
+    /// (counting unified releases, like [`TagParser::check_not_regression`][] does),
+    /// falling back to the package's own version (or the version every package has,
+    /// for unified releases).
+    pub fn suggest_next_tag<I>(
+        &self,
+        packages: &[Package],
+        package: Option<usize>,
+        existing_tags: I,
+        bump: &BumpLevel,
+    ) -> TagResult<NextTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let newest = self.newest_in_series(packages, existing_tags, package);
+        let (current, previous_tag) = match newest {
+            Some(newest) => (newest.announced_version(), Some(newest.tag)),
+            None => {
+                let current = match package {
+                    Some(idx) => packages.get(idx).and_then(|p| p.version.clone()),
+                    None => crate::unified_version(packages).cloned(),
+                };
+                let current = current.ok_or_else(|| TagError::NoVersionToBump {
+                    package_name: package
+                        .and_then(|idx| packages.get(idx))
+                        .map(|p| p.name.clone()),
+                })?;
+                (current, None)
+            }
+        };
+
+        let version = next_version(&current, bump)?;
+        Ok(NextTag {
+            tag: self.try_format_tag(package.and_then(|idx| packages.get(idx)), &version)?,
+            version,
+            previous_tag,
+        })
+    }
+}
+
+impl TagParser {
+    /// Work out the final release tag a prerelease tag leads up to (`my-app-v1.2.3-rc.2`
+    /// graduates to `my-app-v1.2.3`), checking that `existing_tags` haven't released it yet
+    ///
+    /// The new tag is spelled like the prerelease tag, with the prerelease (and any build
+    /// metadata) dropped. The prerelease tag is parsed like [`TagParser::check_not_regression`][]
+    /// parses existing tags, so it doesn't have to match its package's current version.
+    pub fn graduate_tag<I>(
+        &self,
+        packages: &[Package],
+        prerelease_tag: &str,
+        existing_tags: I,
+    ) -> TagResult<NextTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let announcing = self
+            .existing_tag_parser()
+            .parse_tag(packages, prerelease_tag)?;
+        let (series, prerelease) = release_series(&announcing.release)
+            .filter(|(_, version)| !version.pre.is_empty())
+            .ok_or_else(|| TagError::NotAPrerelease {
+                tag: prerelease_tag.to_owned(),
+            })?;
+        let version = Version::new(prerelease.major, prerelease.minor, prerelease.patch);
+
+        let released = self
+            .parse_existing(packages, existing_tags)
+            .find(|existing| {
+                // Build metadata doesn't make it a different release
+                release_series(&existing.release).is_some_and(|(s, v)| {
+                    s == series
+                        && v.pre.is_empty()
+                        && (v.major, v.minor, v.patch)
+                            == (version.major, version.minor, version.patch)
+                })
+            });
+        if let Some(existing) = released {
+            return Err(TagError::AlreadyReleased {
+                tag: prerelease_tag.to_owned(),
+                version,
+                existing_tag: existing.tag,
+            });
+        }
+
+        // Keep the tag's spelling by dropping the suffix that spelled the prerelease
+        let suffix = if prerelease.build.is_empty() {
+            format!("-{}", prerelease.pre)
+        } else {
+            format!("-{}+{}", prerelease.pre, prerelease.build)
+        };
+        let tag = match announcing.tag.strip_suffix(&suffix) {
+            Some(tag) => tag.to_owned(),
+            None => self.format_tag(series.and_then(|idx| packages.get(idx)), &version),
+        };
+        self.check_not_denied(&tag)?;
+        Ok(NextTag {
+            version,
+            tag,
+            previous_tag: Some(announcing.tag),
+        })
+    }
+}
+
+impl TagParser {
+    /// Work out what kind of bump it is from one release of a package (or unified release)
+    /// to another, like `my-app-v1.2.3` => `my-app-v1.3.0-rc.1` ([`BumpKind::Minor`][])
+    ///
+    /// Releases are ordered like [`TagParser::check_not_regression`][] orders them (epochs,
+    /// then versions, then build numbers), and both tags are parsed like it parses existing
+    /// tags, so they don't have to match the packages' current versions.
+    pub fn bump_between(
+        &self,
+        packages: &[Package],
+        old_tag: &str,
+        new_tag: &str,
+    ) -> TagResult<BumpKind> {
+        let parser = self.existing_tag_parser();
+        let old = parser.parse_tag(packages, old_tag)?;
+        let new = parser.parse_tag(packages, new_tag)?;
+        let not_same_series = || TagError::NotSameSeries {
+            old_tag: old_tag.to_owned(),
+            new_tag: new_tag.to_owned(),
+        };
+        let (old_series, old_version) = release_series(&old.release).ok_or_else(not_same_series)?;
+        let (new_series, new_version) = release_series(&new.release).ok_or_else(not_same_series)?;
+        if old_series != new_series {
+            return Err(not_same_series());
+        }
+
+        if self.cmp_announced(&old, &new).is_gt() {
+            return Ok(BumpKind::Downgrade);
+        }
+        if new.epoch.unwrap_or(0) > old.epoch.unwrap_or(0) {
+            return Ok(BumpKind::Major);
+        }
+        Ok(bump_kind(old_version, new_version))
+    }
+}
+
+/// Work out what kind of bump it is from `old` to `new`, see [`BumpKind`][]
+///
+/// Build metadata is ignored, so `1.2.3+a` => `1.2.3+b` is [`BumpKind::None`][].
+pub fn bump_kind(old: &Version, new: &Version) -> BumpKind {
+    let release = |v: &Version| (v.major, v.minor, v.patch);
+    let (old_release, new_release) = (release(old), release(new));
+    // semver puts a prerelease before its release
+    let ordering = old_release
+        .cmp(&new_release)
+        .then_with(|| old.pre.cmp(&new.pre));
+    if ordering.is_gt() {
+        return BumpKind::Downgrade;
+    }
+    if ordering.is_eq() {
+        return BumpKind::None;
+    }
+    if old_release != new_release {
+        return if old.major != new.major {
+            BumpKind::Major
+        } else if old.minor != new.minor {
+            BumpKind::Minor
+        } else {
+            BumpKind::Patch
+        };
+    }
+    if !new.pre.is_empty() {
+        return BumpKind::Prerelease;
+    }
+    // Graduating a prerelease is the bump that started it
+    if new.minor == 0 && new.patch == 0 {
+        BumpKind::Major
+    } else if new.patch == 0 {
+        BumpKind::Minor
+    } else {
+        BumpKind::Patch
+    }
+}
+
+/// Compute the version after `current` for the given kind of bump
+///
+/// This fails with [`TagError::InvalidPrereleaseLabel`][] if a prerelease label isn't
+/// valid semver, with [`TagError::PrereleaseCounterOverflow`][] if the prerelease
+/// counter can't go any higher, and with [`TagError::VersionOverflow`][] if the major,
+/// minor or patch component can't.
+pub fn next_version(current: &Version, bump: &BumpLevel) -> TagResult<Version> {
+    let is_pre = !current.pre.is_empty();
+    let mut next = Version::new(current.major, current.minor, current.patch);
+    match bump {
+        BumpLevel::Major => {
+            if !(is_pre && current.minor == 0 && current.patch == 0) {
+                next = Version::new(increment(current, "major", current.major)?, 0, 0);
+            }
+        }
+        BumpLevel::Minor => {
+            if !(is_pre && current.patch == 0) {
+                let minor = increment(current, "minor", current.minor)?;
+                next = Version::new(current.major, minor, 0);
+            }
+        }
+        BumpLevel::Patch => {
+            if !is_pre {
+                next.patch = increment(current, "patch", current.patch)?;
+            }
+        }
+        BumpLevel::Prerelease { label } => {
+            let current_label = prerelease_label(&current.pre);
+            let label = label.as_deref().or(current_label).unwrap_or("rc");
+            if is_pre && current_label == Some(label) {
+                next.pre = increment_prerelease(current)?;
+            } else {
+                next.pre = Prerelease::new(&format!("{label}.1")).map_err(|e| {
+                    TagError::InvalidPrereleaseLabel {
+                        label: label.to_owned(),
+                        reason: e.to_string(),
+                    }
+                })?;
+                // Switching to a label that sorts lower would go backwards
+                if !is_pre || next.pre < current.pre {
+                    next.patch = increment(current, "patch", current.patch)?;
+                }
+            }
+        }
+    }
+    Ok(next)
+}
+
+/// Add one to a component of `current`, failing if it's already as big as it gets
+fn increment(current: &Version, component: &'static str, value: u64) -> TagResult<u64> {
+    value
+        .checked_add(1)
+        .ok_or_else(|| TagError::VersionOverflow {
+            version: current.clone(),
+            component,
+        })
+}
+
+/// Get the label of a prerelease (`rc` in `rc.1`), if it has one
+fn prerelease_label(pre: &Prerelease) -> Option<&str> {
+    let first = pre.as_str().split('.').next()?;
+    (!first.is_empty() && !first.bytes().all(|b| b.is_ascii_digit())).then_some(first)
+}
+
+/// Increment the counter of a version's prerelease (`rc.1` => `rc.2`, `rc` => `rc.1`)
+fn increment_prerelease(version: &Version) -> TagResult<Prerelease> {
+    let mut identifiers = version
+        .pre
+        .as_str()
+        .split('.')
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    match identifiers.last().and_then(|last| last.parse::<u64>().ok()) {
+        Some(counter) => {
+            let counter =
+                counter
+                    .checked_add(1)
+                    .ok_or_else(|| TagError::PrereleaseCounterOverflow {
+                        version: version.clone(),
+                    })?;
+            *identifiers.last_mut().unwrap() = counter.to_string();
+        }
+        None => identifiers.push("1".to_owned()),
+    }
+    // The identifiers were already valid, and the counter still is
+    Ok(Prerelease::new(&identifiers.join(".")).expect("incremented prerelease is valid"))
+}
@@ -0,0 +1,173 @@
+//! Parsing lots of tags against the same (big) workspace.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::errors::TagResult;
+use crate::matching::PackageMatch;
+use crate::stats::{BulkParse, ParseStats};
+use crate::{NameMatcher, Package, PartialAnnouncementTag, TagParser};
+
+/// A parser and the packages it parses tags against, with the package names indexed
+///
+/// Parsing normally compares a tag against every package's names, which adds up in
+/// workspaces with hundreds of packages. This looks names up instead, so the cost of a
+/// parse barely depends on how many packages there are. Results are exactly what
+/// [`TagParser::parse_tag`][] would produce.
+///
+/// This is `Send + Sync`, so one can be shared between threads (behind an `Arc`). The
+/// parser and packages can't be changed, since the index would go stale; compile a new one
+/// instead.
+#[derive(Debug, Clone)]
+pub struct CompiledWorkspace {
+    parser: TagParser,
+    packages: Vec<Package>,
+    index: NameIndex,
+}
+
+impl CompiledWorkspace {
+    /// Index some packages for parsing tags with the given parser
+    pub fn new(parser: TagParser, packages: Vec<Package>) -> CompiledWorkspace {
+        #[cfg(feature = "unicode")]
+        let packages = if parser.normalize_unicode {
+            crate::unicode::nfc_packages(&packages).unwrap_or(packages)
+        } else {
+            packages
+        };
+        let index = NameIndex::new(&packages, parser.name_matcher());
+        CompiledWorkspace {
+            parser,
+            packages,
+            index,
+        }
+    }
+
+    /// Get the parser
+    pub fn parser(&self) -> &TagParser {
+        &self.parser
+    }
+
+    /// Get the packages, which indices in results refer to
+    ///
+    /// With `TagParser::normalize_unicode`, these are the normalized packages.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// Parse a tag, see [`TagParser::parse_tag`][]
+    pub fn parse_tag(&self, tag: &str) -> TagResult<PartialAnnouncementTag> {
+        #[cfg(feature = "unicode")]
+        if self.parser.normalize_unicode {
+            if let Some(tag) = crate::unicode::nfc_tag(tag) {
+                return self.parse_normalized_tag(&tag);
+            }
+        }
+        self.parse_normalized_tag(tag)
+    }
+
+    /// Parse every tag in `tags`, collecting statistics, see [`TagParser::parse_tags`][]
+    pub fn parse_tags<I>(&self, tags: I) -> BulkParse
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let start = Instant::now();
+        let mut stats = ParseStats::default();
+        let mut results = vec![];
+        for tag in tags {
+            let result = self.parse_tag(tag.as_ref());
+            stats.record(&result);
+            results.push(result);
+        }
+        stats.elapsed = start.elapsed();
+        BulkParse { results, stats }
+    }
+
+    /// Check whether a tag would announce any of our packages, see [`TagParser::is_relevant_tag`][]
+    pub fn is_relevant_tag(&self, tag: &str) -> bool {
+        #[cfg(feature = "unicode")]
+        if self.parser.normalize_unicode {
+            if let Some(tag) = crate::unicode::nfc_tag(tag) {
+                return (self.parser).is_relevant_normalized_tag(
+                    &self.packages,
+                    &tag,
+                    Some(&self.index),
+                );
+            }
+        }
+        (self.parser).is_relevant_normalized_tag(&self.packages, tag, Some(&self.index))
+    }
+
+    fn parse_normalized_tag(&self, tag: &str) -> TagResult<PartialAnnouncementTag> {
+        self.parser
+            .parse_normalized_tag(&self.packages, tag, Some(&self.index), None)
+    }
+}
+
+impl TagParser {
+    /// Index some packages for parsing lots of tags against them, see [`CompiledWorkspace`][]
+    pub fn compile(&self, packages: &[Package]) -> CompiledWorkspace {
+        CompiledWorkspace::new(self.clone(), packages.to_vec())
+    }
+}
+
+/// Every name (and alias) of some packages, normalized the way a [`NameMatcher`][] compares them
+#[derive(Debug, Clone)]
+pub(crate) struct NameIndex {
+    /// How names were normalized
+    names: NameMatcher<'static>,
+    /// The packages with each name, and which alias it is (in the order a linear search
+    /// would find them)
+    by_name: HashMap<String, Vec<(usize, Option<usize>)>>,
+    /// The length of every name, longest first
+    lengths: Vec<usize>,
+}
+
+impl NameIndex {
+    fn new(packages: &[Package], names: NameMatcher<'static>) -> NameIndex {
+        let mut by_name = HashMap::<String, Vec<(usize, Option<usize>)>>::new();
+        for (idx, package) in packages.iter().enumerate() {
+            for (alias, name) in names.package_names(package) {
+                by_name
+                    .entry(names.normalize_str(name).into_owned())
+                    .or_default()
+                    .push((idx, alias));
+            }
+        }
+        let mut lengths = by_name.keys().map(String::len).collect::<Vec<_>>();
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        lengths.dedup();
+        NameIndex {
+            names,
+            by_name,
+            lengths,
+        }
+    }
+
+    /// Find every name that prefixes the input, exactly like [`crate::matching::package_prefixes`][]
+    ///
+    /// Names of the same length that both prefix the input would have to be the same name,
+    /// so going through the lengths longest first gives the same order a stable sort does.
+    pub(crate) fn prefixes<'a>(&self, input: &'a str) -> Vec<PackageMatch<'a>> {
+        let normalized: Cow<'_, str> = self.names.normalize_str(input);
+        let mut result = vec![];
+        for &len in &self.lengths {
+            let Some(candidate) = normalized.get(..len) else {
+                continue;
+            };
+            let Some(found) = self.by_name.get(candidate) else {
+                continue;
+            };
+            for &(idx, alias) in found {
+                result.push(PackageMatch {
+                    idx,
+                    alias,
+                    matched: &input[..len],
+                    rest: &input[len..],
+                });
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,100 @@
+//! Checking a tag against the tags a remote already has, before trying to push it.
+
+/// A reason creating a tag would fail (or cause trouble), see [`check_tag_conflicts`][]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TagConflict {
+    /// The tag already exists
+    Exists,
+    /// A tag that only differs in case exists, which clashes on case-insensitive
+    /// filesystems (and confuses humans everywhere)
+    DifferentCase {
+        /// The existing tag
+        existing_tag: String,
+    },
+    /// An existing tag is a directory of this one or the other way around (`v1` and
+    /// `v1/beta`), which git can't store side by side
+    PathCollision {
+        /// The existing tag
+        existing_tag: String,
+    },
+    /// The tag is named like a branch (`main`) or one of git's own refs (`HEAD`), so git
+    /// commands that take either would be ambiguous
+    BranchLike,
+}
+
+/// Names git itself uses for refs, which are ambiguous whatever the branches are
+const GIT_REF_NAMES: &[&str] = &["HEAD", "FETCH_HEAD", "ORIG_HEAD", "MERGE_HEAD"];
+
+/// Names that everyone's muscle memory uses for branches, for when we don't know the
+/// repository's actual ones
+const BRANCH_NAMES: &[&str] = &["main", "master", "develop", "trunk"];
+
+/// Check whether creating `proposed_tag` would conflict with `existing_tags`
+///
+/// Conflicts come in the order of `existing_tags` (after [`TagConflict::BranchLike`][],
+/// which doesn't depend on them). No conflicts means `git tag` should succeed.
+///
+/// This doesn't know the repository's branches, so tags named like common ones (`main`,
+/// `develop`) are [`TagConflict::BranchLike`][]. See [`check_tag_conflicts_with_branches`][]
+/// to pass the actual ones.
+pub fn check_tag_conflicts<I>(proposed_tag: &str, existing_tags: I) -> Vec<TagConflict>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    check_tag_conflicts_with_branches(proposed_tag, existing_tags, BRANCH_NAMES)
+}
+
+/// Check whether creating `proposed_tag` would conflict with `existing_tags`, or with
+/// one of the repository's `branches`
+///
+/// Branches can be short names (`main`) or full refs (`refs/heads/main`). Tags named
+/// like git's own refs (`HEAD`, `FETCH_HEAD`) always conflict. See
+/// [`check_tag_conflicts`][] for the rest.
+pub fn check_tag_conflicts_with_branches<I, B>(
+    proposed_tag: &str,
+    existing_tags: I,
+    branches: B,
+) -> Vec<TagConflict>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    B: IntoIterator,
+    B::Item: AsRef<str>,
+{
+    let mut conflicts = vec![];
+    let is_branch = |branch: B::Item| {
+        let branch = branch.as_ref();
+        branch.strip_prefix("refs/heads/").unwrap_or(branch) == proposed_tag
+    };
+    if GIT_REF_NAMES.contains(&proposed_tag) || branches.into_iter().any(is_branch) {
+        conflicts.push(TagConflict::BranchLike);
+    }
+    let is_directory_of = |dir: &str, path: &str| {
+        path.strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+    for existing in existing_tags {
+        let existing = existing.as_ref();
+        let conflict = if existing == proposed_tag {
+            TagConflict::Exists
+        } else if existing.eq_ignore_ascii_case(proposed_tag) {
+            TagConflict::DifferentCase {
+                existing_tag: existing.to_owned(),
+            }
+        } else if is_directory_of(existing, proposed_tag) || is_directory_of(proposed_tag, existing)
+        {
+            TagConflict::PathCollision {
+                existing_tag: existing.to_owned(),
+            }
+        } else {
+            continue;
+        };
+        if !conflicts.contains(&conflict) {
+            conflicts.push(conflict);
+        }
+    }
+    conflicts
+}
@@ -0,0 +1,247 @@
+//! A harness for parsing a repo's whole tag history, to check that an axotag upgrade
+//! (or a settings change) still reads every tag the same way.
+//!
+//! Record a [`CorpusReport`][] with the old setup, store it as JSON, and
+//! [`CorpusReport::compare`][] it against a report from the new one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::snapshot::WorkspaceSnapshot;
+use crate::{Package, TagParser};
+
+/// Real tags, along with the packages they were made for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagCorpus {
+    /// The packages to parse the tags against
+    pub packages: Vec<Package>,
+    /// The tags, in the order they were listed
+    pub tags: Vec<String>,
+}
+
+/// Errors reading a [`TagCorpus`][]
+#[derive(Debug, Error, Diagnostic)]
+pub enum CorpusError {
+    /// A file couldn't be read
+    #[error("Couldn't read {path}")]
+    Io {
+        /// The file
+        path: PathBuf,
+        /// Why it couldn't be read
+        #[source]
+        source: std::io::Error,
+    },
+    /// The package manifest wasn't a [`WorkspaceSnapshot`][]
+    #[error("Couldn't parse the package manifest {path}")]
+    #[diagnostic(help("the manifest should look like {{\"packages\": [{{\"name\": \"my-app\", \"version\": \"1.2.3\"}}]}}"))]
+    Manifest {
+        /// The file
+        path: PathBuf,
+        /// What was wrong with it
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// How a single tag of a corpus parsed
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CorpusOutcome {
+    /// It parsed, into this (serialized) [`PartialAnnouncementTag`][crate::PartialAnnouncementTag]
+    Parsed(serde_json::Value),
+    /// It failed to parse
+    Failed {
+        /// The [`TagErrorKind`][crate::errors::TagErrorKind] of the error
+        kind: String,
+        /// The error message
+        message: String,
+    },
+}
+
+/// The parse of every tag in a [`TagCorpus`][]
+///
+/// This (de)serializes, so it can be kept as a baseline for [`CorpusReport::compare`][].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CorpusReport {
+    /// Each tag and how it parsed, in corpus order (later duplicates of a tag are dropped)
+    pub outcomes: Vec<(String, CorpusOutcome)>,
+}
+
+/// A tag a [`CorpusReport`][] reads differently from its baseline
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CorpusChange {
+    /// The tag
+    pub tag: String,
+    /// How it parsed in the baseline, None if it wasn't in the baseline
+    pub before: Option<CorpusOutcome>,
+    /// How it parses now, None if it isn't in the corpus anymore
+    pub after: Option<CorpusOutcome>,
+}
+
+impl TagCorpus {
+    /// Read a corpus from a file of tags (one per line, blank lines are skipped) and a
+    /// package manifest (a [`WorkspaceSnapshot`][] as JSON)
+    pub fn read(
+        tags_path: impl AsRef<Path>,
+        manifest_path: impl AsRef<Path>,
+    ) -> Result<TagCorpus, CorpusError> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|source| CorpusError::Io {
+                path: path.to_owned(),
+                source,
+            })
+        };
+        let manifest_path = manifest_path.as_ref();
+        TagCorpus::from_files(
+            &read(tags_path.as_ref())?,
+            manifest_path,
+            &read(manifest_path)?,
+        )
+    }
+
+    /// [`TagCorpus::read`][], without blocking the async runtime on the file reads
+    #[cfg(feature = "tokio")]
+    pub async fn read_async(
+        tags_path: impl AsRef<Path>,
+        manifest_path: impl AsRef<Path>,
+    ) -> Result<TagCorpus, CorpusError> {
+        let read = |path: &Path| {
+            let path = path.to_owned();
+            async move {
+                tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(|source| CorpusError::Io { path, source })
+            }
+        };
+        let manifest_path = manifest_path.as_ref();
+        let tags = read(tags_path.as_ref()).await?;
+        TagCorpus::from_files(&tags, manifest_path, &read(manifest_path).await?)
+    }
+
+    /// Make a corpus from the contents of its files
+    fn from_files(
+        tags: &str,
+        manifest_path: &Path,
+        manifest: &str,
+    ) -> Result<TagCorpus, CorpusError> {
+        let snapshot: WorkspaceSnapshot =
+            serde_json::from_str(manifest).map_err(|source| CorpusError::Manifest {
+                path: manifest_path.to_owned(),
+                source,
+            })?;
+        Ok(TagCorpus {
+            packages: snapshot.packages,
+            tags: tags
+                .lines()
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        })
+    }
+}
+
+impl TagParser {
+    /// Parse every tag in a corpus
+    pub fn run_corpus(&self, corpus: &TagCorpus) -> CorpusReport {
+        let mut outcomes: Vec<(String, CorpusOutcome)> = vec![];
+        for tag in &corpus.tags {
+            if outcomes.iter().any(|(seen, _)| seen == tag) {
+                continue;
+            }
+            let outcome = match self.parse_tag(&corpus.packages, tag) {
+                Ok(announcing) => CorpusOutcome::Parsed(
+                    serde_json::to_value(announcing).expect("parse results always serialize"),
+                ),
+                Err(e) => CorpusOutcome::Failed {
+                    kind: format!("{:?}", e.kind()),
+                    message: e.to_string(),
+                },
+            };
+            outcomes.push((tag.clone(), outcome));
+        }
+        CorpusReport { outcomes }
+    }
+}
+
+impl CorpusReport {
+    /// Get how many tags parsed
+    pub fn parsed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, CorpusOutcome::Parsed(_)))
+            .count()
+    }
+
+    /// Count the tags that failed to parse, by the kind of error
+    pub fn failures_by_kind(&self) -> BTreeMap<&str, usize> {
+        let mut failures = BTreeMap::new();
+        for (_, outcome) in &self.outcomes {
+            if let CorpusOutcome::Failed { kind, .. } = outcome {
+                *failures.entry(kind.as_str()).or_default() += 1;
+            }
+        }
+        failures
+    }
+
+    /// Find the tags that this report reads differently from the baseline
+    ///
+    /// Changes come in this report's order, followed by tags only the baseline has.
+    /// No changes means every tag parses identically.
+    pub fn compare(&self, baseline: &CorpusReport) -> Vec<CorpusChange> {
+        let find = |report: &CorpusReport, tag: &str| {
+            report
+                .outcomes
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map(|(_, outcome)| outcome.clone())
+        };
+        let mut changes = vec![];
+        for (tag, outcome) in &self.outcomes {
+            let before = find(baseline, tag);
+            if before.as_ref() != Some(outcome) {
+                changes.push(CorpusChange {
+                    tag: tag.clone(),
+                    before,
+                    after: Some(outcome.clone()),
+                });
+            }
+        }
+        for (tag, outcome) in &baseline.outcomes {
+            if find(self, tag).is_none() {
+                changes.push(CorpusChange {
+                    tag: tag.clone(),
+                    before: Some(outcome.clone()),
+                    after: None,
+                });
+            }
+        }
+        changes
+    }
+}
+
+impl fmt::Display for CorpusReport {
+    /// Summarize the report, like "3 tags: 2 parsed, 1 failed (1 TagVersionParse)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failed = self.outcomes.len() - self.parsed();
+        write!(
+            f,
+            "{} tags: {} parsed, {failed} failed",
+            self.outcomes.len(),
+            self.parsed()
+        )?;
+        if failed > 0 {
+            let kinds = self
+                .failures_by_kind()
+                .into_iter()
+                .map(|(kind, count)| format!("{count} {kind}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " ({kinds})")?;
+        }
+        Ok(())
+    }
+}
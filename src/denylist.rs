@@ -0,0 +1,60 @@
+//! Keeping away from tags that other automation in the repo owns.
+//!
+//! Unlike [`TagParser::reserved_tags`][] (which parse, just never as a release), tags on
+//! [`TagParser::denied_tags`][] are an error: parsing them fails, and so does producing
+//! them with [`TagParser::try_format_tag`][].
+
+use crate::errors::{TagError, TagResult};
+use crate::{Package, TagParser, Version};
+
+impl TagParser {
+    /// Check that a tag isn't on [`TagParser::denied_tags`][]
+    pub fn check_not_denied(&self, tag: &str) -> TagResult<()> {
+        match self
+            .denied_tags
+            .iter()
+            .find(|pattern| glob_match(pattern, tag))
+        {
+            Some(pattern) => Err(TagError::DeniedTag {
+                tag: tag.to_owned(),
+                pattern: pattern.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Produce a tag like [`TagParser::format_tag`][], refusing to produce one that's on
+    /// [`TagParser::denied_tags`][]
+    pub fn try_format_tag(
+        &self,
+        package: Option<&Package>,
+        version: &Version,
+    ) -> TagResult<String> {
+        let tag = self.format_tag(package, version);
+        self.check_not_denied(&tag)?;
+        Ok(tag)
+    }
+}
+
+/// Match a whole tag against a pattern where `*` matches any run of characters
+///
+/// Git doesn't allow `*` in tags, so it never has to be matched literally.
+fn glob_match(pattern: &str, tag: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = tag.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    // Without a `*` the pattern has to be the whole tag
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
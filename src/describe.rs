@@ -0,0 +1,147 @@
+//! Human-readable explanations of what a tag announces.
+
+use std::fmt;
+
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagFormat, TagParser, Version};
+
+/// A structured explanation of what a parsed tag announces
+///
+/// The [`Display`][fmt::Display] impl renders something like
+/// "announcing package `my-app` at 1.2.3 (prerelease: no), matched format `{package}-v{version}`".
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagDescription {
+    /// What kind of release this is
+    pub scope: DescribedScope,
+    /// The names of the packages being announced, in input order
+    pub packages: Vec<String>,
+    /// The version being announced, if there is one
+    pub version: Option<Version>,
+    /// The channel being announced, if this is a channel tag
+    pub channel: Option<String>,
+    /// Whether this is a prerelease
+    pub prerelease: bool,
+    /// The format the tag matched
+    pub format: Option<TagFormat>,
+    /// How the parser spells tags like this one, like `{package}-v{version}` (see
+    /// [`TagParser::tag_template`][])
+    pub template: Option<String>,
+}
+
+/// What kind of release a [`TagDescription`][] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DescribedScope {
+    /// Nothing is being announced
+    None,
+    /// Nothing is being announced, because the tag is reserved
+    Reserved,
+    /// Every package with a version is being announced
+    Unified,
+    /// A single package is being announced
+    Package,
+}
+
+impl PartialAnnouncementTag {
+    /// Explain what this tag announces, in a structured form
+    ///
+    /// This assumes the tag was parsed with the default settings, see
+    /// [`TagParser::description`][] for tags parsed with others.
+    pub fn description(&self, packages: &[Package]) -> TagDescription {
+        TagParser::default().description(self, packages)
+    }
+
+    /// Explain what this tag announces, for humans
+    ///
+    /// See [`TagDescription`][] for the structured version.
+    pub fn describe(&self, packages: &[Package]) -> String {
+        self.description(packages).to_string()
+    }
+}
+
+impl TagParser {
+    /// Explain what a tag parsed with these settings announces, in a structured form
+    pub fn description(
+        &self,
+        announcing: &PartialAnnouncementTag,
+        packages: &[Package],
+    ) -> TagDescription {
+        let scope = match &announcing.release {
+            AnnouncementKind::None => DescribedScope::None,
+            AnnouncementKind::Reserved { .. } => DescribedScope::Reserved,
+            AnnouncementKind::Unified(_) | AnnouncementKind::Channel { idx: None, .. } => {
+                DescribedScope::Unified
+            }
+            AnnouncementKind::Package { .. }
+            | AnnouncementKind::UnresolvedPackage { .. }
+            | AnnouncementKind::Channel { idx: Some(_), .. } => DescribedScope::Package,
+        };
+        let names = match &announcing.release {
+            AnnouncementKind::UnresolvedPackage { name, .. } => vec![name.clone()],
+            _ => self
+                .selected_packages(announcing, packages)
+                .into_iter()
+                .filter_map(|idx| packages.get(idx))
+                .map(|p| p.name.clone())
+                .collect(),
+        };
+        let channel = match &announcing.release {
+            AnnouncementKind::Channel { channel, .. } => Some(channel.clone()),
+            _ => None,
+        };
+        let template = announcing.format.map(|format| match scope {
+            DescribedScope::Package => self.tag_template(Some(format)),
+            _ => self.tag_template(None),
+        });
+        TagDescription {
+            scope,
+            packages: names,
+            version: announcing.release.version().cloned(),
+            channel,
+            prerelease: announcing.prerelease,
+            format: announcing.format,
+            template,
+        }
+    }
+
+    /// Explain what a tag parsed with these settings announces, for humans
+    ///
+    /// See [`TagParser::description`][] for the structured version.
+    pub fn describe(&self, announcing: &PartialAnnouncementTag, packages: &[Package]) -> String {
+        self.description(announcing, packages).to_string()
+    }
+}
+
+impl fmt::Display for TagDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self
+            .packages
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match self.scope {
+            DescribedScope::None => return write!(f, "announcing nothing"),
+            DescribedScope::Reserved => return write!(f, "announcing nothing (reserved tag)"),
+            DescribedScope::Package => write!(f, "announcing package {names}")?,
+            DescribedScope::Unified => match self.packages.len() {
+                1 => write!(f, "announcing 1 package ({names})")?,
+                n => write!(f, "announcing {n} packages ({names})")?,
+            },
+        }
+        if let Some(version) = &self.version {
+            write!(f, " at {version}")?;
+        }
+        if let Some(channel) = &self.channel {
+            write!(f, " on channel `{channel}`")?;
+        }
+        let prerelease = if self.prerelease { "yes" } else { "no" };
+        write!(f, " (prerelease: {prerelease})")?;
+        if let Some(template) = &self.template {
+            write!(f, ", matched format `{template}`")?;
+        }
+        Ok(())
+    }
+}
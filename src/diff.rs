@@ -0,0 +1,145 @@
+//! Comparing what a tag announces in two different states of a workspace.
+
+use crate::errors::{TagError, TagResult};
+use crate::{Package, PartialAnnouncementTag, TagParser, TagWarning, Version};
+
+/// What changed about a tag's announcement between two states of a workspace
+///
+/// Packages are identified by name, since the same package can have a different
+/// index in each state. Everything is sorted by package name.
+#[derive(Debug)]
+pub struct AnnouncementDiff {
+    /// The result of parsing the tag against the old packages
+    pub before: TagResult<PartialAnnouncementTag>,
+    /// The result of parsing the tag against the new packages
+    pub after: TagResult<PartialAnnouncementTag>,
+    /// Packages that are only announced with the new packages
+    pub added: Vec<String>,
+    /// Packages that are only announced with the old packages
+    pub removed: Vec<String>,
+    /// Version mismatches that only happen with the new packages
+    pub mismatches_introduced: Vec<VersionMismatch>,
+    /// Version mismatches that only happen with the old packages
+    pub mismatches_resolved: Vec<VersionMismatch>,
+}
+
+/// A tag claiming a different version than its package has
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VersionMismatch {
+    /// The package name
+    pub package_name: String,
+    /// The version the tag claimed
+    pub tag_version: Version,
+    /// The version the package actually has
+    pub real_version: Version,
+}
+
+impl AnnouncementDiff {
+    /// Whether the announcement is the same in both states
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.mismatches_introduced.is_empty()
+            && self.mismatches_resolved.is_empty()
+    }
+}
+
+impl TagParser {
+    /// Parse the same tag against two states of a workspace and report what changed
+    pub fn diff_tag(&self, tag: &str, before: &[Package], after: &[Package]) -> AnnouncementDiff {
+        let before_result = self.parse_tag(before, tag);
+        let after_result = self.parse_tag(after, tag);
+
+        let before_names = self.selected_names(&before_result, before);
+        let after_names = self.selected_names(&after_result, after);
+        let before_mismatches = mismatches(&before_result);
+        let after_mismatches = mismatches(&after_result);
+
+        AnnouncementDiff {
+            added: difference(&after_names, &before_names),
+            removed: difference(&before_names, &after_names),
+            mismatches_introduced: difference(&after_mismatches, &before_mismatches),
+            mismatches_resolved: difference(&before_mismatches, &after_mismatches),
+            before: before_result,
+            after: after_result,
+        }
+    }
+}
+
+impl TagParser {
+    /// Get the sorted names of the packages an announcement selects
+    fn selected_names(
+        &self,
+        result: &TagResult<PartialAnnouncementTag>,
+        packages: &[Package],
+    ) -> Vec<String> {
+        let Ok(announcing) = result else {
+            return vec![];
+        };
+        let mut names = self
+            .selected_packages(announcing, packages)
+            .into_iter()
+            .filter_map(|idx| packages.get(idx))
+            .map(|p| p.name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// Get the sorted version mismatches a parse ran into, fatal or not
+fn mismatches(result: &TagResult<PartialAnnouncementTag>) -> Vec<VersionMismatch> {
+    let mut mismatches = match result {
+        Err(
+            TagError::ContradictoryTagVersion {
+                package_name,
+                tag_version,
+                real_version,
+                ..
+            }
+            | TagError::StaleTagVersion {
+                package_name,
+                tag_version,
+                real_version,
+                ..
+            }
+            | TagError::TagVersionAhead {
+                package_name,
+                tag_version,
+                real_version,
+                ..
+            },
+        ) => vec![VersionMismatch {
+            package_name: package_name.clone(),
+            tag_version: tag_version.clone(),
+            real_version: real_version.clone(),
+        }],
+        Err(_) => vec![],
+        Ok(announcing) => announcing
+            .warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                TagWarning::ContradictoryTagVersion {
+                    package_name,
+                    tag_version,
+                    real_version,
+                } => Some(VersionMismatch {
+                    package_name: package_name.clone(),
+                    tag_version: tag_version.clone(),
+                    real_version: real_version.clone(),
+                }),
+                _ => None,
+            })
+            .collect(),
+    };
+    mismatches.sort();
+    mismatches
+}
+
+/// Get the items of `a` that aren't in `b`
+fn difference<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().filter(|x| !b.contains(x)).cloned().collect()
+}
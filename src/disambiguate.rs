@@ -0,0 +1,142 @@
+//! Structured choices for tags that could reasonably mean more than one thing.
+//!
+//! Interactive tools can present a [`Disambiguation`][] as a picker, while the decision
+//! of what the candidates are (and which one we'd pick) stays here.
+
+use crate::describe::{DescribedScope, TagDescription};
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, ScopePreference, TagParser};
+
+/// The plausible readings of an ambiguous tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Disambiguation {
+    /// The tag
+    pub tag: String,
+    /// Every plausible reading, with the most specific (package-scoped) ones first
+    pub candidates: Vec<Candidate>,
+    /// The index of the candidate [`TagParser::parse_tag`][] would pick
+    ///
+    /// If parsing would refuse to pick (e.g. [`ScopePreference::Error`][]), this is the
+    /// first candidate.
+    pub recommended: usize,
+}
+
+/// One plausible reading of an ambiguous tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Candidate {
+    /// What the tag announces under this reading
+    pub announcement: PartialAnnouncementTag,
+    /// A short label for a picker, like "just `my-app`" or "every package at 1.2.3"
+    pub label: String,
+    /// The full explanation of this reading
+    pub description: TagDescription,
+}
+
+impl Disambiguation {
+    /// Get the recommended candidate
+    pub fn recommended(&self) -> &Candidate {
+        &self.candidates[self.recommended]
+    }
+}
+
+impl TagParser {
+    /// Find every plausible reading of a tag, if there's more than one
+    ///
+    /// This covers tags that could be package-scoped or unified (see
+    /// [`TagParser::scope_preference`][]), packages with version-like names (see
+    /// [`TagParser::require_unambiguous_package_names`][]), and typos of several packages
+    /// (see [`TagParser::typo_tolerance`][]). Returns None if the tag only has one reading
+    /// (or none at all).
+    pub fn disambiguate(&self, packages: &[Package], tag: &str) -> Option<Disambiguation> {
+        let package_reading = TagParser {
+            scope_preference: ScopePreference::Package,
+            require_unambiguous_package_names: false,
+            typo_tolerance: false,
+            ..self.clone()
+        };
+        let unified_reading = TagParser {
+            scope_preference: ScopePreference::Unified,
+            ..package_reading.clone()
+        };
+
+        let by_package = package_reading.parse_tag(packages, tag);
+        let version_like_name = match &by_package {
+            Ok(PartialAnnouncementTag {
+                release: AnnouncementKind::Package { idx, .. },
+                ..
+            }) => packages[*idx].has_version_like_name(),
+            _ => false,
+        };
+        let mut readings = vec![by_package, unified_reading.parse_tag(packages, tag)];
+        if version_like_name {
+            // Without any packages, the tag can only be unified (`v1/v1.0.0` with a prefix)
+            readings.push(unified_reading.parse_tag(&[], tag));
+        }
+
+        if self.typo_tolerance && readings.iter().all(|r| r.is_err()) {
+            // Every package the tag could have been a typo of
+            for idx in self.did_you_mean(packages, tag) {
+                readings.extend(package_reading.parse_corrected(packages, tag, idx, None));
+            }
+        }
+
+        let mut announcements: Vec<PartialAnnouncementTag> = vec![];
+        for announcing in readings.into_iter().flatten() {
+            if !announcements
+                .iter()
+                .any(|a| a.release == announcing.release)
+            {
+                announcements.push(announcing);
+            }
+        }
+        if announcements.len() < 2 {
+            return None;
+        }
+
+        let recommended = self
+            .parse_tag(packages, tag)
+            .ok()
+            .and_then(|chosen| {
+                announcements
+                    .iter()
+                    .position(|a| a.release == chosen.release)
+            })
+            .unwrap_or(0);
+        let candidates = announcements
+            .into_iter()
+            .map(|announcement| {
+                let description = self.description(&announcement, packages);
+                Candidate {
+                    label: candidate_label(&description),
+                    description,
+                    announcement,
+                }
+            })
+            .collect();
+        Some(Disambiguation {
+            tag: tag.to_owned(),
+            candidates,
+            recommended,
+        })
+    }
+}
+
+/// Get a short label for a reading of a tag
+fn candidate_label(description: &TagDescription) -> String {
+    let version = description
+        .version
+        .as_ref()
+        .map(|v| format!(" at {v}"))
+        .unwrap_or_default();
+    match description.scope {
+        DescribedScope::Package => match description.packages.first() {
+            Some(name) => format!("just `{name}`{version}"),
+            None => format!("a single package{version}"),
+        },
+        DescribedScope::Unified => format!("every package{version}"),
+        DescribedScope::None | DescribedScope::Reserved => "nothing".to_owned(),
+    }
+}
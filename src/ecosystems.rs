@@ -0,0 +1,230 @@
+//! Helpers for turning announced versions into what other package ecosystems expect.
+
+use thiserror::Error;
+
+use crate::errors::{TagError, TagResult};
+use crate::{PartialAnnouncementTag, Version};
+
+/// The longest tag a container registry accepts
+pub const MAX_DOCKER_TAG_LEN: usize = 128;
+
+/// Which distribution channel a version should be published to
+///
+/// Package managers have their own names for "this is a prerelease", this centralizes
+/// the mapping so everything downstream of a tag agrees.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DistChannel {
+    /// A normal release
+    Stable,
+    /// A prerelease
+    Prerelease {
+        /// A label for the kind of prerelease, taken from the first prerelease identifier
+        /// (`beta` for `1.0.0-beta.2`), or `prerelease` if that isn't alphabetic
+        label: String,
+    },
+}
+
+impl DistChannel {
+    /// Get the channel for the given version
+    pub fn for_version(version: &Version) -> DistChannel {
+        if version.pre.is_empty() {
+            return DistChannel::Stable;
+        }
+        let first = version.pre.as_str().split('.').next().unwrap_or_default();
+        let label = first
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let label = if label.is_empty() {
+            "prerelease".to_owned()
+        } else {
+            label
+        };
+        DistChannel::Prerelease { label }
+    }
+
+    /// Whether this is a prerelease channel
+    pub fn is_prerelease(&self) -> bool {
+        matches!(self, DistChannel::Prerelease { .. })
+    }
+
+    /// Get the npm dist-tag to publish with (`latest`, or the prerelease label like `beta`)
+    pub fn npm_dist_tag(&self) -> &str {
+        match self {
+            DistChannel::Stable => "latest",
+            DistChannel::Prerelease { label } => label,
+        }
+    }
+
+    /// Get the flag to pass to Homebrew tooling for this channel, if any (`--pre` for prereleases)
+    pub fn homebrew_flag(&self) -> Option<&'static str> {
+        match self {
+            DistChannel::Stable => None,
+            DistChannel::Prerelease { .. } => Some("--pre"),
+        }
+    }
+}
+
+impl PartialAnnouncementTag {
+    /// Get the distribution channel for the announced version, if there is one
+    pub fn dist_channel(&self) -> Option<DistChannel> {
+        self.release.version().map(DistChannel::for_version)
+    }
+}
+
+/// Render a version the way Homebrew formulae and casks expect it
+///
+/// This is lossy:
+/// * build metadata is dropped (`1.2.3+build.4` => `1.2.3`), Homebrew rejects `+`
+/// * everything else is kept as-is (`1.2.3-rc.1` => `1.2.3-rc.1`)
+pub fn to_homebrew_version(version: &Version) -> String {
+    let mut output = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        output.push('-');
+        output.push_str(version.pre.as_str());
+    }
+    output
+}
+
+/// Render a version as an MSI ProductVersion (`major.minor.build`)
+///
+/// MSI only allows up to 255 for the major and minor components and up to 65535
+/// for the build component, anything bigger is an error rather than silently
+/// wrapping. This is also lossy:
+/// * prereleases are dropped (`1.2.3-rc.1` => `1.2.3`), so a prerelease and its
+///   final release get the same ProductVersion
+/// * build metadata is dropped (`1.2.3+build.4` => `1.2.3`)
+pub fn to_msi_version(version: &Version) -> TagResult<String> {
+    let components = [
+        ("major", version.major, 255),
+        ("minor", version.minor, 255),
+        ("patch", version.patch, 65535),
+    ];
+    for (component, value, max) in components {
+        if value > max {
+            return Err(TagError::MsiVersionOverflow {
+                version: version.clone(),
+                component,
+                max,
+            });
+        }
+    }
+    Ok(format!(
+        "{}.{}.{}",
+        version.major, version.minor, version.patch
+    ))
+}
+
+/// Render a version as a Debian upstream version
+///
+/// Prereleases are joined with `~` so that they sort before the final release
+/// (`1.2.3-rc.1` => `1.2.3~rc.1`), and build metadata is kept after a `+`
+/// (`1.2.3+build.4` => `1.2.3+build.4`). Debian only allows `-` in upstream versions
+/// that have a revision, so any `-` inside the prerelease becomes a `.`.
+pub fn to_deb_version(version: &Version) -> String {
+    let mut output = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        output.push('~');
+        output.push_str(&version.pre.as_str().replace('-', "."));
+    }
+    if !version.build.is_empty() {
+        output.push('+');
+        output.push_str(&version.build.as_str().replace('-', "."));
+    }
+    output
+}
+
+/// Render a version as an RPM Version
+///
+/// Prereleases are joined with `~` so that they sort before the final release
+/// (`1.2.3-rc.1` => `1.2.3~rc.1`), which needs RPM 4.10 or newer. RPM doesn't allow
+/// `-` in versions, so any `-` inside the prerelease becomes a `.`. Build metadata
+/// doesn't affect semver ordering, and RPM has nothing equivalent, so it's dropped
+/// (`1.2.3+build.4` => `1.2.3`).
+pub fn to_rpm_version(version: &Version) -> String {
+    let mut output = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        output.push('~');
+        output.push_str(&version.pre.as_str().replace('-', "."));
+    }
+    output
+}
+
+/// Render a version as a container image tag (for Docker and other OCI registries)
+///
+/// Image tags can't contain `+`, so build metadata is joined with `_` instead
+/// (`1.2.3+build.4` => `1.2.3_build.4`). Semver never uses `_`, so
+/// [`from_docker_tag`][] can undo this. Everything else is kept as-is
+/// (`1.2.3-rc.1` => `1.2.3-rc.1`). Versions that still don't make a valid image tag
+/// (see [`check_docker_tag`][]) are an error, so a bad push fails here instead of at the
+/// registry.
+pub fn to_docker_tag(version: &Version) -> TagResult<String> {
+    let tag = version.to_string().replace('+', "_");
+    check_docker_tag(&tag)?;
+    Ok(tag)
+}
+
+/// Get the version back from a container image tag made by [`to_docker_tag`][]
+///
+/// Returns None if it isn't one.
+pub fn from_docker_tag(tag: &str) -> Option<Version> {
+    Version::parse(&tag.replacen('_', "+", 1)).ok()
+}
+
+/// Check that a container image tag is valid
+///
+/// Image tags are 1 to [`MAX_DOCKER_TAG_LEN`][] ASCII letters, digits, `_`, `.`, and `-`,
+/// and can't start with `.` or `-`.
+pub fn check_docker_tag(tag: &str) -> TagResult<()> {
+    let problem = if tag.is_empty() {
+        Some(DockerTagProblem::Empty)
+    } else if let Some(found) = tag.chars().find(|c| !is_docker_tag_char(*c)) {
+        Some(DockerTagProblem::InvalidChar { found })
+    } else if let Some(found) = tag.chars().next().filter(|c| matches!(c, '.' | '-')) {
+        Some(DockerTagProblem::InvalidStart { found })
+    } else if tag.len() > MAX_DOCKER_TAG_LEN {
+        Some(DockerTagProblem::TooLong { len: tag.len() })
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(TagError::InvalidDockerTag {
+            tag: tag.to_owned(),
+            problem,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_docker_tag_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// Why a string isn't a valid container image tag, see [`check_docker_tag`][]
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum DockerTagProblem {
+    /// There's nothing there
+    #[error("it's empty")]
+    Empty,
+    /// It's longer than [`MAX_DOCKER_TAG_LEN`][]
+    #[error("it's {len} characters long, the limit is {MAX_DOCKER_TAG_LEN}")]
+    TooLong {
+        /// How long it is
+        len: usize,
+    },
+    /// It starts with `.` or `-`
+    #[error("it starts with {found:?}")]
+    InvalidStart {
+        /// The first character
+        found: char,
+    },
+    /// It has a character image tags don't allow
+    #[error("it contains {found:?}")]
+    InvalidChar {
+        /// The first such character
+        found: char,
+    },
+}
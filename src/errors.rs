@@ -3,14 +3,23 @@
 use miette::Diagnostic;
 use thiserror::Error;
 
+use crate::TagFormat;
+
 /// An alias for the Result type for this crate
 pub type TagResult<T> = std::result::Result<T, TagError>;
 
 /// Errors axotag can have
+///
+/// Besides the human-readable message, some errors know how to fix the tag
+/// (see [`TagError::fix_its`][]).
 #[derive(Debug, Error, Diagnostic)]
 pub enum TagError {
     /// parse_tag concluded that versions didn't line up
     #[error("The provided announcement tag ({tag}) claims we're releasing {package_name} {tag_version}, but that package is version {real_version}")]
+    #[diagnostic(
+        code(AXOTAG_0001),
+        help("tag {package_name} {real_version} instead, or bump {package_name} to {tag_version}")
+    )]
     ContradictoryTagVersion {
         /// The full tag
         tag: String,
@@ -22,8 +31,46 @@ pub enum TagError {
         real_version: semver::Version,
     },
 
+    /// parse_tag found a tag older than its package (see [`TagParser::directional_version_mismatch`][crate::TagParser::directional_version_mismatch])
+    #[error("The provided announcement tag ({tag}) claims we're releasing {package_name} {tag_version}, but that package is already at {real_version}")]
+    #[diagnostic(
+        code(AXOTAG_0002),
+        help("tag {real_version} instead, or check out the commit that was {tag_version}")
+    )]
+    StaleTagVersion {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+        /// The version the tag claimed
+        tag_version: semver::Version,
+        /// The version the package actually has
+        real_version: semver::Version,
+    },
+
+    /// parse_tag found a tag newer than its package (see [`TagParser::directional_version_mismatch`][crate::TagParser::directional_version_mismatch])
+    #[error("The provided announcement tag ({tag}) claims we're releasing {package_name} {tag_version}, but that package is only at {real_version}")]
+    #[diagnostic(
+        code(AXOTAG_0003),
+        help("bump {package_name} to {tag_version} before tagging")
+    )]
+    TagVersionAhead {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+        /// The version the tag claimed
+        tag_version: semver::Version,
+        /// The version the package actually has
+        real_version: semver::Version,
+    },
+
     /// parse_tag couldn't parse the version component at all
     #[error("Couldn't parse the version from the provided announcement tag ({tag})")]
+    #[diagnostic(
+        code(AXOTAG_0004),
+        help("versions look like 1.2.3, optionally with a prerelease like 1.2.3-rc.1")
+    )]
     TagVersionParse {
         /// the full tag
         tag: String,
@@ -32,10 +79,767 @@ pub enum TagError {
         details: semver::Error,
     },
 
+    /// parse_tag couldn't parse the version component of a `VersionScheme::Pep440` package
+    #[cfg(feature = "pep440")]
+    #[error("Couldn't parse the PEP 440 version from the provided announcement tag ({tag})")]
+    #[diagnostic(
+        code(AXOTAG_0005),
+        help("PEP 440 versions look like 1.2.3, 1.2.3rc1, or 1.2.3.post1")
+    )]
+    Pep440Parse {
+        /// the full tag
+        tag: String,
+        /// parse error
+        #[source]
+        details: crate::pep440::Pep440Error,
+    },
+
+    /// parse_tag wanted a `v` prefix on the version but didn't find one
+    #[error(
+        "The provided announcement tag ({tag}) is missing the required `v` prefix on its version"
+    )]
+    #[diagnostic(
+        code(AXOTAG_0006),
+        help("put a `v` right before the version, like v1.2.3")
+    )]
+    MissingVPrefix {
+        /// The full tag
+        tag: String,
+        /// Where the version starts in the tag
+        version_start: usize,
+    },
+
+    /// parse_tag found a `v` prefix on the version that wasn't allowed
+    #[error("The provided announcement tag ({tag}) has a `v` prefix on its version, which isn't allowed")]
+    #[diagnostic(code(AXOTAG_0007), help("drop the `v` before the version, like 1.2.3"))]
+    ForbiddenVPrefix {
+        /// The full tag
+        tag: String,
+        /// Where the version (and so its `v` prefix) starts in the tag
+        version_start: usize,
+    },
+
+    /// parse_tag found a tag in a format we understand but no longer accept
+    #[error("The provided announcement tag ({tag}) uses the legacy {format:?} format")]
+    #[diagnostic(code(AXOTAG_0008), help("use {canonical} instead"))]
+    LegacyTagFormat {
+        /// The full tag
+        tag: String,
+        /// The legacy format it matched
+        format: TagFormat,
+        /// The tag spelled in the preferred format
+        canonical: String,
+    },
+
+    /// parse_tag found a prefix that isn't one of TagParser::allowed_prefixes
+    #[error("The provided announcement tag ({tag}) has the prefix {prefix}/, which isn't allowed (allowed: {})", list_prefixes(allowed))]
+    #[diagnostic(code(AXOTAG_0009), help("remove the prefix, or use an allowed one"))]
+    UnexpectedPrefix {
+        /// The full tag
+        tag: String,
+        /// The prefix, without its trailing slash
+        prefix: String,
+        /// The prefixes that are allowed
+        allowed: Vec<String>,
+    },
+
+    /// parse_tag found a unified release in a workspace that requires tags to name a package
+    #[error("The provided announcement tag ({tag}) would announce every package, but tags in this workspace have to name a package")]
+    #[diagnostic(code(AXOTAG_0010), help("pick the package to release, like {example}"))]
+    MissingPackageComponent {
+        /// The full tag
+        tag: String,
+        /// The tag spelled for the first releasable package
+        example: String,
+    },
+
+    /// parse_tag found a package with a version-like name, but the tag could also be a unified release
+    #[error("The provided announcement tag ({tag}) could be a release of {package_name} or a unified release")]
+    #[diagnostic(
+        code(AXOTAG_0011),
+        help("the package name {package_name} looks like a version, consider renaming it")
+    )]
+    AmbiguousPackageName {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+    },
+
+    /// parse_tag found a package-scoped tag that could also be a unified release
+    #[error("The provided announcement tag ({tag}) could be a release of just {package_name}, or of every package at {version}")]
+    #[diagnostic(
+        code(AXOTAG_0012),
+        help("use a unified tag like v{version} to release everything")
+    )]
+    AmbiguousScope {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+        /// The version every package has
+        version: semver::Version,
+    },
+
+    /// suggest_next_tag couldn't find any version to bump from
+    #[error("Couldn't find a version to bump for {}", package_name.as_deref().unwrap_or("a unified release"))]
+    #[diagnostic(
+        code(AXOTAG_0013),
+        help("give the package a version, or pass in its existing tags")
+    )]
+    NoVersionToBump {
+        /// The package name, if this wasn't for a unified release
+        package_name: Option<String>,
+    },
+
+    /// to_msi_version got a version with a component too big for MSI
+    #[error("The version {version} can't be used for an MSI, its {component} component is bigger than {max}")]
+    #[diagnostic(
+        code(AXOTAG_0014),
+        help("pick a version whose {component} component is at most {max}")
+    )]
+    MsiVersionOverflow {
+        /// The version
+        version: semver::Version,
+        /// Which component was too big
+        component: &'static str,
+        /// The biggest that component can be
+        max: u64,
+    },
+
+    /// parse_tag found a unified release that doesn't match the packages' versions
+    #[error("The provided announcement tag ({tag}) announces {version}, but {} ({})",
+        if *require_all { "not every package has that version" } else { "no package has that version" },
+        list_versions(packages),
+    )]
+    #[diagnostic(
+        code(AXOTAG_0015),
+        help("tag the version the packages have, or bump them to {version} first")
+    )]
+    UnifiedVersionMismatch {
+        /// The full tag
+        tag: String,
+        /// The version the tag announces
+        version: semver::Version,
+        /// Whether every package needed to have the version (instead of just one)
+        require_all: bool,
+        /// The version of each package, in input order
+        packages: Vec<PackageVersion>,
+    },
+
+    /// parse_tag found a tag for a package that doesn't have a version
+    #[error(
+        "The provided announcement tag ({tag}) names {package_name}, which doesn't have a version"
+    )]
+    #[diagnostic(
+        code(AXOTAG_0016),
+        help("give {package_name} a version, or allow tags to set it")
+    )]
+    UnversionedPackage {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+    },
+
+    /// parse_tag found a tag for a package that's excluded from announcements
+    #[error("The provided announcement tag ({tag}) names {package_name}, which is excluded from announcements")]
+    #[diagnostic(
+        code(AXOTAG_0017),
+        help("release a different package, or stop excluding {package_name}")
+    )]
+    PackageNotReleasable {
+        /// The full tag
+        tag: String,
+        /// The package name
+        package_name: String,
+    },
+
+    /// parse_tag found a placeholder version (`0.0.0`) it was told not to accept
+    #[error("The provided announcement tag ({tag}) {}", match package_name {
+        Some(package_name) => format!("names {package_name}, which has a placeholder version"),
+        None => "announces a placeholder version".to_owned(),
+    })]
+    #[diagnostic(
+        code(AXOTAG_0018),
+        help("give the package a real version before releasing it")
+    )]
+    PlaceholderVersion {
+        /// The full tag
+        tag: String,
+        /// The package with the placeholder version, if it wasn't the tag's own version
+        package_name: Option<String>,
+    },
+
+    /// parse_tag found a version lower than a configured floor
+    #[error("The provided announcement tag ({tag}) announces {version}, but {} can't go below {floor}", package_name.as_deref().unwrap_or("unified releases"))]
+    #[diagnostic(
+        code(AXOTAG_0019),
+        help("release {floor} or newer, or lower the floor in TagParser::version_bounds")
+    )]
+    VersionBelowFloor {
+        /// The full tag
+        tag: String,
+        /// The package the floor is for, or None for unified releases
+        package_name: Option<String>,
+        /// The version the tag announces
+        version: semver::Version,
+        /// The floor
+        floor: semver::Version,
+    },
+
+    /// parse_tag found a version at or above a configured ceiling
+    #[error("The provided announcement tag ({tag}) announces {version}, but {} has to stay below {ceiling}", package_name.as_deref().unwrap_or("unified releases"))]
+    #[diagnostic(code(AXOTAG_0020), help("release something older than {ceiling}, or raise the ceiling in TagParser::version_bounds"))]
+    VersionAboveCeiling {
+        /// The full tag
+        tag: String,
+        /// The package the ceiling is for, or None for unified releases
+        package_name: Option<String>,
+        /// The version the tag announces
+        version: semver::Version,
+        /// The ceiling
+        ceiling: semver::Version,
+    },
+
+    /// check_not_regression found an existing tag with the same or a newer version
+    #[error("The provided announcement tag ({tag}) announces {version}, but {existing_tag} already announced {existing_version}")]
+    #[diagnostic(code(AXOTAG_0021), help("did you mean to bump the version?"))]
+    VersionRegression {
+        /// The full tag
+        tag: String,
+        /// The version the tag announces
+        version: semver::Version,
+        /// The existing tag with the same or a newer version
+        existing_tag: String,
+        /// The version of the existing tag
+        existing_version: semver::Version,
+    },
+
+    /// check_prerelease_first found no prerelease leading up to a final release
+    #[error("The provided announcement tag ({tag}) releases {version}, but none of the existing tags are a prerelease of it")]
+    #[diagnostic(code(AXOTAG_0022), help("tag a prerelease like {version}-rc.1 first"))]
+    MissingPrerelease {
+        /// The full tag
+        tag: String,
+        /// The version the tag announces
+        version: semver::Version,
+    },
+
+    /// TagPattern::new got a pattern it couldn't use
+    #[cfg(feature = "regex")]
+    #[error("The tag pattern {pattern} is invalid: {reason}")]
+    #[diagnostic(
+        code(AXOTAG_0023),
+        help("patterns are regexes with a `version` group, and optionally a `package` one")
+    )]
+    InvalidTagPattern {
+        /// The pattern
+        pattern: String,
+        /// What was wrong with it
+        reason: String,
+    },
+
+    /// parse_tag_input was given a URL that isn't a release or tag page we recognize
+    #[error("The provided URL ({url}) isn't a GitHub or GitLab release or tag URL")]
+    #[diagnostic(code(AXOTAG_0024), help("pass the tag itself instead"))]
+    UnrecognizedTagUrl {
+        /// The URL
+        url: String,
+    },
+
+    /// parse_tag was given no packages, and EmptyWorkspacePolicy::Error says that's wrong
+    #[error(
+        "The provided announcement tag ({tag}) can't be checked, because there are no packages"
+    )]
+    #[diagnostic(
+        code(AXOTAG_0025),
+        help("make sure the workspace's packages are passed along")
+    )]
+    NoPackages {
+        /// The full tag
+        tag: String,
+    },
+
+    /// parse_tag was given a tag that git wouldn't allow
+    #[error("The provided announcement tag ({tag:?}) isn't a valid git tag: {reason} (at byte {position})")]
+    #[diagnostic(
+        code(AXOTAG_0026),
+        help(
+            "git tags can't contain spaces, control characters, `..`, `@{{`, or any of `~^:?*[\\`"
+        )
+    )]
+    InvalidGitTag {
+        /// The full tag
+        tag: String,
+        /// Where in the tag the problem is, in bytes
+        position: usize,
+        /// What's wrong with it
+        reason: String,
+    },
+
+    /// infer_tag couldn't find a version to announce
+    #[error("Couldn't infer a tag for {}, it has no version", package_name.as_deref().unwrap_or("a unified release"))]
+    #[diagnostic(code(AXOTAG_0027), help("give the package a version, or pass a tag"))]
+    NoVersionToInfer {
+        /// The package name, if this wasn't for a unified release
+        package_name: Option<String>,
+    },
+
+    /// infer_tag was asked for a unified release, but the packages have different versions
+    #[error(
+        "Couldn't infer a unified tag, because the packages have different versions ({report})"
+    )]
+    #[diagnostic(code(AXOTAG_0028), help(
+        "tag the packages separately instead: {}",
+        report.suggested_tags().collect::<Vec<_>>().join(", ")
+    ))]
+    HeterogeneousVersions {
+        /// The releasable packages, grouped by version
+        report: crate::infer::VersionReport,
+    },
+
+    /// graduate_tag was given a tag that isn't a prerelease
+    #[error("The provided announcement tag ({tag}) isn't a prerelease, so it can't graduate")]
+    #[diagnostic(
+        code(AXOTAG_0030),
+        help("only prereleases (like v1.2.3-rc.1) can graduate")
+    )]
+    NotAPrerelease {
+        /// The full tag
+        tag: String,
+    },
+
+    /// graduate_tag found that the prerelease already graduated
+    #[error(
+        "The prerelease {tag} can't graduate to {version}, {existing_tag} already released it"
+    )]
+    #[diagnostic(
+        code(AXOTAG_0031),
+        help("{existing_tag} is already out, start a prerelease of the next version instead")
+    )]
+    AlreadyReleased {
+        /// The prerelease tag
+        tag: String,
+        /// The final version it leads up to
+        version: semver::Version,
+        /// The existing tag that released that version
+        existing_tag: String,
+    },
+
+    /// parse_tag found a tag on a maintenance branch for a version the branch doesn't maintain
+    #[error("The provided announcement tag ({tag}) announces {version}, which isn't in the series of its branch ({branch})")]
+    #[diagnostic(
+        code(AXOTAG_0032),
+        help("tag {version} on the branch that maintains its series, or without the branch")
+    )]
+    OutsideBranchSeries {
+        /// The full tag
+        tag: String,
+        /// The branch the tag's prefix named
+        branch: String,
+        /// The version the tag announces
+        version: semver::Version,
+    },
+
+    /// parse_tag found a tag that several packages' names could read
+    #[error("The provided announcement tag ({tag}) could be a release of {package_name} or of {other_package_name}")]
+    #[diagnostic(
+        code(AXOTAG_0033),
+        help("rename one of the packages so neither name starts with the other")
+    )]
+    OverlappingPackageNames {
+        /// The full tag
+        tag: String,
+        /// The package with the longest name, which would normally win
+        package_name: String,
+        /// The other package
+        other_package_name: String,
+    },
+
+    /// A version or tag can't be used as a container image tag
+    #[error("{tag} can't be used as a container image tag, {problem}")]
+    #[diagnostic(
+        code(AXOTAG_0034),
+        help("image tags can only use ASCII letters, digits, `_`, `.` and `-`")
+    )]
+    InvalidDockerTag {
+        /// The would-be image tag
+        tag: String,
+        /// What's wrong with it
+        problem: crate::ecosystems::DockerTagProblem,
+    },
+
+    /// parse_tag found a segment that could be several kinds of thing
+    #[error(
+        "The provided announcement tag ({tag}) is ambiguous, {segment} could be {}",
+        list_kinds(kinds)
+    )]
+    #[diagnostic(
+        code(AXOTAG_0035),
+        help("rename whatever shouldn't be called {segment}, or set TagParser::segment_order to pick one")
+    )]
+    AmbiguousSegment {
+        /// The full tag
+        tag: String,
+        /// The segment (everything before the slash it ends at)
+        segment: String,
+        /// What it could be
+        kinds: Vec<crate::SegmentKind>,
+    },
+
+    /// bump_between got tags that aren't releases of the same package
+    #[error("Can't compare {old_tag} and {new_tag}, they aren't releases of the same package")]
+    #[diagnostic(
+        code(AXOTAG_0036),
+        help("both tags need a version, and to be for the same package (or both unified)")
+    )]
+    NotSameSeries {
+        /// The older tag
+        old_tag: String,
+        /// The newer tag
+        new_tag: String,
+    },
+
+    /// parse_tag (or try_format_tag) got a tag that's on our denylist
+    #[error("The tag {tag} belongs to other automation (it matches {pattern})")]
+    #[diagnostic(
+        code(AXOTAG_0037),
+        help("pick a different tag, or take {pattern} off TagParser::denied_tags")
+    )]
+    DeniedTag {
+        /// The full tag
+        tag: String,
+        /// The entry of TagParser::denied_tags it matched
+        pattern: String,
+    },
+
+    /// check_tag_metadata found a tag that isn't signed like TagParser::signatures requires
+    #[error("The tag {tag} {status}, but release tags need {}", required_signature(*policy))]
+    #[diagnostic(
+        code(AXOTAG_0038),
+        help("recreate the tag with `git tag -s`, and check that its signing key is trusted")
+    )]
+    SignatureRequired {
+        /// The full tag
+        tag: String,
+        /// The tag's signature
+        status: crate::metadata::SignatureStatus,
+        /// The policy it didn't satisfy
+        policy: crate::metadata::SignaturePolicy,
+    },
+
+    /// next_version was asked for a prerelease label semver doesn't allow
+    #[error("Can't make a prerelease labelled {label:?}: {reason}")]
+    #[diagnostic(
+        code(AXOTAG_0039),
+        help("prerelease labels can only have ASCII letters, digits, hyphens, and dots")
+    )]
+    InvalidPrereleaseLabel {
+        /// The label
+        label: String,
+        /// What semver didn't like about it
+        reason: String,
+    },
+
+    /// next_version found a prerelease counter that's already as big as it gets
+    #[error("Can't bump {version}, its prerelease counter is already as big as it can be")]
+    #[diagnostic(
+        code(AXOTAG_0040),
+        help("start a prerelease with a different label, or bump the version")
+    )]
+    PrereleaseCounterOverflow {
+        /// The version
+        version: semver::Version,
+    },
+
+    /// next_version found a version component that's already as big as it gets
+    #[error("Can't bump {version}, its {component} component is already as big as it can be")]
+    #[diagnostic(
+        code(AXOTAG_0041),
+        help("bump a bigger component instead, or start over with a new version")
+    )]
+    VersionOverflow {
+        /// The version
+        version: semver::Version,
+        /// Which component couldn't go any higher
+        component: &'static str,
+    },
+
     /// parse_tag couldn't make sense of the --tag provided
+    #[deprecated = "nothing produces this, failed parses report what was wrong with the tag"]
     #[error("The provided announcement tag ({tag}) didn't match any Package or Version")]
+    #[diagnostic(
+        code(AXOTAG_0029),
+        help("tags look like my-app-v1.2.3, or v1.2.3 to release every package")
+    )]
     NoTagMatch {
         /// The --tag
         tag: String,
     },
 }
+
+/// A package and its version, for errors that need to list them
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PackageVersion {
+    /// The package name
+    pub package_name: String,
+    /// The package's version, if it has one
+    pub version: Option<semver::Version>,
+}
+
+/// List packages and their versions, like `my-app 1.2.3, other (no version)`
+fn list_versions(packages: &[PackageVersion]) -> String {
+    packages
+        .iter()
+        .map(|p| match &p.version {
+            Some(version) => format!("{} {version}", p.package_name),
+            None => format!("{} (no version)", p.package_name),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// List the kinds of thing a segment could be, like `a package or an allowed prefix`
+fn list_kinds(kinds: &[crate::SegmentKind]) -> String {
+    kinds
+        .iter()
+        .map(|kind| kind.to_string())
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Describe the signature a policy requires, like `a verified signature`
+fn required_signature(policy: crate::metadata::SignaturePolicy) -> &'static str {
+    use crate::metadata::SignaturePolicy;
+    match policy {
+        SignaturePolicy::Any => "no signature",
+        SignaturePolicy::Signed => "a signature",
+        SignaturePolicy::Verified => "a verified signature",
+    }
+}
+
+/// List prefixes, like `releases/, nightly/`
+fn list_prefixes(prefixes: &[String]) -> String {
+    if prefixes.is_empty() {
+        return "none".to_owned();
+    }
+    prefixes
+        .iter()
+        .map(|prefix| format!("{}/", prefix.trim_end_matches('/')))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The kind of a [`TagError`][], without any of its details
+///
+/// Useful for matching on (or counting) errors without caring about their payloads.
+/// Each kind also has a stable code (see [`TagErrorKind::code`][]) for matching on in
+/// places that don't speak Rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum TagErrorKind {
+    /// [`TagError::ContradictoryTagVersion`][] (`AXOTAG_0001`)
+    ContradictoryTagVersion,
+    /// [`TagError::StaleTagVersion`][] (`AXOTAG_0002`)
+    StaleTagVersion,
+    /// [`TagError::TagVersionAhead`][] (`AXOTAG_0003`)
+    TagVersionAhead,
+    /// [`TagError::TagVersionParse`][] (`AXOTAG_0004`)
+    TagVersionParse,
+    /// [`TagError::Pep440Parse`][] (`AXOTAG_0005`)
+    #[cfg(feature = "pep440")]
+    Pep440Parse,
+    /// [`TagError::MissingVPrefix`][] (`AXOTAG_0006`)
+    MissingVPrefix,
+    /// [`TagError::ForbiddenVPrefix`][] (`AXOTAG_0007`)
+    ForbiddenVPrefix,
+    /// [`TagError::LegacyTagFormat`][] (`AXOTAG_0008`)
+    LegacyTagFormat,
+    /// [`TagError::UnexpectedPrefix`][] (`AXOTAG_0009`)
+    UnexpectedPrefix,
+    /// [`TagError::MissingPackageComponent`][] (`AXOTAG_0010`)
+    MissingPackageComponent,
+    /// [`TagError::AmbiguousPackageName`][] (`AXOTAG_0011`)
+    AmbiguousPackageName,
+    /// [`TagError::AmbiguousScope`][] (`AXOTAG_0012`)
+    AmbiguousScope,
+    /// [`TagError::NoVersionToBump`][] (`AXOTAG_0013`)
+    NoVersionToBump,
+    /// [`TagError::MsiVersionOverflow`][] (`AXOTAG_0014`)
+    MsiVersionOverflow,
+    /// [`TagError::UnifiedVersionMismatch`][] (`AXOTAG_0015`)
+    UnifiedVersionMismatch,
+    /// [`TagError::UnversionedPackage`][] (`AXOTAG_0016`)
+    UnversionedPackage,
+    /// [`TagError::PackageNotReleasable`][] (`AXOTAG_0017`)
+    PackageNotReleasable,
+    /// [`TagError::PlaceholderVersion`][] (`AXOTAG_0018`)
+    PlaceholderVersion,
+    /// [`TagError::VersionBelowFloor`][] (`AXOTAG_0019`)
+    VersionBelowFloor,
+    /// [`TagError::VersionAboveCeiling`][] (`AXOTAG_0020`)
+    VersionAboveCeiling,
+    /// [`TagError::VersionRegression`][] (`AXOTAG_0021`)
+    VersionRegression,
+    /// [`TagError::MissingPrerelease`][] (`AXOTAG_0022`)
+    MissingPrerelease,
+    /// [`TagError::InvalidTagPattern`][] (`AXOTAG_0023`)
+    #[cfg(feature = "regex")]
+    InvalidTagPattern,
+    /// [`TagError::UnrecognizedTagUrl`][] (`AXOTAG_0024`)
+    UnrecognizedTagUrl,
+    /// [`TagError::NoPackages`][] (`AXOTAG_0025`)
+    NoPackages,
+    /// [`TagError::InvalidGitTag`][] (`AXOTAG_0026`)
+    InvalidGitTag,
+    /// [`TagError::NoVersionToInfer`][] (`AXOTAG_0027`)
+    NoVersionToInfer,
+    /// [`TagError::HeterogeneousVersions`][] (`AXOTAG_0028`)
+    HeterogeneousVersions,
+    /// [`TagError::NotAPrerelease`][] (`AXOTAG_0030`)
+    NotAPrerelease,
+    /// [`TagError::AlreadyReleased`][] (`AXOTAG_0031`)
+    AlreadyReleased,
+    /// [`TagError::OutsideBranchSeries`][] (`AXOTAG_0032`)
+    OutsideBranchSeries,
+    /// [`TagError::OverlappingPackageNames`][] (`AXOTAG_0033`)
+    OverlappingPackageNames,
+    /// [`TagError::InvalidDockerTag`][] (`AXOTAG_0034`)
+    InvalidDockerTag,
+    /// [`TagError::AmbiguousSegment`][] (`AXOTAG_0035`)
+    AmbiguousSegment,
+    /// [`TagError::NotSameSeries`][] (`AXOTAG_0036`)
+    NotSameSeries,
+    /// [`TagError::DeniedTag`][] (`AXOTAG_0037`)
+    DeniedTag,
+    /// [`TagError::SignatureRequired`][] (`AXOTAG_0038`)
+    SignatureRequired,
+    /// [`TagError::InvalidPrereleaseLabel`][] (`AXOTAG_0039`)
+    InvalidPrereleaseLabel,
+    /// [`TagError::PrereleaseCounterOverflow`][] (`AXOTAG_0040`)
+    PrereleaseCounterOverflow,
+    /// [`TagError::VersionOverflow`][] (`AXOTAG_0041`)
+    VersionOverflow,
+    /// [`TagError::NoTagMatch`][] (`AXOTAG_0029`)
+    #[deprecated = "nothing produces this, failed parses report what was wrong with the tag"]
+    NoTagMatch,
+}
+
+impl TagErrorKind {
+    /// Get the stable code of this kind of error, like `AXOTAG_0004`
+    ///
+    /// Codes are never changed or reused, so they're safe to match on (and to link docs
+    /// with). New kinds of errors get new codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TagErrorKind::ContradictoryTagVersion => "AXOTAG_0001",
+            TagErrorKind::StaleTagVersion => "AXOTAG_0002",
+            TagErrorKind::TagVersionAhead => "AXOTAG_0003",
+            TagErrorKind::TagVersionParse => "AXOTAG_0004",
+            #[cfg(feature = "pep440")]
+            TagErrorKind::Pep440Parse => "AXOTAG_0005",
+            TagErrorKind::MissingVPrefix => "AXOTAG_0006",
+            TagErrorKind::ForbiddenVPrefix => "AXOTAG_0007",
+            TagErrorKind::LegacyTagFormat => "AXOTAG_0008",
+            TagErrorKind::UnexpectedPrefix => "AXOTAG_0009",
+            TagErrorKind::MissingPackageComponent => "AXOTAG_0010",
+            TagErrorKind::AmbiguousPackageName => "AXOTAG_0011",
+            TagErrorKind::AmbiguousScope => "AXOTAG_0012",
+            TagErrorKind::NoVersionToBump => "AXOTAG_0013",
+            TagErrorKind::MsiVersionOverflow => "AXOTAG_0014",
+            TagErrorKind::UnifiedVersionMismatch => "AXOTAG_0015",
+            TagErrorKind::UnversionedPackage => "AXOTAG_0016",
+            TagErrorKind::PackageNotReleasable => "AXOTAG_0017",
+            TagErrorKind::PlaceholderVersion => "AXOTAG_0018",
+            TagErrorKind::VersionBelowFloor => "AXOTAG_0019",
+            TagErrorKind::VersionAboveCeiling => "AXOTAG_0020",
+            TagErrorKind::VersionRegression => "AXOTAG_0021",
+            TagErrorKind::MissingPrerelease => "AXOTAG_0022",
+            #[cfg(feature = "regex")]
+            TagErrorKind::InvalidTagPattern => "AXOTAG_0023",
+            TagErrorKind::UnrecognizedTagUrl => "AXOTAG_0024",
+            TagErrorKind::NoPackages => "AXOTAG_0025",
+            TagErrorKind::InvalidGitTag => "AXOTAG_0026",
+            TagErrorKind::NoVersionToInfer => "AXOTAG_0027",
+            TagErrorKind::HeterogeneousVersions => "AXOTAG_0028",
+            TagErrorKind::NotAPrerelease => "AXOTAG_0030",
+            TagErrorKind::AlreadyReleased => "AXOTAG_0031",
+            TagErrorKind::OutsideBranchSeries => "AXOTAG_0032",
+            TagErrorKind::OverlappingPackageNames => "AXOTAG_0033",
+            TagErrorKind::InvalidDockerTag => "AXOTAG_0034",
+            TagErrorKind::AmbiguousSegment => "AXOTAG_0035",
+            TagErrorKind::NotSameSeries => "AXOTAG_0036",
+            TagErrorKind::DeniedTag => "AXOTAG_0037",
+            TagErrorKind::SignatureRequired => "AXOTAG_0038",
+            TagErrorKind::InvalidPrereleaseLabel => "AXOTAG_0039",
+            TagErrorKind::PrereleaseCounterOverflow => "AXOTAG_0040",
+            TagErrorKind::VersionOverflow => "AXOTAG_0041",
+            #[allow(deprecated)]
+            TagErrorKind::NoTagMatch => "AXOTAG_0029",
+        }
+    }
+}
+
+impl TagError {
+    /// Get the stable code of this error, like `AXOTAG_0004`
+    ///
+    /// This is also the [`Diagnostic::code`][] of the error, see [`TagErrorKind::code`][].
+    pub fn code(&self) -> &'static str {
+        self.kind().code()
+    }
+
+    /// Get the kind of this error
+    pub fn kind(&self) -> TagErrorKind {
+        match self {
+            TagError::ContradictoryTagVersion { .. } => TagErrorKind::ContradictoryTagVersion,
+            TagError::StaleTagVersion { .. } => TagErrorKind::StaleTagVersion,
+            TagError::TagVersionAhead { .. } => TagErrorKind::TagVersionAhead,
+            TagError::TagVersionParse { .. } => TagErrorKind::TagVersionParse,
+            #[cfg(feature = "pep440")]
+            TagError::Pep440Parse { .. } => TagErrorKind::Pep440Parse,
+            TagError::MissingVPrefix { .. } => TagErrorKind::MissingVPrefix,
+            TagError::ForbiddenVPrefix { .. } => TagErrorKind::ForbiddenVPrefix,
+            TagError::LegacyTagFormat { .. } => TagErrorKind::LegacyTagFormat,
+            TagError::UnexpectedPrefix { .. } => TagErrorKind::UnexpectedPrefix,
+            TagError::MissingPackageComponent { .. } => TagErrorKind::MissingPackageComponent,
+            TagError::AmbiguousPackageName { .. } => TagErrorKind::AmbiguousPackageName,
+            TagError::AmbiguousScope { .. } => TagErrorKind::AmbiguousScope,
+            TagError::NoVersionToBump { .. } => TagErrorKind::NoVersionToBump,
+            TagError::MsiVersionOverflow { .. } => TagErrorKind::MsiVersionOverflow,
+            TagError::UnifiedVersionMismatch { .. } => TagErrorKind::UnifiedVersionMismatch,
+            TagError::UnversionedPackage { .. } => TagErrorKind::UnversionedPackage,
+            TagError::PackageNotReleasable { .. } => TagErrorKind::PackageNotReleasable,
+            TagError::PlaceholderVersion { .. } => TagErrorKind::PlaceholderVersion,
+            TagError::VersionBelowFloor { .. } => TagErrorKind::VersionBelowFloor,
+            TagError::VersionAboveCeiling { .. } => TagErrorKind::VersionAboveCeiling,
+            TagError::VersionRegression { .. } => TagErrorKind::VersionRegression,
+            TagError::MissingPrerelease { .. } => TagErrorKind::MissingPrerelease,
+            #[cfg(feature = "regex")]
+            TagError::InvalidTagPattern { .. } => TagErrorKind::InvalidTagPattern,
+            TagError::UnrecognizedTagUrl { .. } => TagErrorKind::UnrecognizedTagUrl,
+            TagError::NoPackages { .. } => TagErrorKind::NoPackages,
+            TagError::InvalidGitTag { .. } => TagErrorKind::InvalidGitTag,
+            TagError::NoVersionToInfer { .. } => TagErrorKind::NoVersionToInfer,
+            TagError::HeterogeneousVersions { .. } => TagErrorKind::HeterogeneousVersions,
+            TagError::NotAPrerelease { .. } => TagErrorKind::NotAPrerelease,
+            TagError::AlreadyReleased { .. } => TagErrorKind::AlreadyReleased,
+            TagError::OutsideBranchSeries { .. } => TagErrorKind::OutsideBranchSeries,
+            TagError::OverlappingPackageNames { .. } => TagErrorKind::OverlappingPackageNames,
+            TagError::InvalidDockerTag { .. } => TagErrorKind::InvalidDockerTag,
+            TagError::AmbiguousSegment { .. } => TagErrorKind::AmbiguousSegment,
+            TagError::NotSameSeries { .. } => TagErrorKind::NotSameSeries,
+            TagError::DeniedTag { .. } => TagErrorKind::DeniedTag,
+            TagError::SignatureRequired { .. } => TagErrorKind::SignatureRequired,
+            TagError::InvalidPrereleaseLabel { .. } => TagErrorKind::InvalidPrereleaseLabel,
+            TagError::PrereleaseCounterOverflow { .. } => TagErrorKind::PrereleaseCounterOverflow,
+            TagError::VersionOverflow { .. } => TagErrorKind::VersionOverflow,
+            #[allow(deprecated)]
+            TagError::NoTagMatch { .. } => TagErrorKind::NoTagMatch,
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Explaining how a tag was (or wasn't) read, for debugging and `--explain` flags.
+//!
+//! [`TagParser::parse_tag_traced`][] records every reading the [`grammar`][crate::grammar]
+//! pipeline tried and why it was moved past, so a tag that fails to parse (or parses
+//! as something surprising) explains itself.
+
+use std::fmt;
+
+use crate::errors::{TagError, TagErrorKind, TagResult};
+use crate::grammar::{PipelineStage, TagMatch};
+use crate::{Package, PartialAnnouncementTag, RejectionReason, SegmentKind, TagParser, TagWarning};
+
+/// How [`TagParser::parse_tag_traced`][] went about parsing a tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ParseTrace {
+    /// The namespace the tag started with, if it was in one of [`TagParser::namespaces`][]
+    ///
+    /// The rest of the trace is about the rest of the tag.
+    pub namespace: Option<String>,
+    /// Every matcher that was tried, in order
+    ///
+    /// Parsing stops at the first reading that works out (or is an error). Tags that are
+    /// handled before any matcher runs (like [`TagParser::reserved_tags`][]) have none.
+    pub attempts: Vec<ParseAttempt>,
+    /// Every package whose name starts the tag (or its last slash-delimited segment),
+    /// in input order
+    pub candidates: Vec<Candidate>,
+    /// How the tag was read after [`TagParser::typo_tolerance`][] corrected it, if no
+    /// reading of the tag itself worked out
+    pub typo: Option<Box<TypoCorrection>>,
+}
+
+/// A matcher [`TagParser::parse_tag_traced`][] tried
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ParseAttempt {
+    /// Which matcher it was
+    pub stage: PipelineStage,
+    /// What came of it
+    pub outcome: AttemptOutcome,
+}
+
+/// What came of trying a matcher, see [`ParseAttempt`][]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AttemptOutcome {
+    /// The matcher didn't understand the tag
+    NoMatch,
+    /// The matcher found the parts of the tag, but the version didn't parse, so the next
+    /// matcher got a go
+    Rejected {
+        /// The package it found, or None for a unified release
+        package_name: Option<String>,
+        /// The version it found
+        version: String,
+        /// The kind of error the version got
+        kind: TagErrorKind,
+        /// The error message
+        message: String,
+    },
+    /// The tag was read this way
+    Accepted {
+        /// The package it found, or None for a unified release
+        package_name: Option<String>,
+        /// The version it found
+        version: String,
+    },
+    /// The tag was read this way, but the segment the package was in turned out to be
+    /// something else (see [`TagParser::segment_order`][]), so the tag was read again as
+    /// a unified release
+    Reinterpreted {
+        /// The package it found
+        package_name: Option<String>,
+        /// The version it found
+        version: String,
+        /// The segment (everything before the slash it ends at)
+        segment: String,
+        /// What the segment was taken to be
+        kind: SegmentKind,
+    },
+    /// The tag was read this way, and the reading was an error (so parsing failed)
+    Failed {
+        /// The package it found, or None for a unified release
+        package_name: Option<String>,
+        /// The version it found
+        version: String,
+        /// The kind of error
+        kind: TagErrorKind,
+        /// The error message
+        message: String,
+    },
+}
+
+/// A package [`TagParser::parse_tag_traced`][] considered
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Candidate {
+    /// The package name
+    pub package_name: String,
+    /// Why it wasn't picked, or None if it was
+    ///
+    /// Packages that would have been a fine reading, but lost to another one, get None too.
+    pub rejected: Option<RejectionReason>,
+}
+
+/// A tag [`TagParser::typo_tolerance`][] corrected, see [`ParseTrace::typo`][]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TypoCorrection {
+    /// The tag, with the package name corrected
+    pub tag: String,
+    /// The package it was corrected to
+    pub package_name: String,
+    /// How the corrected tag was parsed
+    pub trace: ParseTrace,
+}
+
+/// One line per attempt and per candidate, like `Dash format: my-app at v1.2.3, accepted`
+impl fmt::Display for ParseTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![];
+        if let Some(namespace) = &self.namespace {
+            lines.push(format!("namespace: {namespace}"));
+        }
+        let reading = |package_name: &Option<String>, version: &str| match package_name {
+            Some(name) => format!("{name} at {version}"),
+            None => format!("unified at {version}"),
+        };
+        for ParseAttempt { stage, outcome } in &self.attempts {
+            lines.push(match outcome {
+                AttemptOutcome::NoMatch => format!("{stage}: no match"),
+                AttemptOutcome::Rejected {
+                    package_name,
+                    version,
+                    message,
+                    ..
+                } => format!(
+                    "{stage}: {}, rejected: {message}",
+                    reading(package_name, version)
+                ),
+                AttemptOutcome::Accepted {
+                    package_name,
+                    version,
+                } => format!("{stage}: {}, accepted", reading(package_name, version)),
+                AttemptOutcome::Reinterpreted {
+                    package_name,
+                    version,
+                    segment,
+                    kind,
+                } => format!(
+                    "{stage}: {}, but {segment} is {kind}, reading it again",
+                    reading(package_name, version)
+                ),
+                AttemptOutcome::Failed {
+                    package_name,
+                    version,
+                    message,
+                    ..
+                } => format!(
+                    "{stage}: {}, failed: {message}",
+                    reading(package_name, version)
+                ),
+            });
+        }
+        for candidate in &self.candidates {
+            lines.push(match &candidate.rejected {
+                Some(reason) => format!("candidate {}: {reason}", candidate.package_name),
+                None => format!("candidate {}: fine", candidate.package_name),
+            });
+        }
+        if let Some(typo) = &self.typo {
+            lines.push(format!("corrected to {}:", typo.tag));
+            lines.extend(
+                typo.trace
+                    .to_string()
+                    .lines()
+                    .map(|line| format!("  {line}")),
+            );
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl ParseTrace {
+    /// Get the package and version a matcher found, for recording the attempt
+    pub(crate) fn reading(
+        packages: &[Package],
+        tag: &str,
+        found: &TagMatch,
+    ) -> (Option<String>, String) {
+        let package_name = (found.package.as_ref()).map(|span| packages[span.idx].name.clone());
+        (package_name, tag[found.version.clone()].to_owned())
+    }
+
+    /// Record what came of reading the version (and checking the package) a matcher found
+    pub(crate) fn record(
+        &mut self,
+        stage: PipelineStage,
+        (package_name, version): (Option<String>, String),
+        result: &TagResult<TagResult<PartialAnnouncementTag>>,
+    ) {
+        let outcome = match result {
+            Ok(Ok(_)) => AttemptOutcome::Accepted {
+                package_name,
+                version,
+            },
+            Ok(Err(e)) => AttemptOutcome::Rejected {
+                package_name,
+                version,
+                kind: e.kind(),
+                message: e.to_string(),
+            },
+            Err(e) => AttemptOutcome::Failed {
+                package_name,
+                version,
+                kind: e.kind(),
+                message: e.to_string(),
+            },
+        };
+        self.attempts.push(ParseAttempt { stage, outcome });
+    }
+
+    /// Record that the accepted reading's package segment turned out to be something
+    /// else, so the tag is being read again as a unified release
+    pub(crate) fn reinterpret(&mut self, tag: &str, segment: &str, kind: SegmentKind) {
+        let Some(attempt) = self.attempts.last_mut() else {
+            return;
+        };
+        if let AttemptOutcome::Accepted {
+            package_name,
+            version,
+        } = &attempt.outcome
+        {
+            attempt.outcome = AttemptOutcome::Reinterpreted {
+                package_name: package_name.clone(),
+                version: version.clone(),
+                segment: segment.to_owned(),
+                kind,
+            };
+        }
+        let version = tag.rsplit_once('/').map_or(tag, |(_, version)| version);
+        self.attempts.push(ParseAttempt {
+            stage: PipelineStage::Unified,
+            outcome: AttemptOutcome::Accepted {
+                package_name: None,
+                version: version.to_owned(),
+            },
+        });
+    }
+
+    /// Record that the accepted reading failed a check that comes after reading it
+    pub(crate) fn fail(&mut self, e: &TagError) {
+        let Some(attempt) = self.attempts.last_mut() else {
+            return;
+        };
+        if let AttemptOutcome::Accepted {
+            package_name,
+            version,
+        } = &attempt.outcome
+        {
+            attempt.outcome = AttemptOutcome::Failed {
+                package_name: package_name.clone(),
+                version: version.clone(),
+                kind: e.kind(),
+                message: e.to_string(),
+            };
+        }
+    }
+}
+
+impl TagParser {
+    /// Parse a tag like [`TagParser::parse_tag`][], and explain how it went
+    ///
+    /// The trace is recorded as the tag is parsed, so it always agrees with the result.
+    pub fn parse_tag_traced(
+        &self,
+        packages: &[Package],
+        tag: &str,
+    ) -> (TagResult<PartialAnnouncementTag>, ParseTrace) {
+        let mut trace = ParseTrace::default();
+        let result = self.parse_tag_recording(packages, tag, Some(&mut trace));
+        (result, trace)
+    }
+
+    /// Find every package whose name starts the tag, and why it wasn't picked
+    pub(crate) fn trace_candidates(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        chosen: Option<usize>,
+    ) -> Vec<Candidate> {
+        let mut rejected = self
+            .rejected_candidates(packages, tag, chosen, None)
+            .into_iter()
+            .filter_map(|warning| match warning {
+                TagWarning::RejectedCandidate {
+                    package_name,
+                    reason,
+                } => Some((package_name, reason)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let names = self.name_matcher();
+        let component = crate::unified_version_component(tag);
+        packages
+            .iter()
+            .filter(|package| names.strip_prefix(component, &package.name).is_some())
+            .map(|package| {
+                let pos = rejected.iter().position(|(name, _)| *name == package.name);
+                Candidate {
+                    package_name: package.name.clone(),
+                    rejected: pos.map(|pos| rejected.remove(pos).1),
+                }
+            })
+            .collect()
+    }
+}
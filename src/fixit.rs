@@ -0,0 +1,89 @@
+//! Machine-readable fixes for bad tags, so tools can correct them instead of just complaining.
+
+use std::ops::Range;
+
+use crate::errors::TagError;
+
+/// An edit that would fix a tag: replace the bytes in `range` with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FixIt {
+    /// The byte range of the tag to replace (empty for an insertion)
+    pub range: Range<usize>,
+    /// The text to put there (empty for a deletion)
+    pub replacement: String,
+}
+
+impl FixIt {
+    /// Apply this fix to the tag it was made for
+    pub fn apply(&self, tag: &str) -> String {
+        let mut fixed = tag.to_owned();
+        fixed.replace_range(self.range.clone(), &self.replacement);
+        fixed
+    }
+}
+
+impl TagError {
+    /// Get edits that would fix the tag this error is about, if we know any
+    ///
+    /// Several fixes are alternatives, not steps: apply only one of them.
+    pub fn fix_its(&self) -> Vec<FixIt> {
+        match self {
+            TagError::MissingVPrefix { version_start, .. } => vec![FixIt {
+                range: *version_start..*version_start,
+                replacement: "v".to_owned(),
+            }],
+            TagError::ForbiddenVPrefix { version_start, .. } => vec![FixIt {
+                range: *version_start..*version_start + 1,
+                replacement: String::new(),
+            }],
+            TagError::LegacyTagFormat { tag, canonical, .. } => vec![FixIt {
+                range: 0..tag.len(),
+                replacement: canonical.clone(),
+            }],
+            TagError::UnexpectedPrefix { prefix, .. } => vec![FixIt {
+                range: 0..prefix.len() + 1,
+                replacement: String::new(),
+            }],
+            TagError::MissingPackageComponent { tag, example } => vec![FixIt {
+                range: 0..tag.len(),
+                replacement: example.clone(),
+            }],
+            // Tags that are ahead of their package are better fixed by bumping the package
+            TagError::ContradictoryTagVersion {
+                tag,
+                tag_version,
+                real_version,
+                ..
+            }
+            | TagError::StaleTagVersion {
+                tag,
+                tag_version,
+                real_version,
+                ..
+            } => {
+                // Only fixable if the version was spelled out normally
+                let tag_version = tag_version.to_string();
+                tag.rfind(&tag_version)
+                    .map(|start| FixIt {
+                        range: start..start + tag_version.len(),
+                        replacement: real_version.to_string(),
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Get where a part of a tag (which should be a subslice of it) starts
+pub(crate) fn offset_in(tag: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(tag.as_ptr() as usize);
+    if offset <= tag.len() && offset + part.len() <= tag.len() {
+        offset
+    } else {
+        tag.rfind(part).unwrap_or(0)
+    }
+}
@@ -0,0 +1,306 @@
+//! Tag formats, for when you need to produce tags as well as parse them.
+
+use semver::Version;
+
+use crate::matching::package_prefixes;
+use crate::{strip_prefix_package, NameMatcher, Package};
+
+/// A way of spelling release tags
+///
+/// Unified releases are spelled the same way in every format (`v1.2.3`), the
+/// formats only disagree on how a package gets attached to the version.
+/// Every format accepts an arbitrary slash-delimited prefix like `releases/`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum TagFormat {
+    /// `my-app-v1.2.3`
+    #[default]
+    Dash,
+    /// `my-app/v1.2.3`
+    ///
+    /// A `vN` element between the package and the version is treated as a Go-style
+    /// major-version marker (`my-app/v2/v2.1.0`) unless a package has that name.
+    Slash,
+    /// `crates/my-app/v1.2.3`, using [`Package::path`][] instead of the package's name
+    ///
+    /// Packages without a path are spelled like [`TagFormat::Slash`][].
+    ///
+    /// This follows Go's conventions for major versions: a package at `my-mod/v2` is
+    /// tagged `my-mod/v2.1.0` (the `v2` element is a major-version marker, and must
+    /// agree with the version).
+    Path,
+}
+
+/// How a particular tag was actually spelled
+///
+/// Unlike [`TagFormat`][] (which is a convention you can ask for), this describes what
+/// was found, including any arbitrary prefix. Comparing [`TagStyle::without_prefix`][]
+/// across tags is a quick way to check that a repo uses one convention consistently.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TagStyle {
+    /// `v1.2.3`
+    Unified,
+    /// `my-app-v1.2.3`
+    PackageDash,
+    /// `my-app/v1.2.3`
+    PackageSlash,
+    /// `crates/my-app/v1.2.3`
+    PackagePath,
+    /// Any of the other styles behind an arbitrary prefix, like `releases/v1.2.3`
+    SlashPrefixed {
+        /// The prefix, without its trailing slash (`releases`)
+        prefix: String,
+        /// The style of the rest of the tag
+        style: Box<TagStyle>,
+    },
+    /// Matched a custom [`TagMatcher`][crate::TagMatcher], which names its own convention
+    Custom {
+        /// The name of the convention, like `changesets`
+        name: String,
+    },
+    /// Matched one of [`TagParser::patterns`][crate::TagParser::patterns]
+    #[cfg(feature = "regex")]
+    Pattern {
+        /// The source of the pattern's regex
+        pattern: String,
+    },
+}
+
+impl TagStyle {
+    /// Get the style of a tag in the given format, with the given (possibly empty) prefix
+    pub(crate) fn new(format: Option<TagFormat>, prefix: &str) -> TagStyle {
+        let style = match format {
+            None => TagStyle::Unified,
+            Some(TagFormat::Dash) => TagStyle::PackageDash,
+            Some(TagFormat::Slash) => TagStyle::PackageSlash,
+            Some(TagFormat::Path) => TagStyle::PackagePath,
+        };
+        if prefix.is_empty() {
+            style
+        } else {
+            TagStyle::SlashPrefixed {
+                prefix: prefix.to_owned(),
+                style: Box::new(style),
+            }
+        }
+    }
+
+    /// Get the arbitrary prefix of the tag, if it had one
+    pub fn prefix(&self) -> Option<&str> {
+        match self {
+            TagStyle::SlashPrefixed { prefix, .. } => Some(prefix),
+            _ => None,
+        }
+    }
+
+    /// Get the style with any prefix removed
+    pub fn without_prefix(&self) -> &TagStyle {
+        match self {
+            TagStyle::SlashPrefixed { style, .. } => style.without_prefix(),
+            style => style,
+        }
+    }
+}
+
+impl TagFormat {
+    /// Get a template describing how this format spells package tags, like `{package}-v{version}`
+    ///
+    /// This is the spelling with the default settings, see
+    /// [`TagParser::tag_template`][crate::TagParser::tag_template] for a parser's own.
+    pub fn template(&self) -> &'static str {
+        match self {
+            TagFormat::Dash => "{package}-v{version}",
+            TagFormat::Slash => "{package}/v{version}",
+            TagFormat::Path => "{path}/v{version}",
+        }
+    }
+
+    /// Spell out a tag for the given package (or a unified release, if None)
+    ///
+    /// The version always gets a `v` prefix, use [`TagParser::format_tag`][crate::TagParser::format_tag]
+    /// to respect a [`VPrefixPolicy`][crate::VPrefixPolicy] and
+    /// [`TagParser::package_separators`][crate::TagParser::package_separators].
+    pub fn format(&self, package: Option<&Package>, version: &Version) -> String {
+        self.format_version(package, &format!("v{version}"), '-')
+    }
+
+    /// Spell out a tag for the given package with an already-rendered version component,
+    /// joining [`TagFormat::Dash`][] tags with `separator`
+    pub(crate) fn format_version(
+        &self,
+        package: Option<&Package>,
+        version: &str,
+        separator: char,
+    ) -> String {
+        let Some(package) = package else {
+            return version.to_owned();
+        };
+        match (self, package.normalized_path()) {
+            (TagFormat::Dash, _) => format!("{}{separator}{version}", package.name),
+            (TagFormat::Path, Some(path)) => match split_major_version_marker(&path) {
+                // A module at the root of a Go repo with a major-version marker just gets a
+                // version tag
+                Some(("", _)) => version.to_owned(),
+                Some((path, _)) => format!("{path}/{version}"),
+                None => format!("{path}/{version}"),
+            },
+            (TagFormat::Slash | TagFormat::Path, _) => format!("{}/{version}", package.name),
+        }
+    }
+
+    /// Try to find a package in the given tag using this format
+    ///
+    /// [`TagFormat::Dash`][] tags can separate the package and version with any of `separators`.
+    pub(crate) fn strip_package<'a>(
+        &self,
+        tag: &'a str,
+        packages: &[Package],
+        names: NameMatcher,
+        separators: &[char],
+    ) -> Option<FormatMatch<'a>> {
+        match self {
+            TagFormat::Dash => {
+                // Any slash-delimited prefix is ignored, check if the rest is
+                // in "some-package-v1.0.0" format
+                let (prefix, rest) = tag.rsplit_once('/').unwrap_or(("", tag));
+                // Must be followed by a separator to be accepted, so fall back to shorter
+                // names if the longest one isn't ("sdk-v2.0.0" with "sdk" and "sdk-v2")
+                let (idx, alias, after, version) = package_prefixes(rest, packages, names)
+                    .into_iter()
+                    .find_map(|found| {
+                        let version = found.rest.strip_prefix(separators)?;
+                        Some((found.idx, found.alias, found.rest, version))
+                    })?;
+                Some(FormatMatch {
+                    idx,
+                    alias,
+                    name: &rest[..rest.len() - after.len()],
+                    prefix,
+                    version,
+                })
+            }
+            TagFormat::Slash => {
+                // We're at least in "blah/v1.0.0" format
+                let (prefix, version) = tag.rsplit_once('/')?;
+                let (rest, maybe_package) = split_last_component(prefix);
+                // Check if this is "blah/blah/some-package/v1.0.0" format by checking if the last
+                // slash-delimited component is exactly a package name (strip_prefix produces empty
+                // string)
+                if let Some((idx, alias, "")) = strip_prefix_package(maybe_package, packages, names)
+                {
+                    return Some(FormatMatch {
+                        idx,
+                        alias,
+                        name: maybe_package,
+                        prefix: rest,
+                        version,
+                    });
+                }
+                // Check if this is "blah/some-package/v2/v2.1.0" format, with a Go-style
+                // major-version marker that has to agree with the version
+                let (prefix, marker) = prefix.rsplit_once('/')?;
+                let major = major_version_marker(marker)?;
+                if !has_major_version(version, major) {
+                    return None;
+                }
+                let (rest, maybe_package) = split_last_component(prefix);
+                match strip_prefix_package(maybe_package, packages, names) {
+                    Some((idx, alias, "")) => Some(FormatMatch {
+                        idx,
+                        alias,
+                        name: maybe_package,
+                        prefix: rest,
+                        version,
+                    }),
+                    _ => None,
+                }
+            }
+            TagFormat::Path => {
+                // Check if this is "blah/crates/some-package/v1.0.0" format by checking if
+                // everything before the version ends with a package's path, preferring
+                // whichever path is longest
+                let (prefix, version) = tag.rsplit_once('/')?;
+                let mut result: Option<(usize, &str, &str)> = None;
+                for (pkg_id, package) in packages.iter().enumerate() {
+                    let Some(path) = package.normalized_path() else {
+                        continue;
+                    };
+                    // A package at "my-mod/v2" is tagged "my-mod/v2.1.0" in Go, so also
+                    // try the path without its major-version marker
+                    let go_path = split_major_version_marker(&path)
+                        .filter(|(_, major)| has_major_version(version, *major))
+                        .map(|(path, _)| path);
+                    for path in std::iter::once(path.as_str()).chain(go_path) {
+                        let Some(rest) = prefix.strip_suffix(path) else {
+                            continue;
+                        };
+                        if path.is_empty() || !(rest.is_empty() || rest.ends_with('/')) {
+                            continue;
+                        }
+                        if result.is_some_and(|(_, best, _)| best.len() >= path.len()) {
+                            continue;
+                        }
+                        let name = &prefix[rest.len()..];
+                        let rest = rest.strip_suffix('/').unwrap_or(rest);
+                        result = Some((pkg_id, name, rest));
+                    }
+                }
+                result.map(|(idx, name, prefix)| FormatMatch {
+                    idx,
+                    alias: None,
+                    name,
+                    prefix,
+                    version,
+                })
+            }
+        }
+    }
+}
+
+/// A package found in a tag by [`TagFormat::strip_package`][]
+pub(crate) struct FormatMatch<'a> {
+    /// The idx of the package
+    pub(crate) idx: usize,
+    /// The idx of the alias the tag used, if it didn't use the package's name
+    pub(crate) alias: Option<usize>,
+    /// The package's name (or path) as the tag spelled it
+    pub(crate) name: &'a str,
+    /// The arbitrary prefix before the package (without its trailing slash)
+    pub(crate) prefix: &'a str,
+    /// The rest of the tag (which should be the version)
+    pub(crate) version: &'a str,
+}
+
+/// Split the last slash-delimited component off a path (or the whole thing if there's no slash)
+fn split_last_component(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+/// Check if a path element is a Go-style major-version marker like `v2`, and get the major version
+///
+/// Go never uses `v0` or `v1` markers, so neither do we.
+fn major_version_marker(element: &str) -> Option<u64> {
+    let major = element.strip_prefix('v')?;
+    if !major.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    major.parse().ok().filter(|major| *major >= 2)
+}
+
+/// Split a Go-style major-version marker off the end of a path (`my-mod/v2` => `my-mod`, 2)
+fn split_major_version_marker(path: &str) -> Option<(&str, u64)> {
+    let (prefix, marker) = path.rsplit_once('/').unwrap_or(("", path));
+    Some((prefix, major_version_marker(marker)?))
+}
+
+/// Check if the version component of a tag (`v2.1.0`) has the given major version
+fn has_major_version(version: &str, major: u64) -> bool {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version
+        .split_once('.')
+        .is_some_and(|(v, _)| v.parse::<u64>().ok() == Some(major))
+}
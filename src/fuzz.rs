@@ -0,0 +1,279 @@
+//! Generators of valid and near-valid tags, for fuzzing code that consumes axotag.
+//!
+//! Tags are described by a [`TagRecipe`][] and rendered against a package set, so
+//! fuzzers generate tags that follow the same grammar axotag accepts (and the typical
+//! ways humans get it slightly wrong). Recipes implement [`arbitrary::Arbitrary`][] for
+//! coverage-guided fuzzers, and the functions here provide proptest strategies.
+
+use std::collections::BTreeSet;
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+use proptest::sample::select;
+use semver::Prerelease;
+
+use crate::{
+    parse_tag, unified_version, Package, TagFormat, TagParser, VPrefixPolicy, Version,
+    VersionScheme,
+};
+
+/// Pieces that package names are made of
+///
+/// These are few enough that names often prefix each other (`app` and `app-cli`), and
+/// include a version-like one, to exercise the tricky parts of matching.
+const NAME_PARTS: &[&str] = &["axo", "lotl", "say", "app", "cli", "core", "2"];
+
+/// A description of a tag to generate for some set of packages
+#[derive(Debug, Clone, PartialEq, Eq, Arbitrary)]
+pub struct TagRecipe {
+    /// The package to tag (wrapping around the releasable packages), or None for a
+    /// unified release
+    pub package: Option<usize>,
+    /// How to spell the tag
+    pub format: TagFormat,
+    /// Whether the version gets a `v`
+    pub v_prefix: bool,
+    /// Whether to put the tag behind a `releases/` prefix
+    pub prefix: bool,
+    /// The version to use if the package (or workspace) doesn't have one, as
+    /// `(major, minor, patch, rc)`
+    pub fallback_version: (u8, u8, u8, Option<u8>),
+    /// How to break the tag, if at all
+    pub mutation: Option<TagMutation>,
+}
+
+/// A typical way of getting a tag slightly wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Arbitrary)]
+pub enum TagMutation {
+    /// Drop the `v` before the version (`my-app-1.2.3`), or add one if there wasn't any
+    ToggleV,
+    /// Double the `v` (`my-app-vv1.2.3`)
+    DoubleV,
+    /// Misspell the package name (`my-appp-v1.2.3`)
+    TypoName,
+    /// Use `_` between the package and the version (`my-app_v1.2.3`)
+    WrongSeparator,
+    /// Drop the patch version (`my-app-v1.2`)
+    TruncateVersion,
+    /// Add a fourth version component (`my-app-v1.2.3.4`)
+    ExtraComponent,
+    /// Add a leading zero to the minor version (`my-app-v1.02.3`)
+    LeadingZero,
+    /// Uppercase the whole tag (`MY-APP-V1.2.3`)
+    Uppercase,
+    /// Leave trailing whitespace (`my-app-v1.2.3 `)
+    TrailingWhitespace,
+}
+
+/// Every [`TagMutation`][]
+const TAG_MUTATIONS: &[TagMutation] = &[
+    TagMutation::ToggleV,
+    TagMutation::DoubleV,
+    TagMutation::TypoName,
+    TagMutation::WrongSeparator,
+    TagMutation::TruncateVersion,
+    TagMutation::ExtraComponent,
+    TagMutation::LeadingZero,
+    TagMutation::Uppercase,
+    TagMutation::TrailingWhitespace,
+];
+
+impl TagRecipe {
+    /// Produce the tag this recipe describes for the given packages
+    pub fn render(&self, packages: &[Package]) -> String {
+        let releasable = packages.iter().filter(|p| !p.excluded).collect::<Vec<_>>();
+        let package = self
+            .package
+            .filter(|_| !releasable.is_empty())
+            .map(|idx| releasable[idx % releasable.len()]);
+        let version = match package {
+            Some(package) => package.version.clone(),
+            None => unified_version(packages).cloned(),
+        };
+        let version = version.unwrap_or_else(|| self.fallback_version());
+
+        let parser = TagParser {
+            formats: vec![self.format],
+            v_prefix: if self.v_prefix {
+                VPrefixPolicy::Require
+            } else {
+                VPrefixPolicy::Forbid
+            },
+            ..TagParser::default()
+        };
+        let mut tag = parser.format_tag(package, &version);
+        if self.prefix {
+            tag.insert_str(0, "releases/");
+        }
+        let version_start = tag.len() - version.to_string().len();
+        match self.mutation {
+            Some(mutation) => mutate(tag, version_start, package, mutation),
+            None => tag,
+        }
+    }
+
+    fn fallback_version(&self) -> Version {
+        let (major, minor, patch, rc) = self.fallback_version;
+        let mut version = Version::new(major.into(), minor.into(), patch.into());
+        if let Some(rc) = rc {
+            version.pre = Prerelease::new(&format!("rc.{rc}")).expect("rc.N is a valid prerelease");
+        }
+        version
+    }
+}
+
+/// Break a tag in the given way
+///
+/// `version_start` is where the version (after any `v`) starts in the tag.
+fn mutate(
+    mut tag: String,
+    version_start: usize,
+    package: Option<&Package>,
+    mutation: TagMutation,
+) -> String {
+    let has_v = tag[..version_start].ends_with('v');
+    let v_start = version_start - usize::from(has_v);
+    match mutation {
+        TagMutation::ToggleV if has_v => tag.replace_range(v_start..version_start, ""),
+        TagMutation::ToggleV => tag.insert(version_start, 'v'),
+        TagMutation::DoubleV => tag.insert(v_start, 'v'),
+        TagMutation::TypoName => match package.and_then(|p| Some((tag.find(&p.name)?, p))) {
+            Some((start, package)) => tag.insert(start + package.name.len() - 1, 'x'),
+            None => tag.insert(v_start, 'x'),
+        },
+        TagMutation::WrongSeparator if v_start > 0 => tag.replace_range(v_start - 1..v_start, "_"),
+        TagMutation::WrongSeparator => tag.insert(0, '_'),
+        TagMutation::TruncateVersion => {
+            let core_end = tag[version_start..]
+                .find(['-', '+'])
+                .map_or(tag.len(), |end| version_start + end);
+            let patch_start = tag[..core_end].rfind('.').unwrap_or(core_end);
+            tag.replace_range(patch_start..core_end, "");
+        }
+        TagMutation::ExtraComponent => {
+            let core_end = tag[version_start..]
+                .find(['-', '+'])
+                .map_or(tag.len(), |end| version_start + end);
+            tag.insert_str(core_end, ".4");
+        }
+        TagMutation::LeadingZero => {
+            let minor_start = tag[version_start..]
+                .find('.')
+                .map_or(version_start, |dot| version_start + dot + 1);
+            tag.insert(minor_start, '0');
+        }
+        TagMutation::Uppercase => tag.make_ascii_uppercase(),
+        TagMutation::TrailingWhitespace => tag.push(' '),
+    }
+    tag
+}
+
+impl<'a> Arbitrary<'a> for Package {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Package> {
+        let parts = u.int_in_range(1..=3)?;
+        let name = (0..parts)
+            .map(|_| u.choose(NAME_PARTS).copied())
+            .collect::<arbitrary::Result<Vec<_>>>()?
+            .join("-");
+        let version = if u.arbitrary()? {
+            let (major, minor, patch) = u.arbitrary::<(u8, u8, u8)>()?;
+            Some(Version::new(major.into(), minor.into(), patch.into()))
+        } else {
+            None
+        };
+        let path = u.arbitrary::<bool>()?.then(|| format!("crates/{name}"));
+        Ok(Package {
+            name,
+            version,
+            path,
+            aliases: vec![],
+            excluded: u.ratio(1, 8)?,
+            version_scheme: VersionScheme::Semver,
+            namespace: None,
+        })
+    }
+}
+
+/// A proptest strategy for a workspace's packages
+///
+/// See [`Package`][]'s [`Arbitrary`][] impl for the kind of packages this makes. Like in
+/// a real workspace, no two packages have the same name.
+pub fn packages() -> impl Strategy<Value = Vec<Package>> {
+    let name = prop::collection::vec(select(NAME_PARTS), 1..=3).prop_map(|parts| parts.join("-"));
+    let version = prop::option::of((0..4u64, 0..20u64, 0..20u64));
+    let package = (name, version, any::<bool>(), prop::bool::weighted(0.125)).prop_map(
+        |(name, version, has_path, excluded)| Package {
+            path: has_path.then(|| format!("crates/{name}")),
+            version: version.map(|(major, minor, patch)| Version::new(major, minor, patch)),
+            name,
+            aliases: vec![],
+            excluded,
+            version_scheme: VersionScheme::Semver,
+            namespace: None,
+        },
+    );
+    prop::collection::vec(package, 1..=4).prop_map(|mut packages| {
+        let mut seen = BTreeSet::new();
+        packages.retain(|p| seen.insert(p.name.clone()));
+        packages
+    })
+}
+
+/// A proptest strategy for [`TagRecipe`][]s
+pub fn tag_recipes() -> impl Strategy<Value = TagRecipe> {
+    let format = prop_oneof![
+        Just(TagFormat::Dash),
+        Just(TagFormat::Slash),
+        Just(TagFormat::Path),
+    ];
+    let mutation = prop::option::of(select(TAG_MUTATIONS));
+    let fallback_version = (any::<u8>(), any::<u8>(), any::<u8>(), any::<Option<u8>>());
+    (
+        any::<Option<usize>>(),
+        format,
+        any::<bool>(),
+        any::<bool>(),
+        fallback_version,
+        mutation,
+    )
+        .prop_map(
+            |(package, format, v_prefix, prefix, fallback_version, mutation)| TagRecipe {
+                package,
+                format,
+                v_prefix,
+                prefix,
+                fallback_version,
+                mutation,
+            },
+        )
+}
+
+/// A proptest strategy for tags the default [`TagParser`][] accepts for these packages
+pub fn valid_tags(packages: Vec<Package>) -> impl Strategy<Value = String> {
+    let rendering = packages.clone();
+    tag_recipes()
+        .prop_map(move |recipe| {
+            TagRecipe {
+                mutation: None,
+                ..recipe
+            }
+            .render(&rendering)
+        })
+        .prop_filter("the default parser should accept the tag", move |tag| {
+            parse_tag(&packages, tag).is_ok()
+        })
+}
+
+/// A proptest strategy for tags with one of the usual mistakes, for these packages
+///
+/// These are often rejected, but not always (the default [`TagParser`][] allows
+/// tags without a `v`, for instance).
+pub fn near_valid_tags(packages: Vec<Package>) -> impl Strategy<Value = String> {
+    (tag_recipes(), select(TAG_MUTATIONS)).prop_map(move |(recipe, mutation)| {
+        TagRecipe {
+            mutation: Some(mutation),
+            ..recipe
+        }
+        .render(&packages)
+    })
+}
@@ -0,0 +1,303 @@
+//! The pipeline tags are parsed with: a series of matchers that each find the parts of a
+//! tag (prefix, package, separator, and version) their own way.
+//!
+//! [`TagParser::parse_tag`][] tries any `TagParser::patterns` (with the `regex` feature), then
+//! [`TagParser::matchers`][], then [`TagParser::formats`][], and finally the unified
+//! reading (`v1.2.3`, behind any prefix). The first reading whose version parses wins.
+//! Everything after finding the parts (channels, version schemes, version checks) is
+//! shared, so custom matchers get the same treatment as the built-in formats.
+
+use std::ops::Range;
+
+use crate::compiled::NameIndex;
+use crate::format::FormatMatch;
+use crate::{
+    strip_prefix_package, unscoped_name, NameMatcher, Package, TagFormat, TagParser, TagStyle,
+};
+
+/// A way of finding the parts of a tag, see [`TagParser::matchers`][]
+pub trait TagMatcher: std::fmt::Debug + Send + Sync {
+    /// Find the parts of the tag, if this matcher understands it
+    ///
+    /// Only say where things are: the version is parsed (and checked against the
+    /// package) afterwards, and if it doesn't parse the next matcher gets a go.
+    ///
+    /// Every range in the match has to be a range of `tag` (in order, in bounds, and on
+    /// char boundaries), [`PackageSpan::idx`][] has to be an index of
+    /// [`MatchContext::packages`][], and [`PackageSpan::alias`][] one of that package's
+    /// [`Package::aliases`][]. Matches that break these rules are ignored, as if the
+    /// matcher hadn't matched.
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch>;
+}
+
+/// Where a matcher in the pipeline came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum PipelineStage {
+    /// The `TagParser::patterns` entry with this idx
+    Pattern(usize),
+    /// The [`TagParser::matchers`][] entry with this idx
+    Matcher(usize),
+    /// One of [`TagParser::formats`][]
+    Format(TagFormat),
+    /// The unified reading (`v1.2.3`, behind any prefix)
+    Unified,
+}
+
+impl std::fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineStage::Pattern(idx) => write!(f, "pattern #{idx}"),
+            PipelineStage::Matcher(idx) => write!(f, "matcher #{idx}"),
+            PipelineStage::Format(format) => write!(f, "{format:?} format"),
+            PipelineStage::Unified => write!(f, "unified reading"),
+        }
+    }
+}
+
+/// What matchers get to look at besides the tag
+#[derive(Debug, Clone, Copy)]
+pub struct MatchContext<'a> {
+    parser: &'a TagParser,
+    packages: &'a [Package],
+    index: Option<&'a NameIndex>,
+}
+
+/// The parts of a tag a [`TagMatcher`][] found, as byte ranges of the tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagMatch {
+    /// The arbitrary prefix before the rest of the tag, without its trailing slash
+    /// (empty if there isn't one)
+    pub prefix: Range<usize>,
+    /// The package the tag names, or None for a unified release
+    pub package: Option<PackageSpan>,
+    /// Whatever is between the package and the version (empty if there's no package)
+    pub separator: Range<usize>,
+    /// The version, including any `v` (this can also be a channel)
+    pub version: Range<usize>,
+    /// How sure the matcher is that it found the package
+    pub confidence: Confidence,
+    /// The format to report for the tag (and to hold it to, with
+    /// [`TagParser::reject_legacy_formats`][]), if it's spelled like one
+    pub format: Option<TagFormat>,
+    /// How the tag was spelled
+    pub style: TagStyle,
+}
+
+impl TagMatch {
+    /// Check that every range is in the tag and every idx is in the packages
+    fn is_valid(&self, packages: &[Package], tag: &str) -> bool {
+        let in_tag = |range: &Range<usize>| tag.get(range.clone()).is_some();
+        let package = self.package.as_ref().is_none_or(|span| {
+            in_tag(&span.range)
+                && packages.get(span.idx).is_some_and(|package| {
+                    span.alias.is_none_or(|alias| alias < package.aliases.len())
+                })
+        });
+        package && in_tag(&self.prefix) && in_tag(&self.separator) && in_tag(&self.version)
+    }
+}
+
+/// Where a [`TagMatch`][] found a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSpan {
+    /// The idx of the package
+    pub idx: usize,
+    /// The idx of the alias the tag used, if it didn't use the package's name
+    pub alias: Option<usize>,
+    /// Where the package's name (or path) is in the tag
+    pub range: Range<usize>,
+}
+
+/// How sure a [`TagMatcher`][] is that it found the package
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Confidence {
+    /// The tag spelled the package exactly like one of its names (or its path)
+    #[default]
+    Exact,
+    /// The tag only spelled the package's name the same modulo case or separators,
+    /// which gets a [`TagWarning::InexactPackageName`][crate::TagWarning::InexactPackageName]
+    Inexact,
+}
+
+/// A package name found at the start of some input by [`MatchContext::strip_package_prefix`][]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackagePrefix<'i> {
+    /// The idx of the package
+    pub idx: usize,
+    /// The idx of the alias that matched, if it wasn't the package's name
+    pub alias: Option<usize>,
+    /// How exactly the name matched
+    pub confidence: Confidence,
+    /// The rest of the input
+    pub rest: &'i str,
+}
+
+impl<'a> MatchContext<'a> {
+    pub(crate) fn new(parser: &'a TagParser, packages: &'a [Package]) -> MatchContext<'a> {
+        MatchContext {
+            parser,
+            packages,
+            index: None,
+        }
+    }
+
+    /// Look package names up in an index of the packages
+    pub(crate) fn with_index(self, index: &'a NameIndex) -> MatchContext<'a> {
+        MatchContext {
+            index: Some(index),
+            ..self
+        }
+    }
+
+    /// Get how we compare package names
+    pub(crate) fn name_matcher(&self) -> NameMatcher<'a> {
+        let names = self.parser.name_matcher();
+        match self.index {
+            Some(index) => names.with_index(index),
+            None => names,
+        }
+    }
+
+    /// Run a matcher, ignoring its match if it breaks the rules of [`TagMatcher::match_tag`][]
+    pub(crate) fn run(&self, matcher: &dyn TagMatcher, tag: &str) -> Option<TagMatch> {
+        let found = matcher.match_tag(self, tag)?;
+        if found.is_valid(self.packages, tag) {
+            Some(found)
+        } else {
+            crate::trace::debug!(?matcher, ?found, "matcher returned an invalid match");
+            None
+        }
+    }
+
+    /// Get the settings the tag is being parsed with
+    pub fn parser(&self) -> &'a TagParser {
+        self.parser
+    }
+
+    /// Get the packages the tag could be for
+    pub fn packages(&self) -> &'a [Package] {
+        self.packages
+    }
+
+    /// Find the package whose name (or alias) starts the input, preferring the longest
+    ///
+    /// Names are compared like the built-in formats compare them, respecting
+    /// [`TagParser::case_insensitive_names`][] and friends.
+    pub fn strip_package_prefix<'i>(&self, input: &'i str) -> Option<PackagePrefix<'i>> {
+        let (idx, alias, rest) = strip_prefix_package(input, self.packages, self.name_matcher())?;
+        let name = &input[..input.len() - rest.len()];
+        Some(PackagePrefix {
+            idx,
+            alias,
+            confidence: self.name_confidence(idx, alias, name),
+            rest,
+        })
+    }
+
+    /// Check whether a tag spelled a package's name exactly
+    fn name_confidence(&self, idx: usize, alias: Option<usize>, found: &str) -> Confidence {
+        let name = &self.packages[idx].name;
+        let exact = alias.is_some()
+            || found == name
+            || (self.parser.strip_npm_scopes && found == unscoped_name(name));
+        if exact {
+            Confidence::Exact
+        } else {
+            Confidence::Inexact
+        }
+    }
+}
+
+impl TagMatcher for TagFormat {
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch> {
+        let FormatMatch {
+            idx,
+            alias,
+            name,
+            prefix,
+            version,
+        } = self.strip_package(tag, cx.packages, cx.name_matcher(), cx.parser.separators())?;
+        let name_start = crate::fixit::offset_in(tag, name);
+        let version_start = tag.len() - version.len();
+        // Paths are always matched exactly
+        let confidence = match self {
+            TagFormat::Path => Confidence::Exact,
+            TagFormat::Dash | TagFormat::Slash => cx.name_confidence(idx, alias, name),
+        };
+        Some(TagMatch {
+            prefix: 0..prefix.len(),
+            package: Some(PackageSpan {
+                idx,
+                alias,
+                range: name_start..name_start + name.len(),
+            }),
+            separator: name_start + name.len()..version_start,
+            version: version_start..tag.len(),
+            confidence,
+            format: Some(*self),
+            style: TagStyle::new(Some(*self), prefix),
+        })
+    }
+}
+
+/// The reading of a tag as a unified release, which looks the same in every format
+#[derive(Debug)]
+pub(crate) struct UnifiedMatcher;
+
+impl TagMatcher for UnifiedMatcher {
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch> {
+        let (prefix, version) = tag.rsplit_once('/').unwrap_or(("", tag));
+        let version_start = tag.len() - version.len();
+        Some(TagMatch {
+            prefix: 0..prefix.len(),
+            package: None,
+            separator: version_start..version_start,
+            version: version_start..tag.len(),
+            confidence: Confidence::Exact,
+            format: cx.parser.formats.first().copied(),
+            style: TagStyle::new(None, prefix),
+        })
+    }
+}
+
+impl TagParser {
+    /// Get every matcher we try, in order of priority (the unified reading always comes last)
+    pub(crate) fn pipeline(&self) -> impl Iterator<Item = &dyn TagMatcher> {
+        self.pipeline_stages().map(|(_, matcher)| matcher)
+    }
+
+    /// Get every matcher we try like [`TagParser::pipeline`][], along with where it came from
+    pub(crate) fn pipeline_stages(&self) -> impl Iterator<Item = (PipelineStage, &dyn TagMatcher)> {
+        #[cfg(feature = "regex")]
+        let patterns = self.patterns.iter().map(|p| p as &dyn TagMatcher);
+        #[cfg(not(feature = "regex"))]
+        let patterns = std::iter::empty();
+        let patterns = patterns
+            .enumerate()
+            .map(|(idx, p)| (PipelineStage::Pattern(idx), p));
+        let matchers = self.matchers.iter().enumerate();
+        let matchers = matchers.map(|(idx, m)| (PipelineStage::Matcher(idx), m.as_ref()));
+        let formats =
+            (self.formats.iter()).map(|f| (PipelineStage::Format(*f), f as &dyn TagMatcher));
+        patterns
+            .chain(matchers)
+            .chain(formats)
+            .chain(std::iter::once((
+                PipelineStage::Unified,
+                &UnifiedMatcher as &dyn TagMatcher,
+            )))
+    }
+
+    /// Find every way our matchers can read a tag, in the order parsing tries them
+    ///
+    /// This doesn't check the versions, so readings that [`TagParser::parse_tag`][]
+    /// would move past are included; it's for explaining how a tag was (or wasn't) read.
+    pub fn tag_matches(&self, packages: &[Package], tag: &str) -> Vec<TagMatch> {
+        let cx = MatchContext::new(self, packages);
+        self.pipeline()
+            .filter_map(|matcher| cx.run(matcher, tag))
+            .collect()
+    }
+}
@@ -0,0 +1,407 @@
+//! Checking a new tag against the tags a repo already has.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::errors::{TagError, TagResult};
+use crate::{
+    AnnouncementKind, Package, PartialAnnouncementTag, PlaceholderPolicy, ScopePreference,
+    TagParser, TagWarning, UnifiedVersionPolicy, UnversionedPolicy, Version, VersionMatchPolicy,
+};
+
+/// What to do when a new tag doesn't announce a newer version than existing tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RegressionPolicy {
+    /// Fail with [`TagError::VersionRegression`][]
+    #[default]
+    Error,
+    /// Accept the tag, but record a [`TagWarning::VersionRegression`][]
+    Warn,
+    /// Accept the tag without complaint
+    Ignore,
+}
+
+/// Whether prereleases count as releases, see [`TagParser::latest_releases`][]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrereleasePolicy {
+    /// Only final releases count
+    #[default]
+    Skip,
+    /// Prereleases count too
+    Include,
+}
+
+impl TagParser {
+    /// Parse `new_tag`, and check that it announces a newer version than any of
+    /// `existing_tags` for the same package (or unified release)
+    ///
+    /// Like in [`TagParser::latest_releases`][], unified releases count as releases of
+    /// every package: a package's tag has to be newer than the unified ones too, and a
+    /// unified tag has to be newer than the tags of every package it selects (see
+    /// [`TagParser::selected_packages`][]). Re-announcing an existing version (even
+    /// spelled differently) counts as a regression. What happens then depends on
+    /// [`TagParser::regressions`][].
+    pub fn check_not_regression<I>(
+        &self,
+        packages: &[Package],
+        new_tag: &str,
+        existing_tags: I,
+    ) -> TagResult<PartialAnnouncementTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut announcing = self.parse_tag(packages, new_tag)?;
+        let Some((series, version)) = release_series(&announcing.release) else {
+            return Ok(announcing);
+        };
+        let version = version.clone();
+
+        let newest = self.newest_by_series(packages, existing_tags, |_| true);
+        let mut releases = vec![series, None];
+        if series.is_none() {
+            let selected = self.selected_packages(&announcing, packages);
+            releases.extend(selected.into_iter().map(Some));
+        }
+        let Some(existing) = self.newest_among(&newest, releases).cloned() else {
+            return Ok(announcing);
+        };
+        if self.cmp_announced(&announcing, &existing).is_gt() {
+            return Ok(announcing);
+        }
+        let existing_version = existing.announced_version();
+        let existing_tag = existing.tag;
+        match self.regressions {
+            RegressionPolicy::Error => Err(TagError::VersionRegression {
+                tag: new_tag.to_owned(),
+                version,
+                existing_tag,
+                existing_version,
+            }),
+            RegressionPolicy::Warn => {
+                announcing.warnings.push(TagWarning::VersionRegression {
+                    version,
+                    existing_tag,
+                    existing_version,
+                });
+                Ok(announcing)
+            }
+            RegressionPolicy::Ignore => Ok(announcing),
+        }
+    }
+
+    /// Parse `new_tag`, and if it's a final release, check that `existing_tags` include a
+    /// prerelease of the same version for the same package (or unified release)
+    ///
+    /// This only checks anything if [`TagParser::require_prereleases`][] is set. Any
+    /// prerelease counts, whatever its label, and build metadata is ignored.
+    pub fn check_prerelease_first<I>(
+        &self,
+        packages: &[Package],
+        new_tag: &str,
+        existing_tags: I,
+    ) -> TagResult<PartialAnnouncementTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let announcing = self.parse_tag(packages, new_tag)?;
+        if !self.require_prereleases {
+            return Ok(announcing);
+        }
+        let Some((series, version)) = release_series(&announcing.release) else {
+            return Ok(announcing);
+        };
+        if !version.pre.is_empty() {
+            return Ok(announcing);
+        }
+        let leads_up = |candidate: &Version| {
+            !candidate.pre.is_empty()
+                && (candidate.major, candidate.minor, candidate.patch)
+                    == (version.major, version.minor, version.patch)
+        };
+        let had_prerelease = self
+            .parse_existing(packages, existing_tags)
+            .any(|existing| {
+                release_series(&existing.release)
+                    .is_some_and(|(s, candidate)| s == series && leads_up(candidate))
+            });
+        if had_prerelease {
+            Ok(announcing)
+        } else {
+            Err(TagError::MissingPrerelease {
+                tag: new_tag.to_owned(),
+                version: version.clone(),
+            })
+        }
+    }
+
+    /// Remove announcements of a release that an earlier announcement already made
+    ///
+    /// Releases are compared with [`PartialAnnouncementTag::same_release`][] under
+    /// [`TagParser::build_metadata`][], and the first announcement of each release is kept.
+    pub fn dedup_announcements(
+        &self,
+        announcements: impl IntoIterator<Item = PartialAnnouncementTag>,
+    ) -> Vec<PartialAnnouncementTag> {
+        let mut unique: Vec<PartialAnnouncementTag> = vec![];
+        for announcing in announcements {
+            if !unique
+                .iter()
+                .any(|seen| seen.same_release(&announcing, self.build_metadata))
+            {
+                unique.push(announcing);
+            }
+        }
+        unique
+    }
+
+    /// Compare announcements for sorting them newest release first
+    ///
+    /// Unified releases come first, then each package's releases (by index), then
+    /// anything without a version (like channels and reserved tags). Within each of
+    /// those, newer versions come first, with prereleases after the release they lead up
+    /// to (`1.0.0`, then `1.0.0-rc.2`, then `1.0.0-rc.1`) and build metadata compared
+    /// according to [`TagParser::build_metadata`][]. Higher epochs always come first, and
+    /// higher build numbers come before lower ones of the same version.
+    pub fn cmp_newest_first(
+        &self,
+        a: &PartialAnnouncementTag,
+        b: &PartialAnnouncementTag,
+    ) -> Ordering {
+        match (release_series(&a.release), release_series(&b.release)) {
+            (Some((a_series, _)), Some((b_series, _))) => a_series
+                .cmp(&b_series)
+                .then_with(|| self.cmp_announced(b, a)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Sort announcements newest release first, grouped by package
+    ///
+    /// See [`TagParser::cmp_newest_first`][]. Announcements that compare equal keep
+    /// their order.
+    pub fn sort_newest_first(&self, announcements: &mut [PartialAnnouncementTag]) {
+        announcements.sort_by(|a, b| self.cmp_newest_first(a, b));
+    }
+
+    /// Find the release just before `announcing` for the same package (or unified
+    /// release) among `announcements`
+    ///
+    /// If several announcements have that version, the first one wins.
+    pub fn previous_announcement<'a>(
+        &self,
+        announcing: &PartialAnnouncementTag,
+        announcements: &'a [PartialAnnouncementTag],
+    ) -> Option<&'a PartialAnnouncementTag> {
+        let (series, _) = release_series(&announcing.release)?;
+        let mut previous: Option<&PartialAnnouncementTag> = None;
+        for candidate in announcements {
+            if release_series(&candidate.release).is_some_and(|(s, _)| s == series)
+                && self.cmp_announced(candidate, announcing).is_lt()
+                && previous.is_none_or(|best| self.cmp_announced(candidate, best).is_gt())
+            {
+                previous = Some(candidate);
+            }
+        }
+        previous
+    }
+
+    /// Compare what two announcements (for the same series) announce, oldest first
+    ///
+    /// Epochs count first, then versions (per [`TagParser::build_metadata`][]), then
+    /// build numbers. PEP 440 versions are compared directly, if both have one.
+    pub(crate) fn cmp_announced(
+        &self,
+        a: &PartialAnnouncementTag,
+        b: &PartialAnnouncementTag,
+    ) -> Ordering {
+        #[cfg(feature = "pep440")]
+        if let (Some(a), Some(b)) = (&a.pep440, &b.pep440) {
+            return a.cmp(b);
+        }
+        let version = |announcing: &PartialAnnouncementTag| announcing.release.version().cloned();
+        a.epoch
+            .unwrap_or(0)
+            .cmp(&b.epoch.unwrap_or(0))
+            .then_with(|| match (version(a), version(b)) {
+                (Some(a), Some(b)) => self.build_metadata.compare(&a, &b),
+                (a, b) => a.cmp(&b),
+            })
+            .then_with(|| a.build_number.cmp(&b.build_number))
+    }
+
+    /// Find the newest of `tags` for the given package (or unified release, if None)
+    ///
+    /// Unified releases count as releases of every package, like in
+    /// [`TagParser::latest_releases`][]. The result always has a version (see
+    /// [`PartialAnnouncementTag::announced_version`][]).
+    ///
+    /// Tags are parsed like [`TagParser::parse_existing`][] does.
+    pub(crate) fn newest_in_series<I>(
+        &self,
+        packages: &[Package],
+        tags: I,
+        series: Option<usize>,
+    ) -> Option<PartialAnnouncementTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let newest = self.newest_by_series(packages, tags, |_| true);
+        self.newest_among(&newest, [series, None]).cloned()
+    }
+
+    /// Find the newest version of each package released by `existing_tags`
+    ///
+    /// Unified releases count as releases of every package. Packages that were never
+    /// released (and [`Package::excluded`][] ones) are left out. Tags are parsed like
+    /// [`TagParser::check_not_regression`][] parses them, so their versions don't have to
+    /// match the packages' current ones.
+    pub fn latest_releases<I>(
+        &self,
+        packages: &[Package],
+        existing_tags: I,
+        prereleases: PrereleasePolicy,
+    ) -> BTreeMap<usize, Version>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let newest = self.newest_by_series(packages, existing_tags, |announcing| {
+            prereleases == PrereleasePolicy::Include || !announcing.prerelease
+        });
+        packages
+            .iter()
+            .enumerate()
+            .filter(|(_, package)| !package.excluded)
+            .filter_map(|(idx, _)| {
+                let best = self.newest_among(&newest, [Some(idx), None])?;
+                Some((idx, best.announced_version()))
+            })
+            .collect()
+    }
+
+    /// Find the newest release among several series of [`TagParser::newest_by_series`][]
+    ///
+    /// The earliest series wins ties.
+    fn newest_among<'a>(
+        &self,
+        newest: &'a BTreeMap<Option<usize>, PartialAnnouncementTag>,
+        series: impl IntoIterator<Item = Option<usize>>,
+    ) -> Option<&'a PartialAnnouncementTag> {
+        series
+            .into_iter()
+            .filter_map(|series| newest.get(&series))
+            .reduce(|best, candidate| {
+                if self.cmp_announced(candidate, best).is_gt() {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Find the newest of `tags` (that `keep` keeps) for each package and for unified releases
+    ///
+    /// Unified releases are under None. Every result has a version.
+    fn newest_by_series<I>(
+        &self,
+        packages: &[Package],
+        tags: I,
+        keep: impl Fn(&PartialAnnouncementTag) -> bool,
+    ) -> BTreeMap<Option<usize>, PartialAnnouncementTag>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut newest = BTreeMap::<Option<usize>, PartialAnnouncementTag>::new();
+        for announcing in self.parse_existing(packages, tags) {
+            let Some((series, _)) = release_series(&announcing.release) else {
+                continue;
+            };
+            if !keep(&announcing) {
+                continue;
+            }
+            if newest
+                .get(&series)
+                .is_none_or(|best| self.cmp_announced(&announcing, best).is_gt())
+            {
+                newest.insert(series, announcing);
+            }
+        }
+        newest
+    }
+
+    /// Parse a repo's existing tags, skipping any that don't parse
+    ///
+    /// Tags are parsed with [`TagParser::existing_tag_parser`][], since old tags will
+    /// naturally disagree with the workspace's current versions (and may predate its
+    /// current policies).
+    pub(crate) fn parse_existing<'a, I>(
+        &'a self,
+        packages: &'a [Package],
+        tags: I,
+    ) -> impl Iterator<Item = PartialAnnouncementTag> + 'a
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        I::IntoIter: 'a,
+    {
+        let parser = self.existing_tag_parser();
+        tags.into_iter()
+            .filter_map(move |tag| parser.parse_tag(packages, tag.as_ref()).ok())
+    }
+}
+
+impl TagParser {
+    /// Get a parser for tags that were made in the past, see [`TagParser::parse_existing`][]
+    ///
+    /// Old tags were made against old versions of the workspace, under whatever policies
+    /// were in place then, so only the settings that say how tags are read still apply.
+    /// The ones that check a tag against the workspace's current state (its versions,
+    /// [`TagParser::version_bounds`][], [`TagParser::scope_preference`][], and so on) or
+    /// reject it for its spelling (like
+    /// [`TagParser::reject_legacy_formats`][] and [`TagParser::require_package_component`][])
+    /// are relaxed.
+    pub(crate) fn existing_tag_parser(&self) -> TagParser {
+        TagParser {
+            version_match: VersionMatchPolicy::Ignore,
+            directional_version_mismatch: false,
+            unified_versions: UnifiedVersionPolicy::Unchecked,
+            unversioned_packages: UnversionedPolicy::Adopt,
+            placeholder_versions: PlaceholderPolicy::Allow,
+            version_bounds: vec![],
+            require_package_component: false,
+            reject_legacy_formats: false,
+            deprecate_legacy_formats: false,
+            scope_preference: ScopePreference::Package,
+            ..self.clone()
+        }
+    }
+}
+
+impl PartialAnnouncementTag {
+    /// Get the version of an announcement that's known to have one
+    pub(crate) fn announced_version(&self) -> Version {
+        self.release
+            .version()
+            .cloned()
+            .expect("only releases with a version are in a series")
+    }
+}
+
+/// Get which package a release is for (None for unified releases) and its version
+pub(crate) fn release_series(release: &AnnouncementKind) -> Option<(Option<usize>, &Version)> {
+    match release {
+        AnnouncementKind::Unified(version) => Some((None, version)),
+        AnnouncementKind::Package { idx, version } => Some((Some(*idx), version)),
+        // Without the packages there's nothing to group unresolved ones by
+        AnnouncementKind::None
+        | AnnouncementKind::Reserved { .. }
+        | AnnouncementKind::UnresolvedPackage { .. }
+        | AnnouncementKind::Channel { .. } => None,
+    }
+}
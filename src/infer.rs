@@ -0,0 +1,135 @@
+//! Working out which tag to use when there isn't one to parse.
+
+use std::fmt;
+
+use crate::errors::{TagError, TagResult};
+use crate::{Package, PartialAnnouncementTag, TagParser, Version};
+
+/// What an inferred tag should announce, see [`TagParser::infer_tag`][]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InferScope {
+    /// Every releasable package at once, as a unified release
+    #[default]
+    All,
+    /// A single package (by idx)
+    Package(usize),
+}
+
+/// Releasable packages grouped by their version, see [`TagParser::version_report`][]
+///
+/// The [`Display`][fmt::Display] impl renders something like "3 packages at 1.2.3, 2 at 0.9.0".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VersionReport {
+    /// The groups, biggest first (ties go to the newer version)
+    pub clusters: Vec<VersionCluster>,
+}
+
+/// Packages that share a version
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VersionCluster {
+    /// The version
+    pub version: Version,
+    /// The names of the packages with that version, in input order
+    pub package_names: Vec<String>,
+    /// The tag to announce each package with on its own, in the same order
+    pub suggested_tags: Vec<String>,
+}
+
+impl VersionReport {
+    /// Whether every package has the same version, so a unified tag would work
+    pub fn is_unified(&self) -> bool {
+        self.clusters.len() <= 1
+    }
+
+    /// Get every suggested tag, biggest cluster first
+    pub fn suggested_tags(&self) -> impl Iterator<Item = &str> {
+        self.clusters
+            .iter()
+            .flat_map(|cluster| cluster.suggested_tags.iter().map(String::as_str))
+    }
+}
+
+impl fmt::Display for VersionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            let count = cluster.package_names.len();
+            match i {
+                0 if count == 1 => write!(f, "1 package at {}", cluster.version)?,
+                0 => write!(f, "{count} packages at {}", cluster.version)?,
+                _ => write!(f, ", {count} at {}", cluster.version)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TagParser {
+    /// Group the releasable packages with versions by their version
+    ///
+    /// This explains why a unified release can't work, and how to tag each package instead.
+    pub fn version_report(&self, packages: &[Package]) -> VersionReport {
+        let mut clusters: Vec<VersionCluster> = vec![];
+        for package in packages.iter().filter(|p| !p.excluded) {
+            let Some(version) = &package.version else {
+                continue;
+            };
+            let tag = self.format_tag(Some(package), version);
+            match clusters.iter_mut().find(|c| &c.version == version) {
+                Some(cluster) => {
+                    cluster.package_names.push(package.name.clone());
+                    cluster.suggested_tags.push(tag);
+                }
+                None => clusters.push(VersionCluster {
+                    version: version.clone(),
+                    package_names: vec![package.name.clone()],
+                    suggested_tags: vec![tag],
+                }),
+            }
+        }
+        clusters.sort_by(|a, b| {
+            b.package_names
+                .len()
+                .cmp(&a.package_names.len())
+                .then_with(|| b.version.cmp(&a.version))
+        });
+        VersionReport { clusters }
+    }
+
+    /// Work out the tag that should be used to announce some packages, and parse it
+    ///
+    /// For [`InferScope::All`][], every releasable package with a version has to have
+    /// the same one (packages without a version are left out). The tag is produced like
+    /// [`TagParser::format_tag`][] does, and then parsed like any other tag, so it gets
+    /// the same checks (and warnings) it would get if it had been passed in.
+    pub fn infer_tag(
+        &self,
+        packages: &[Package],
+        scope: InferScope,
+    ) -> TagResult<PartialAnnouncementTag> {
+        let tag = match scope {
+            InferScope::All => {
+                let report = self.version_report(packages);
+                let version = match report.clusters.as_slice() {
+                    [] => return Err(TagError::NoVersionToInfer { package_name: None }),
+                    [cluster] => &cluster.version,
+                    _ => return Err(TagError::HeterogeneousVersions { report }),
+                };
+                self.format_tag(None, version)
+            }
+            InferScope::Package(idx) => {
+                let package = packages.get(idx);
+                let version = package.and_then(|p| p.version.as_ref()).ok_or_else(|| {
+                    TagError::NoVersionToInfer {
+                        package_name: package.map(|p| p.name.clone()),
+                    }
+                })?;
+                self.format_tag(package, version)
+            }
+        };
+        self.parse_tag(packages, &tag)
+    }
+}
@@ -0,0 +1,152 @@
+//! Lenient parsing for versions that aren't quite semver.
+
+use semver::{Prerelease, Version};
+
+use crate::TagWarning;
+
+/// Something that had to be fixed to turn a version into valid semver
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum VersionFixup {
+    /// Missing minor/patch components were filled in with zeros (`1.2` => `1.2.0`)
+    MissingComponents,
+    /// Leading zeros were removed from components (`1.02.3` => `1.2.3`)
+    LeadingZeros,
+    /// Components after the patch were dropped (`1.2.3.4` => `1.2.3`)
+    ExtraComponents {
+        /// The components that were dropped, like `4`
+        dropped: String,
+    },
+}
+
+/// How a package's versions are written in tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum VersionScheme {
+    /// Semver, or close to it if [`TagParser::lenient_versions`][crate::TagParser::lenient_versions] is on
+    #[default]
+    Semver,
+    /// Close to semver, as if [`TagParser::lenient_versions`][crate::TagParser::lenient_versions] was on
+    Lenient,
+    /// Calendar versions like `2024.01.15` or `24.04`, read as semver (`2024.1.15`, `24.4.0`)
+    ///
+    /// Zero-padded and missing components aren't worth a warning here, but extra ones
+    /// still are.
+    CalVer,
+    /// Semver with an optional epoch in front, like `2!1.2.3`
+    ///
+    /// The epoch ends up in [`PartialAnnouncementTag::epoch`][crate::PartialAnnouncementTag::epoch],
+    /// and a higher epoch is always newer, whatever the version.
+    Epoch,
+    /// Semver with an optional build counter at the end of the prerelease, like
+    /// `1.2.3-build.456` or `1.2.3-rc.1.build.7`
+    ///
+    /// The counter is taken out of the version (so `1.2.3-build.456` is a release of
+    /// 1.2.3, not a prerelease) and ends up in
+    /// [`PartialAnnouncementTag::build_number`][crate::PartialAnnouncementTag::build_number].
+    /// Higher counters are newer builds of the same version.
+    BuildNumber,
+    /// Python versions, like `1.2.3.post1` or `1.2rc1` (see the [`pep440`][crate::pep440]
+    /// module for how they're read)
+    #[cfg(feature = "pep440")]
+    Pep440,
+}
+
+/// The version component of a tag, parsed
+pub(crate) struct TagVersion {
+    /// The version
+    pub(crate) version: Version,
+    /// Anything odd about how the version was written
+    pub(crate) warnings: Vec<TagWarning>,
+    /// The epoch, for [`VersionScheme::Epoch`][]
+    pub(crate) epoch: Option<u64>,
+    /// The build counter, for [`VersionScheme::BuildNumber`][]
+    pub(crate) build_number: Option<u64>,
+    /// The maintenance branch, for [`TagParser::branch_qualifiers`][crate::TagParser::branch_qualifiers]
+    pub(crate) branch: Option<crate::branches::MaintenanceBranch>,
+    /// The PEP 440 version, for [`VersionScheme::Pep440`][]
+    #[cfg(feature = "pep440")]
+    pub(crate) pep440: Option<crate::pep440::Pep440Version>,
+}
+
+/// Split an epoch like `2!` off the front of a version
+pub(crate) fn split_epoch(input: &str) -> (Option<u64>, &str) {
+    let epoch = input
+        .split_once('!')
+        .filter(|(epoch, _)| !epoch.is_empty() && epoch.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|(epoch, rest)| Some((epoch.parse().ok()?, rest)));
+    match epoch {
+        Some((epoch, rest)) => (Some(epoch), rest),
+        None => (None, input),
+    }
+}
+
+/// Take a `build.N` counter off the end of a version's prerelease
+pub(crate) fn split_build_number(mut version: Version) -> (Version, Option<u64>) {
+    let pre = version.pre.as_str();
+    let Some((rest, counter)) = pre.rsplit_once('.') else {
+        return (version, None);
+    };
+    let rest = match rest.strip_suffix("build") {
+        Some("") => "",
+        Some(rest) => match rest.strip_suffix('.') {
+            Some(rest) => rest,
+            None => return (version, None),
+        },
+        None => return (version, None),
+    };
+    let Some(counter) = counter
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| counter.parse().ok())
+        .flatten()
+    else {
+        return (version, None);
+    };
+    version.pre = Prerelease::new(rest).expect("a prefix of a prerelease is a prerelease");
+    (version, Some(counter))
+}
+
+/// Try to make sense of a version that failed to parse as semver
+///
+/// Only the numeric `major.minor.patch` part is fixed up, anything after it
+/// (prerelease or build metadata) still has to be valid semver.
+pub(crate) fn parse_lenient(input: &str) -> Option<(Version, Vec<VersionFixup>)> {
+    let core_len = input.find(['-', '+']).unwrap_or(input.len());
+    let (core, rest) = input.split_at(core_len);
+
+    let mut fixups = vec![];
+    let mut components = core.split('.').collect::<Vec<_>>();
+    if components
+        .iter()
+        .any(|c| c.is_empty() || !c.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    if components.len() < 3 {
+        fixups.push(VersionFixup::MissingComponents);
+        components.resize(3, "0");
+    }
+    if components.len() > 3 {
+        fixups.push(VersionFixup::ExtraComponents {
+            dropped: components.split_off(3).join("."),
+        });
+    }
+    for component in &mut components {
+        let trimmed = component.trim_start_matches('0');
+        let trimmed = if trimmed.is_empty() { "0" } else { trimmed };
+        if trimmed != *component {
+            *component = trimmed;
+            if !fixups.contains(&VersionFixup::LeadingZeros) {
+                fixups.push(VersionFixup::LeadingZeros);
+            }
+        }
+    }
+
+    let version = format!("{}{rest}", components.join("."))
+        .parse::<Version>()
+        .ok()?;
+    Some((version, fixups))
+}
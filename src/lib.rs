@@ -4,31 +4,206 @@
 //! # axotag
 //!
 //! This library contains tag-parsing code for use with cargo-dist.
+//!
+//! # Ordering
+//!
+//! Everything axotag returns is deterministic, so it's safe to snapshot. Collections
+//! that correspond to some input (like the results of [`TagParser::parse_tags`][]) keep
+//! the order of that input, and everything else is sorted (maps are `BTreeMap`s, and
+//! lists of packages are sorted by name and then version). When several packages
+//! could match the same tag equally well, the one that comes first in the input wins.
+
+use std::sync::Arc;
 
-use errors::{TagError, TagResult};
+pub use bounds::VersionBounds;
+use branches::MaintenanceBranch;
+use compiled::NameIndex;
+use errors::{PackageVersion, TagError, TagResult};
+use explain::{AttemptOutcome, ParseAttempt, ParseTrace};
+pub use format::{TagFormat, TagStyle};
+pub use grammar::TagMatcher;
+use grammar::{Confidence, MatchContext, TagMatch};
+pub use history::{PrereleasePolicy, RegressionPolicy};
+pub use lenient::VersionScheme;
+use lenient::{TagVersion, VersionFixup};
+use matching::package_prefixes;
+pub use metadata::{SignaturePolicy, TagMetadata};
+pub use namespaces::Namespace;
+#[cfg(feature = "regex")]
+pub use pattern::TagPattern;
+pub use presets::TagPreset;
+pub use refs::TagOrigin;
+pub use segments::SegmentKind;
 pub use semver;
 pub use semver::Version;
+pub use similarity::Similarity;
+pub use warnings::{RejectionReason, TagWarning};
 
+pub mod actions;
+pub mod bounds;
+pub mod branches;
+pub mod bump;
+pub mod compiled;
+pub mod conflicts;
+#[cfg(feature = "corpus")]
+pub mod corpus;
+pub mod denylist;
+pub mod describe;
+pub mod diff;
+pub mod disambiguate;
+pub mod ecosystems;
 pub mod errors;
+pub mod explain;
+pub mod fixit;
+pub mod format;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+pub mod grammar;
+pub mod history;
+pub mod infer;
+pub mod lenient;
+pub mod lint;
+pub mod matching;
+pub mod metadata;
+pub mod namespaces;
+#[cfg(feature = "regex")]
+pub mod pattern;
+#[cfg(feature = "pep440")]
+pub mod pep440;
+pub mod plan;
+pub mod presets;
+pub mod refs;
+pub mod relevance;
+pub mod roundtrip;
+pub mod sanitize;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod segments;
+pub mod similarity;
+pub mod snapshot;
+pub mod stats;
 #[cfg(test)]
 mod tests;
+pub mod title;
+mod trace;
+#[cfg(feature = "unicode")]
+mod unicode;
+pub mod warnings;
 
 /// Represents an opaque package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Package {
     /// The package's name
     pub name: String,
     /// The package's version, if specified
     pub version: Option<Version>,
+    /// The package's directory relative to the root of the workspace (like `crates/my-app`),
+    /// if known
+    ///
+    /// This is used by [`TagFormat::Path`][].
+    pub path: Option<String>,
+    /// Other names tags might use for this package
+    ///
+    /// Useful when the crate name differs from what tags call it (crate `foo-cli`, tag
+    /// `foo-v1.2.3`), or to keep a renamed package's old tags parseable. Aliases are
+    /// only used for parsing, tags are always produced with the package's name.
+    pub aliases: Vec<String>,
+    /// Whether this package should never be announced (like cargo-dist's `dist = false`)
+    ///
+    /// Tags naming this package fail with [`TagError::PackageNotReleasable`][], and unified
+    /// releases don't select it.
+    pub excluded: bool,
+    /// How this package's versions are written in tags
+    ///
+    /// Tags picking this package parse their version with this scheme. Unified releases
+    /// use the scheme every package agrees on (or semver, if they don't).
+    pub version_scheme: VersionScheme,
+    /// The sub-project this package belongs to (like `js` or `rust`), if it's in one of
+    /// [`TagParser::namespaces`][]
+    pub namespace: Option<String>,
+}
+
+impl Package {
+    /// Get this package's path with `/` separators and no trailing slash
+    pub(crate) fn normalized_path(&self) -> Option<String> {
+        let path = self.path.as_ref()?.replace('\\', "/");
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        (!path.is_empty()).then(|| path.to_owned())
+    }
+
+    /// Whether this package's name looks like a version (`v1`, `2`, `1.2.3`)
+    ///
+    /// Such names can make tags ambiguous, see [`TagParser::require_unambiguous_package_names`][].
+    pub fn has_version_like_name(&self) -> bool {
+        let name = self.name.strip_prefix('v').unwrap_or(&self.name);
+        name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_digit() || c == '.')
+    }
+
+    /// Whether this package has a placeholder version (`0.0.0`)
+    ///
+    /// Some workspaces don't bother versioning packages they never intend to release.
+    pub fn has_placeholder_version(&self) -> bool {
+        self.version.as_ref().is_some_and(is_placeholder_version)
+    }
+}
+
+/// Check if a version is a placeholder (`0.0.0`, ignoring build metadata)
+pub(crate) fn is_placeholder_version(version: &Version) -> bool {
+    version.major == 0 && version.minor == 0 && version.patch == 0 && version.pre.is_empty()
 }
 
 /// details on what we're announcing (partially computed)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PartialAnnouncementTag {
     /// The full tag
     pub tag: String,
     /// The release
-    pub release: ReleaseType,
+    pub release: AnnouncementKind,
     /// whether we're prereleasing
     pub prerelease: bool,
+    /// The first of [`TagParser::formats`][] that matched the tag
+    pub format: Option<TagFormat>,
+    /// How the tag was actually spelled
+    pub style: Option<TagStyle>,
+    /// Whether the announced package had no version, so it adopts the tag's version
+    ///
+    /// See [`UnversionedPolicy`][].
+    pub version_adopted: bool,
+    /// The alias of the package the tag used, if it didn't use the package's name
+    ///
+    /// See [`Package::aliases`][].
+    pub alias: Option<String>,
+    /// The epoch of the version, if its package uses [`VersionScheme::Epoch`][] and the
+    /// tag had one (`2` for `v2!1.2.3`)
+    pub epoch: Option<u64>,
+    /// The build counter of the version, if its package uses
+    /// [`VersionScheme::BuildNumber`][] and the tag had one (`456` for `v1.2.3-build.456`)
+    pub build_number: Option<u64>,
+    /// The maintenance branch the tag was made on, if it names one
+    ///
+    /// See [`TagParser::branch_qualifiers`][] and [`TagParser::series_branches`][].
+    pub branch: Option<MaintenanceBranch>,
+    /// The PEP 440 version, if its package uses `VersionScheme::Pep440`
+    ///
+    /// When both announcements have one, it's what they're ordered by.
+    #[cfg(feature = "pep440")]
+    pub pep440: Option<pep440::Pep440Version>,
+    /// Where the tag came from, if it was extracted from a ref or URL
+    ///
+    /// See [`TagParser::parse_tag_input`][].
+    pub origin: TagOrigin,
+    /// The namespace the tag started with, if it was in one of [`TagParser::namespaces`][]
+    ///
+    /// A unified release in a namespace only releases that namespace's packages.
+    pub namespace: Option<String>,
+    /// Anything odd we noticed about the tag that wasn't worth failing over
+    pub warnings: Vec<TagWarning>,
 }
 
 impl Default for PartialAnnouncementTag {
@@ -36,19 +211,133 @@ impl Default for PartialAnnouncementTag {
     fn default() -> PartialAnnouncementTag {
         PartialAnnouncementTag {
             tag: String::new(),
-            release: ReleaseType::None,
+            release: AnnouncementKind::None,
             prerelease: false,
+            format: None,
+            style: None,
+            version_adopted: false,
+            alias: None,
+            epoch: None,
+            build_number: None,
+            branch: None,
+            #[cfg(feature = "pep440")]
+            pep440: None,
+            origin: TagOrigin::Tag,
+            namespace: None,
+            warnings: vec![],
         }
     }
 }
 
-/// which type of release we're announcing
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum ReleaseType {
+impl PartialAnnouncementTag {
+    /// Get the build metadata of the announced version (`build.42` in `v1.2.3+build.42`), if any
+    pub fn build_metadata(&self) -> Option<&semver::BuildMetadata> {
+        self.release
+            .version()
+            .map(|v| &v.build)
+            .filter(|b| !b.is_empty())
+    }
+
+    /// Get the arbitrary prefix of the tag (like `releases` for `releases/v1.2.3`), if it had one
+    pub fn prefix(&self) -> Option<&str> {
+        self.style.as_ref().and_then(|style| style.prefix())
+    }
+
+    /// Get the indices of the packages this announcement would release, in input order
+    ///
+    /// A unified release selects every package with exactly that version (build metadata
+    /// included, see [`TagParser::selected_packages`][] to respect
+    /// [`TagParser::build_metadata`][]), a package release selects that package, and a
+    /// channel selects its package (or every package, if it's a unified channel). Unified
+    /// releases in a namespace only select that namespace's packages, and
+    /// [`Package::excluded`][] packages are never selected.
+    pub fn selected_packages(&self, packages: &[Package]) -> Vec<usize> {
+        self.selected_packages_under(packages, BuildMetadataPolicy::Exact)
+    }
+
+    /// [`PartialAnnouncementTag::selected_packages`][], comparing unified releases' versions
+    /// to the packages' under the given policy
+    pub(crate) fn selected_packages_under(
+        &self,
+        packages: &[Package],
+        policy: BuildMetadataPolicy,
+    ) -> Vec<usize> {
+        let mut selected = match &self.release {
+            AnnouncementKind::None
+            | AnnouncementKind::Reserved { .. }
+            | AnnouncementKind::UnresolvedPackage { .. } => vec![],
+            AnnouncementKind::Unified(version) => packages
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| {
+                    p.version
+                        .as_ref()
+                        .is_some_and(|v| policy.compare(v, version).is_eq())
+                })
+                .map(|(idx, _)| idx)
+                .collect(),
+            AnnouncementKind::Package { idx, .. }
+            | AnnouncementKind::Channel { idx: Some(idx), .. } => {
+                vec![*idx]
+            }
+            AnnouncementKind::Channel { idx: None, .. } => (0..packages.len()).collect(),
+        };
+        selected.retain(|idx| {
+            packages.get(*idx).is_some_and(|p| {
+                !p.excluded && (self.namespace.is_none() || p.namespace == self.namespace)
+            })
+        });
+        selected
+    }
+
+    /// Whether this announces the same release as another announcement, however it was spelled
+    ///
+    /// With [`BuildMetadataPolicy::Ignore`][], versions that only differ in build metadata
+    /// are the same release (`v1.2.3+build.1` and `v1.2.3+build.2`).
+    pub fn same_release(
+        &self,
+        other: &PartialAnnouncementTag,
+        policy: BuildMetadataPolicy,
+    ) -> bool {
+        #[cfg(feature = "pep440")]
+        if let (Some(a), Some(b)) = (&self.pep440, &other.pep440) {
+            if a != b {
+                return false;
+            }
+        }
+        if self.epoch.unwrap_or(0) != other.epoch.unwrap_or(0)
+            || self.build_number != other.build_number
+        {
+            return false;
+        }
+        match (&self.release, &other.release) {
+            (AnnouncementKind::Unified(a), AnnouncementKind::Unified(b)) => {
+                policy.compare(a, b).is_eq()
+            }
+            (
+                AnnouncementKind::Package { idx, version },
+                AnnouncementKind::Package {
+                    idx: other_idx,
+                    version: other_version,
+                },
+            ) => idx == other_idx && policy.compare(version, other_version).is_eq(),
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// What a tag announces, see [`PartialAnnouncementTag::release`][]
+///
+/// Each kind carries exactly what it needs (like the index and version of a package
+/// release), so there's no way to have, say, both a unified version and a package.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AnnouncementKind {
     /// none
     None,
     /// unified release
-    Version(Version),
+    Unified(Version),
     /// package
     Package {
         /// The index of the package from the passed in list
@@ -56,120 +345,1604 @@ pub enum ReleaseType {
         /// The version of the package (in case the package didn't yet have one)
         version: Version,
     },
+    /// one of [`TagParser::reserved_tags`][], which is never a release
+    Reserved {
+        /// The reserved name that matched
+        name: String,
+    },
+    /// a package we don't have, because there were no packages to match against
+    ///
+    /// See [`EmptyWorkspacePolicy::Unresolved`][].
+    UnresolvedPackage {
+        /// The name of the package, as the tag spelled it
+        name: String,
+        /// The version of the package
+        version: Version,
+    },
+    /// a floating channel like `latest` or `stable`, which doesn't name a version
+    Channel {
+        /// The index of the package from the passed in list, if the channel was package-scoped
+        idx: Option<usize>,
+        /// The name of the channel
+        channel: String,
+    },
+}
+
+/// The old name of [`AnnouncementKind`][]
+#[deprecated = "renamed to AnnouncementKind"]
+pub type ReleaseType = AnnouncementKind;
+
+impl AnnouncementKind {
+    /// Get the version being released, if there is one
+    pub fn version(&self) -> Option<&Version> {
+        match self {
+            AnnouncementKind::None
+            | AnnouncementKind::Reserved { .. }
+            | AnnouncementKind::Channel { .. } => None,
+            AnnouncementKind::Unified(version)
+            | AnnouncementKind::Package { version, .. }
+            | AnnouncementKind::UnresolvedPackage { version, .. } => Some(version),
+        }
+    }
+
+    /// Get the index of the package being released, if the release is for one of ours
+    ///
+    /// Package-scoped channels count, unified releases and unresolved packages don't.
+    pub fn package_idx(&self) -> Option<usize> {
+        match self {
+            AnnouncementKind::Package { idx, .. }
+            | AnnouncementKind::Channel { idx: Some(idx), .. } => Some(*idx),
+            _ => None,
+        }
+    }
 }
 
 /// Do the actual parsing logic for a tag
 ///
-/// If `tag` is None, then we had no --tag to parse, and need to do inference.
-/// The return value is then essentially a default/empty PartialAnnouncementTag
-/// which later passes will fill in.
+/// If there's no tag to parse, [`TagParser::infer_tag`][] can work out which one to use.
+///
+/// This uses the default [`TagParser`][], see that type if you need to customize parsing.
 pub fn parse_tag(packages: &[Package], tag: &str) -> TagResult<PartialAnnouncementTag> {
-    // First thing's first: if they gave us an announcement tag then we should try to parse it
-    let mut announcing_package = None;
-    let announcing_version;
-    let announcing_prerelease;
-    let announcement_tag = tag.to_owned();
-    let mut tag_suffix;
-    // Check if we're using `/`'s to delimit things
-    if let Some((prefix, suffix)) = announcement_tag.rsplit_once('/') {
-        // We're at least in "blah/v1.0.0" format
-        let maybe_package = if let Some((_prefix, package)) = prefix.rsplit_once('/') {
-            package
-        } else {
-            // There's only one `/`, assume the whole prefix could be a package name
-            prefix
+    TagParser::default().parse_tag(packages, tag)
+}
+
+/// Check whether a tag would announce any of `packages`, without fully parsing it
+///
+/// This uses the default [`TagParser`][], see [`TagParser::is_relevant_tag`][].
+pub fn is_relevant_tag(packages: &[Package], tag: &str) -> bool {
+    TagParser::default().is_relevant_tag(packages, tag)
+}
+
+/// Parse a tag, returning the recoverable oddities we noticed separately
+///
+/// See [`TagParser::parse_tag_with_warnings`][].
+pub fn parse_tag_with_warnings(
+    packages: &[Package],
+    tag: &str,
+) -> TagResult<(PartialAnnouncementTag, Vec<TagWarning>)> {
+    TagParser::default().parse_tag_with_warnings(packages, tag)
+}
+
+/// Settings for parsing (and producing) tags
+///
+/// A TagParser never changes while parsing (any hooks, like [`Similarity`][], must be
+/// `Send + Sync`), so one parser per repo can be shared behind an `Arc` across threads.
+#[derive(Debug, Clone)]
+pub struct TagParser {
+    /// The formats we accept, in order of priority
+    ///
+    /// Parsing tries each of these in order and reports the first one that matched,
+    /// formatting always uses the first one. Repos migrating between conventions
+    /// can list their new format first and their old one after it.
+    pub formats: Vec<TagFormat>,
+    /// Custom patterns to try before any of the [`TagParser::formats`][], in order of priority
+    ///
+    /// This is the escape hatch for conventions the formats don't cover.
+    #[cfg(feature = "regex")]
+    pub patterns: Vec<TagPattern>,
+    /// Custom matchers to try after any `TagParser::patterns` and before any of the
+    /// [`TagParser::formats`][], in order of priority
+    ///
+    /// For conventions a regex can't describe. See [`grammar`][] for how they fit in.
+    pub matchers: Vec<Arc<dyn TagMatcher>>,
+    /// Floating tags like `latest` or `my-app-stable` to recognize as
+    /// [`AnnouncementKind::Channel`][] instead of failing to parse their version
+    pub channels: Vec<String>,
+    /// Whether to recognize moving `vN` and `vN.N` tags (like GitHub Actions use) as
+    /// [`AnnouncementKind::Channel`][]s
+    ///
+    /// See [`PartialAnnouncementTag::moving_alias`][] to classify them, and
+    /// [`TagParser::moving_alias_tags`][] to produce them.
+    pub moving_aliases: bool,
+    /// The arbitrary prefixes tags may have (like `releases/`), or None to allow any
+    ///
+    /// Tags with any other prefix fail with [`TagError::UnexpectedPrefix`][]. Allowed
+    /// prefixes don't get a [`TagWarning::UnusualPrefix`][], and the trailing slash is
+    /// optional. See [`PartialAnnouncementTag::prefix`][] to route tags by their prefix.
+    pub allowed_prefixes: Option<Vec<String>>,
+    /// Prerelease qualifiers like `lts` that name a maintenance branch instead of a
+    /// prerelease
+    ///
+    /// `v1.2.4-lts` is then a release of 1.2.4 on the `lts` branch (see
+    /// [`PartialAnnouncementTag::branch`][]), and `v1.2.4-rc.1.lts` a prerelease on it.
+    pub branch_qualifiers: Vec<String>,
+    /// The characters that can separate a package from its version in
+    /// [`TagFormat::Dash`][] tags (`my_app_1.2.3` with `_`)
+    ///
+    /// Any of them is accepted, and the first one is used to produce tags. If this is
+    /// empty, `-` is used.
+    pub package_separators: Vec<char>,
+    /// Whether prefixes that name a series, like `release-1.x/` or `1.2.x/`, are
+    /// maintenance branches
+    ///
+    /// These end up in [`PartialAnnouncementTag::branch`][] instead of getting a
+    /// [`TagWarning::UnusualPrefix`][] (and are allowed whatever
+    /// [`TagParser::allowed_prefixes`][] says). Tags whose version isn't in the series fail
+    /// with [`TagError::OutsideBranchSeries`][].
+    pub series_branches: bool,
+    /// Which kind of thing wins when a slash-delimited segment of a tag could be several
+    ///
+    /// With a package named `nightly` and `nightly` in [`TagParser::allowed_prefixes`][],
+    /// `nightly/v1.2.3` could be a release of that package or a unified release. The first
+    /// kind in this list that the segment could be wins (and gets a
+    /// [`TagWarning::AmbiguousSegment`][]), and if none of them are the tag fails with
+    /// [`TagError::AmbiguousSegment`][]. This is empty by default, so ambiguous tags always
+    /// fail; [`SegmentKind::DEFAULT_ORDER`][] (packages, then branches, then prefixes) is
+    /// the suggested order. See [`segments`][] for what counts as each kind.
+    pub segment_order: Vec<SegmentKind>,
+    /// Sub-projects whose packages are tagged under their own namespace
+    ///
+    /// Tags starting with a namespace and a slash (`js/my-app@1.2.3`) are parsed against
+    /// just the packages in it (see [`Package::namespace`][]), with the namespace's parser.
+    /// Other tags are parsed against every package, as usual. [`TagParser::format_tag`][]
+    /// puts a package's tags in its namespace.
+    pub namespaces: Vec<Namespace>,
+    /// Tags like `nightly` or `edge` that must never be read as releases
+    ///
+    /// These have to match the whole tag, and parse as [`AnnouncementKind::Reserved`][].
+    pub reserved_tags: Vec<String>,
+    /// Tags that belong to other automation in the repo, like `latest` or `deploy-*`
+    ///
+    /// `*` matches any run of characters, and otherwise entries have to match the whole
+    /// tag. Tags matching one fail to parse with [`TagError::DeniedTag`][], and
+    /// [`TagParser::try_format_tag`][] refuses to produce them.
+    pub denied_tags: Vec<String>,
+    /// Whether channel tags should be resolved to the current version of the workspace
+    ///
+    /// Package-scoped channels resolve to that package's version, unified channels
+    /// resolve if every package agrees on a version. Channels that can't be resolved
+    /// are left as [`AnnouncementKind::Channel`][].
+    pub resolve_channels: bool,
+    /// Whether to reject tags that only match one of the later (legacy) [`TagParser::formats`][]
+    ///
+    /// The error will tell the user how the tag should be spelled in the first format.
+    /// Leave this off to merely report which format matched (e.g. when auditing old tags).
+    pub reject_legacy_formats: bool,
+    /// Whether tags that only match one of the later (legacy) [`TagParser::formats`][]
+    /// get a [`TagWarning::DeprecatedTagFormat`][], saying how to spell them in the first
+    ///
+    /// This is for the transition period of a migration, when old tags still have to work
+    /// but tooling should nudge people toward the new format. [`TagParser::reject_legacy_formats`][]
+    /// wins if both are set.
+    pub deprecate_legacy_formats: bool,
+    /// What to do when a tag claims a different version than its package has
+    pub version_match: VersionMatchPolicy,
+    /// Whether mismatched versions should say which way they're off
+    ///
+    /// Tags older than their package fail with [`TagError::StaleTagVersion`][], and tags
+    /// newer than it with [`TagError::TagVersionAhead`][], instead of
+    /// [`TagError::ContradictoryTagVersion`][] (which is still used when only build
+    /// metadata differs). This only matters with [`VersionMatchPolicy::Error`][].
+    pub directional_version_mismatch: bool,
+    /// Whether to accept common non-semver versions like `v1.2`, `v1.02.3`, and `1.2.3.4`
+    ///
+    /// These get normalized to semver, with a [`TagWarning::LenientVersion`][] recording
+    /// what had to be fixed.
+    pub lenient_versions: bool,
+    /// Whether versions in tags need a `v` prefix (`v1.2.3` vs `1.2.3`)
+    ///
+    /// [`TagParser::format_tag`][] uses a `v` prefix unless this is [`VPrefixPolicy::Forbid`][].
+    pub v_prefix: VPrefixPolicy,
+    /// How to treat build metadata (`v1.2.3+build.42`) when comparing versions
+    ///
+    /// This applies to comparing a tag against its package's version, and to comparing
+    /// tags against each other (like [`TagParser::check_not_regression`][] and
+    /// [`TagParser::dedup_announcements`][] do).
+    pub build_metadata: BuildMetadataPolicy,
+    /// Whether to reject tags that are ambiguous because a package name looks like a version
+    ///
+    /// With a package named `v1`, the tag `v1/v1.0.0` could be a release of that package,
+    /// or a unified release with a `v1/` prefix. Normally the package reading wins and a
+    /// [`TagWarning::VersionLikePackageName`][] is recorded, with this set it's an error
+    /// instead.
+    ///
+    /// This also rejects tags that two packages' names could both read, like `app-1-2.0.0`
+    /// with packages `app` and `app-1` (which otherwise goes to the longest name, `app-1`).
+    pub require_unambiguous_package_names: bool,
+    /// What to do with a package-scoped tag when every package in the workspace has its version
+    ///
+    /// In that case `my-app-v1.2.3` could reasonably mean "release `my-app`" or
+    /// "release everything at 1.2.3".
+    pub scope_preference: ScopePreference,
+    /// Whether tags have to name a package when the workspace has several releasable ones
+    ///
+    /// Unified releases (`v1.2.3`) then fail with [`TagError::MissingPackageComponent`][],
+    /// so nobody announces everything by accident. Unified channels (`latest`) are still fine.
+    pub require_package_component: bool,
+    /// Whether package names should match tags regardless of ASCII case (`My-App-v1.2.3`)
+    pub case_insensitive_names: bool,
+    /// Whether `-` and `_` should be treated as the same in package names (`my_app-v1.2.3`)
+    ///
+    /// crates.io already treats these as the same name.
+    pub separator_insensitive_names: bool,
+    /// Whether a tag naming a package we don't have should be read as naming the most
+    /// similar package instead (see [`TagParser::did_you_mean`][])
+    ///
+    /// A [`TagWarning::TypoCorrected`][] records the correction.
+    pub typo_tolerance: bool,
+    /// How to score package names against each other for [`TagParser::did_you_mean`][]
+    pub similarity: Similarity,
+    /// How similar (from 0.0 to 1.0) a package name has to be to get suggested
+    pub similarity_threshold: f64,
+    /// Which packages need to have a unified release's version
+    pub unified_versions: UnifiedVersionPolicy,
+    /// What to do with a tag for a package that doesn't have a version
+    pub unversioned_packages: UnversionedPolicy,
+    /// What to do with placeholder versions (`0.0.0`), in tags and in packages
+    pub placeholder_versions: PlaceholderPolicy,
+    /// Limits on which versions packages (or unified releases) can announce
+    ///
+    /// Tags announcing a version outside these fail to parse.
+    pub version_bounds: Vec<VersionBounds>,
+    /// Whether npm-scoped package names can be written without their scope
+    /// (`my-app-v1.2.3` for `@my-org/my-app`)
+    ///
+    /// [`TagParser::format_tag`][] leaves the scope out too.
+    pub strip_npm_scopes: bool,
+    /// Whether to NFC-normalize tags and package names before matching them, so names
+    /// with accents match however they were typed
+    ///
+    /// Results describe the normalized tag (and normalized package names).
+    #[cfg(feature = "unicode")]
+    pub normalize_unicode: bool,
+    /// What [`TagParser::check_not_regression`][] does when a tag doesn't announce a newer version
+    pub regressions: RegressionPolicy,
+    /// Whether final releases need a prerelease of the same version (like `v1.2.3-rc.1`)
+    /// to have been tagged first
+    ///
+    /// Only [`TagParser::check_prerelease_first`][] checks this, since it needs the
+    /// existing tags.
+    pub require_prereleases: bool,
+    /// What signatures release tags need
+    ///
+    /// Only [`TagParser::check_tag_metadata`][] (and [`TagParser::plan_tag_with_metadata`][])
+    /// check this, since it needs the tag's metadata from git.
+    pub signatures: SignaturePolicy,
+    /// What to do when there are no packages to match tags against
+    pub empty_workspace: EmptyWorkspacePolicy,
+}
+
+/// Which reading of a tag wins when it could be package-scoped or unified
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScopePreference {
+    /// Release just the named package
+    #[default]
+    Package,
+    /// Release every package, as if the tag were unified
+    Unified,
+    /// Fail with [`TagError::AmbiguousScope`][]
+    Error,
+}
+
+/// How to parse tags when there are no packages at all
+///
+/// Generic tooling (that doesn't know about the workspace) calls [`parse_tag`][] with no
+/// packages, and package-scoped tags can't be checked against anything then.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyWorkspacePolicy {
+    /// Only accept unified releases (`v1.2.3`)
+    #[default]
+    Unified,
+    /// Fail with [`TagError::NoPackages`][]
+    Error,
+    /// Also accept `{package}-v{version}` tags for any package name, as
+    /// [`AnnouncementKind::UnresolvedPackage`][]
+    ///
+    /// Only the [`TagFormat::Dash`][] format can be read this way (if it's one of our
+    /// formats), since names in the other formats are indistinguishable from an
+    /// arbitrary prefix.
+    Unresolved,
+}
+
+/// Which packages need to have a unified release's version
+///
+/// [`Package::excluded`][] packages never count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnifiedVersionPolicy {
+    /// Accept any version (a unified release simply selects the packages that have it)
+    #[default]
+    Unchecked,
+    /// At least one package has to have the version
+    Any,
+    /// Every package has to have the version
+    All,
+}
+
+/// What to do with a tag for a package that doesn't have a version
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnversionedPolicy {
+    /// Announce the package at the tag's version
+    ///
+    /// [`PartialAnnouncementTag::version_adopted`][] records that this happened.
+    #[default]
+    Adopt,
+    /// Fail with [`TagError::UnversionedPackage`][]
+    Error,
+}
+
+/// What to do with placeholder versions (`0.0.0`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaceholderPolicy {
+    /// Treat them like any other version
+    #[default]
+    Allow,
+    /// Accept them, but record a [`TagWarning::PlaceholderVersion`][]
+    Warn,
+    /// Fail with [`TagError::PlaceholderVersion`][]
+    Error,
+    /// Never release packages with placeholder versions
+    ///
+    /// Tags naming such a package (or announcing a placeholder version) fail with
+    /// [`TagError::PlaceholderVersion`][], and [`TagParser::selected_packages`][] skips them.
+    Exclude,
+}
+
+/// How to treat build metadata when comparing a tag's version to a package's
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BuildMetadataPolicy {
+    /// Build metadata must match exactly (`1.2.3+build.42` is not `1.2.3`)
+    #[default]
+    Exact,
+    /// Build metadata is ignored when comparing versions (`1.2.3+build.42` is `1.2.3`)
+    ///
+    /// The tag's build metadata is still kept on the result.
+    Ignore,
+}
+
+impl BuildMetadataPolicy {
+    /// Compare two versions under this policy
+    ///
+    /// [`BuildMetadataPolicy::Ignore`][] uses semver precedence, so versions that only differ
+    /// in build metadata compare equal.
+    pub fn compare(&self, a: &Version, b: &Version) -> std::cmp::Ordering {
+        match self {
+            BuildMetadataPolicy::Exact => a.cmp(b),
+            BuildMetadataPolicy::Ignore => a.cmp_precedence(b),
+        }
+    }
+}
+
+/// Whether versions in tags need a `v` prefix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VPrefixPolicy {
+    /// Accept both `v1.2.3` and `1.2.3`
+    #[default]
+    Allow,
+    /// Only accept `v1.2.3`
+    Require,
+    /// Only accept `1.2.3`
+    Forbid,
+}
+
+/// What to do when a tag's version doesn't match its package's version
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionMatchPolicy {
+    /// Fail with [`TagError::ContradictoryTagVersion`][]
+    #[default]
+    Error,
+    /// Accept the tag's version, but record a [`TagWarning::ContradictoryTagVersion`][]
+    Warn,
+    /// Accept the tag's version without complaint
+    Ignore,
+}
+
+// Parsers (and their results) have to stay shareable across threads
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TagParser>();
+    assert_send_sync::<PartialAnnouncementTag>();
+    assert_send_sync::<TagError>();
+};
+
+impl Default for TagParser {
+    /// Constructs a TagParser that accepts every format, preferring [`TagFormat::Dash`][]
+    fn default() -> TagParser {
+        TagParser {
+            formats: vec![TagFormat::Dash, TagFormat::Slash, TagFormat::Path],
+            #[cfg(feature = "regex")]
+            patterns: vec![],
+            matchers: vec![],
+            channels: vec!["latest".to_owned(), "stable".to_owned()],
+            allowed_prefixes: None,
+            branch_qualifiers: vec![],
+            package_separators: vec!['-'],
+            segment_order: vec![],
+            namespaces: vec![],
+            series_branches: false,
+            reserved_tags: vec![],
+            denied_tags: vec![],
+            moving_aliases: false,
+            resolve_channels: false,
+            reject_legacy_formats: false,
+            deprecate_legacy_formats: false,
+            version_match: VersionMatchPolicy::Error,
+            directional_version_mismatch: false,
+            lenient_versions: false,
+            v_prefix: VPrefixPolicy::Allow,
+            build_metadata: BuildMetadataPolicy::Exact,
+            require_unambiguous_package_names: false,
+            scope_preference: ScopePreference::Package,
+            require_package_component: false,
+            case_insensitive_names: false,
+            separator_insensitive_names: false,
+            typo_tolerance: false,
+            similarity: Similarity::default(),
+            similarity_threshold: 0.75,
+            unified_versions: UnifiedVersionPolicy::Unchecked,
+            unversioned_packages: UnversionedPolicy::Adopt,
+            placeholder_versions: PlaceholderPolicy::Allow,
+            version_bounds: vec![],
+            strip_npm_scopes: false,
+            #[cfg(feature = "unicode")]
+            normalize_unicode: false,
+            regressions: RegressionPolicy::Error,
+            require_prereleases: false,
+            signatures: SignaturePolicy::Any,
+            empty_workspace: EmptyWorkspacePolicy::Unified,
+        }
+    }
+}
+
+impl TagParser {
+    /// Parse a tag using these settings
+    ///
+    /// See [`parse_tag`][] for details.
+    pub fn parse_tag(&self, packages: &[Package], tag: &str) -> TagResult<PartialAnnouncementTag> {
+        self.parse_tag_recording(packages, tag, None)
+    }
+
+    /// Parse a tag, recording how it went in `trace` (see [`TagParser::parse_tag_traced`][])
+    pub(crate) fn parse_tag_recording(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        trace: Option<&mut ParseTrace>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        #[cfg(feature = "unicode")]
+        if self.normalize_unicode {
+            if let Some((packages, tag)) = unicode::nfc_normalize(packages, tag) {
+                return self.parse_normalized_tag(&packages, &tag, None, trace);
+            }
+        }
+        self.parse_normalized_tag(packages, tag, None, trace)
+    }
+
+    /// Parse a tag whose unicode (if we care about that) has already been normalized,
+    /// looking package names up in `index` if it's an index of `packages`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, packages, trace), fields(packages = packages.len()))
+    )]
+    fn parse_normalized_tag(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        index: Option<&NameIndex>,
+        mut trace: Option<&mut ParseTrace>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        refs::check_git_tag(tag)?;
+        self.check_not_denied(tag)?;
+        if let Some(result) = self.parse_namespaced(packages, tag, trace.as_deref_mut()) {
+            return result;
+        }
+        let result = match self.parse_tag_inner(packages, tag, index, trace.as_deref_mut()) {
+            Ok(announcing) => {
+                let result = self.check_reading(packages, tag, index, announcing, &mut trace);
+                if let (Err(e), Some(trace)) = (&result, trace.as_deref_mut()) {
+                    trace.fail(e);
+                }
+                result
+            }
+            Err(e) => {
+                trace::debug!(error = %e, "no reading worked out, checking for typos");
+                (self.parse_typo(packages, tag, trace.as_deref_mut())).unwrap_or(Err(e))
+            }
         };
-        // Check if this is "blah/blah/some-package/v1.0.0" format by checking if the last slash-delimited
-        // component is exactly a package name (strip_prefix produces empty string)
-        if let Some((package, "")) = strip_prefix_package(maybe_package, packages) {
-            announcing_package = Some(package);
+        if let Some(trace) = trace {
+            let chosen =
+                (result.as_ref().ok()).and_then(|announcing| announcing.release.package_idx());
+            trace.candidates = self.trace_candidates(packages, tag, chosen);
         }
-        tag_suffix = suffix;
-    } else {
-        tag_suffix = &announcement_tag;
-    };
+        result
+    }
+
+    /// Check the reading [`TagParser::parse_tag_inner`][] picked against everything that
+    /// isn't about the package and version alone (like prefixes)
+    fn check_reading(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        index: Option<&NameIndex>,
+        announcing: PartialAnnouncementTag,
+        trace: &mut Option<&mut ParseTrace>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        let mut announcing =
+            self.resolve_package_segment(packages, tag, announcing, trace.as_deref_mut())?;
+        trace::debug!(release = ?announcing.release, format = ?announcing.format, "parsed tag");
+        let chosen = announcing.release.package_idx();
+        let mut series_branch = announcing
+            .prefix()
+            .filter(|_| self.series_branches)
+            .and_then(MaintenanceBranch::from_prefix);
+        if let Some(prefix) = announcing.prefix().filter(|p| self.lists_prefix(p)) {
+            if series_branch.is_some() {
+                let prefix = prefix.to_owned();
+                let kinds = [SegmentKind::Branch, SegmentKind::Prefix];
+                let kind = self.resolve_segment(tag, &prefix, &kinds, &mut announcing.warnings)?;
+                if kind == SegmentKind::Prefix {
+                    series_branch = None;
+                }
+            }
+        }
+        if let Some(branch) = series_branch {
+            if let Some(version) = announcing.release.version() {
+                if !branch.covers(version) {
+                    return Err(TagError::OutsideBranchSeries {
+                        tag: tag.to_owned(),
+                        branch: branch.name,
+                        version: version.clone(),
+                    });
+                }
+            }
+            announcing.branch = Some(branch);
+        } else if let Some(prefix) = announcing.prefix() {
+            match &self.allowed_prefixes {
+                None => announcing.warnings.push(TagWarning::UnusualPrefix {
+                    prefix: prefix.to_owned(),
+                }),
+                Some(_) if self.lists_prefix(prefix) => {}
+                Some(allowed) => {
+                    return Err(TagError::UnexpectedPrefix {
+                        tag: tag.to_owned(),
+                        prefix: prefix.to_owned(),
+                        allowed: allowed.clone(),
+                    })
+                }
+            }
+        }
+        if let (Some(alias), Some(package)) = (&announcing.alias, chosen.map(|i| &packages[i])) {
+            announcing.warnings.push(TagWarning::AliasUsed {
+                package_name: package.name.clone(),
+                alias: alias.clone(),
+            });
+        }
+        announcing
+            .warnings
+            .extend(self.rejected_candidates(packages, tag, chosen, index));
+        Ok(announcing)
+    }
+
+    /// Parse a tag, returning the recoverable oddities we noticed separately
+    ///
+    /// This is [`TagParser::parse_tag`][] with [`PartialAnnouncementTag::warnings`][]
+    /// moved out of the result.
+    pub fn parse_tag_with_warnings(
+        &self,
+        packages: &[Package],
+        tag: &str,
+    ) -> TagResult<(PartialAnnouncementTag, Vec<TagWarning>)> {
+        let mut announcing = self.parse_tag(packages, tag)?;
+        let warnings = std::mem::take(&mut announcing.warnings);
+        Ok((announcing, warnings))
+    }
+
+    /// The actual implementation of [`TagParser::parse_tag`][]
+    fn parse_tag_inner(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        index: Option<&NameIndex>,
+        mut trace: Option<&mut ParseTrace>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        if let Some(name) = self.reserved_tags.iter().find(|name| *name == tag) {
+            trace::debug!(name, "tag is reserved");
+            return Ok(PartialAnnouncementTag {
+                tag: tag.to_owned(),
+                release: AnnouncementKind::Reserved { name: name.clone() },
+                ..PartialAnnouncementTag::default()
+            });
+        }
+        if packages.is_empty() {
+            match self.empty_workspace {
+                EmptyWorkspacePolicy::Unified => {}
+                EmptyWorkspacePolicy::Error => {
+                    return Err(TagError::NoPackages {
+                        tag: tag.to_owned(),
+                    })
+                }
+                EmptyWorkspacePolicy::Unresolved => {
+                    if let Some(announcing) = self.parse_unresolved(tag) {
+                        return Ok(announcing);
+                    }
+                }
+            }
+        }
+
+        // First thing's first: see if any of our matchers can find a package in the tag.
+        // If a matcher finds a package but the rest of the tag isn't a version, move on
+        // to the next one and only report the error if nothing else works out. The last
+        // matcher reads the tag as a unified release, which always matches.
+        let mut cx = MatchContext::new(self, packages);
+        if let Some(index) = index {
+            cx = cx.with_index(index);
+        }
+        let mut first_error = None;
+        for (stage, matcher) in self.pipeline_stages() {
+            let Some(found) = cx.run(matcher, tag) else {
+                trace::trace!(?matcher, "matcher didn't match");
+                if let Some(trace) = trace.as_deref_mut() {
+                    trace.attempts.push(ParseAttempt {
+                        stage,
+                        outcome: AttemptOutcome::NoMatch,
+                    });
+                }
+                continue;
+            };
+            trace::debug!(
+                ?matcher,
+                package = ?found.package.as_ref().map(|span| &packages[span.idx].name),
+                version = &tag[found.version.clone()],
+                "trying reading"
+            );
+            let reading = (trace.is_some()).then(|| ParseTrace::reading(packages, tag, &found));
+            let result = self.read_match(packages, tag, found);
+            if let (Some(trace), Some(reading)) = (trace.as_deref_mut(), reading) {
+                trace.record(stage, reading, &result);
+            }
+            match result? {
+                Ok(announcing) => return Ok(announcing),
+                Err(e) => {
+                    trace::debug!(error = %e, "rejected reading, trying the next matcher");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.expect("the unified reading of a tag always matches"))
+    }
+
+    /// Read a tag as `{package}-v{version}` for a package we don't have
+    ///
+    /// The package name is whatever comes before the first dash that's followed by
+    /// a version. Since there are no packages, no checks against them apply.
+    fn parse_unresolved(&self, tag: &str) -> Option<PartialAnnouncementTag> {
+        if !self.formats.contains(&TagFormat::Dash) {
+            return None;
+        }
+        let (prefix, rest) = tag.rsplit_once('/').unwrap_or(("", tag));
+        rest.match_indices(self.separators())
+            .filter(|(i, _)| *i > 0)
+            .find_map(|(i, sep)| {
+                let version = &rest[i + sep.len()..];
+                let TagVersion {
+                    version, warnings, ..
+                } = self
+                    .parse_tag_version(tag, version, VersionScheme::Semver)
+                    .ok()?;
+                Some(PartialAnnouncementTag {
+                    tag: tag.to_owned(),
+                    prerelease: !version.pre.is_empty(),
+                    release: AnnouncementKind::UnresolvedPackage {
+                        name: rest[..i].to_owned(),
+                        version,
+                    },
+                    format: Some(TagFormat::Dash),
+                    style: Some(TagStyle::new(Some(TagFormat::Dash), prefix)),
+                    warnings,
+                    ..PartialAnnouncementTag::default()
+                })
+            })
+    }
+
+    /// Read the version (and check the package) where a matcher found them
+    ///
+    /// The outer error means the tag is bad, the inner one means the version didn't
+    /// parse (so another matcher's reading might still work out).
+    fn read_match(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        found: TagMatch,
+    ) -> TagResult<TagResult<PartialAnnouncementTag>> {
+        let package = found.package.as_ref().map(|span| span.idx);
+        let alias = found
+            .package
+            .as_ref()
+            .and_then(|span| Some(packages[span.idx].aliases[span.alias?].clone()));
+        let version = &tag[found.version.clone()];
+        if let Some(release) = self.parse_channel(packages, package, version) {
+            self.check_releasable(packages, tag, package)?;
+            let mut announcing = channel_tag(tag, release, found.format, found.style);
+            announcing.alias = alias;
+            return Ok(Ok(announcing));
+        }
+        let scheme = match package {
+            Some(idx) => packages[idx].version_scheme,
+            None => unified_scheme(packages),
+        };
+        let parsed = match self.parse_tag_version(tag, version, scheme) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(Err(e)),
+        };
+        let version = parsed.version;
+        let mut warnings = parsed.warnings;
+        let package = match found.package {
+            Some(span) => {
+                if found.confidence == Confidence::Inexact {
+                    warnings.push(TagWarning::InexactPackageName {
+                        package_name: packages[span.idx].name.clone(),
+                        found: tag[span.range].to_owned(),
+                    });
+                }
+                if let Some(format) = &found.format {
+                    self.check_legacy_format(
+                        packages,
+                        tag,
+                        span.idx,
+                        &version,
+                        format,
+                        &mut warnings,
+                    )?;
+                    self.check_version_like_name(packages, tag, span.idx, &mut warnings)?;
+                    self.check_overlapping_names(packages, tag, span.idx, format)?;
+                }
+                self.resolve_scope(packages, tag, span.idx, &version)?
+            }
+            None => {
+                self.check_package_component(packages, tag, &version)?;
+                None
+            }
+        };
+        let mut announcing =
+            self.finish_tag(packages, tag, package, version, found.format, warnings)?;
+        announcing.style = Some(found.style);
+        announcing.alias = alias;
+        announcing.epoch = parsed.epoch;
+        announcing.build_number = parsed.build_number;
+        announcing.branch = parsed.branch;
+        #[cfg(feature = "pep440")]
+        {
+            announcing.pep440 = parsed.pep440;
+        }
+        Ok(Ok(announcing))
+    }
 
-    // If we don't have an announcing_package yet, check if this is "some-package-v1.0.0" format
-    if announcing_package.is_none() {
-        if let Some((package, suffix)) = strip_prefix_package(tag_suffix, packages) {
-            // Must be followed by a dash to be accepted
-            if let Some(suffix) = suffix.strip_prefix('-') {
-                tag_suffix = suffix;
-                announcing_package = Some(package);
+    /// Find packages whose names prefix the tag but that we didn't pick, and explain why
+    fn rejected_candidates(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        chosen: Option<usize>,
+        index: Option<&NameIndex>,
+    ) -> Vec<TagWarning> {
+        let component = unified_version_component(tag);
+        let chosen_name = chosen.and_then(|idx| packages.get(idx)).map(|p| &p.name);
+        let names = self.name_matcher();
+        // Every package whose (full) name prefixes the tag, in input order
+        let candidates: Vec<(usize, &str)> = match index {
+            Some(index) => {
+                let mut found = index
+                    .prefixes(component)
+                    .into_iter()
+                    .filter(|found| {
+                        found.alias.is_none()
+                            && found.matched.len() == packages[found.idx].name.len()
+                    })
+                    .map(|found| (found.idx, found.rest))
+                    .collect::<Vec<_>>();
+                found.sort_by_key(|(idx, _)| *idx);
+                found.dedup_by_key(|(idx, _)| *idx);
+                found
             }
+            None => packages
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, package)| {
+                    Some((idx, names.strip_prefix(component, &package.name)?))
+                })
+                .collect(),
+        };
+        let mut warnings = vec![];
+        for (idx, rest) in candidates {
+            let package = &packages[idx];
+            if Some(idx) == chosen {
+                continue;
+            }
+            let reason = if let Some(chosen_name) = chosen_name.filter(|name| {
+                name.len() > package.name.len() && names.strip_prefix(component, name).is_some()
+            }) {
+                RejectionReason::LongerMatch {
+                    package_name: chosen_name.clone(),
+                }
+            } else if let Some(version) = rest.strip_prefix(self.separators()) {
+                if self
+                    .parse_tag_version(tag, version, package.version_scheme)
+                    .is_ok()
+                {
+                    // It was a fine reading, we just preferred another one
+                    continue;
+                }
+                RejectionReason::InvalidVersion {
+                    version: version.to_owned(),
+                }
+            } else if let Some(found) = rest.chars().next() {
+                RejectionReason::WrongSeparator { found }
+            } else {
+                continue;
+            };
+            trace::debug!(package = package.name, ?reason, "rejected package");
+            warnings.push(TagWarning::RejectedCandidate {
+                package_name: package.name.clone(),
+                reason,
+            });
         }
+        warnings
     }
 
-    // At this point, assuming the input is valid, tag_suffix should just be the version
-    // component with an optional "v" prefix, so strip that "v"
-    if let Some(suffix) = tag_suffix.strip_prefix('v') {
-        tag_suffix = suffix;
+    /// If we're rejecting (or deprecating) legacy formats, error out (or warn) if the tag
+    /// matched one
+    fn check_legacy_format(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: usize,
+        version: &Version,
+        format: &TagFormat,
+        warnings: &mut Vec<TagWarning>,
+    ) -> TagResult<()> {
+        let checked = self.reject_legacy_formats || self.deprecate_legacy_formats;
+        if !checked || self.formats.first() == Some(format) {
+            return Ok(());
+        }
+        let canonical = self.format_tag(packages.get(package), version);
+        if self.reject_legacy_formats {
+            return Err(TagError::LegacyTagFormat {
+                tag: tag.to_owned(),
+                format: *format,
+                canonical,
+            });
+        }
+        warnings.push(TagWarning::DeprecatedTagFormat {
+            format: *format,
+            canonical,
+        });
+        Ok(())
     }
 
-    // Now parse the version out
-    match tag_suffix.parse::<Version>() {
-        Ok(version) => {
-            // Register whether we're announcing a prerelease
-            announcing_prerelease = !version.pre.is_empty();
-            announcing_version = version;
+    /// Parse the version component of a tag
+    pub(crate) fn parse_tag_version(
+        &self,
+        tag: &str,
+        version: &str,
+        scheme: VersionScheme,
+    ) -> TagResult<TagVersion> {
+        // At this point, assuming the input is valid, version should just be the version
+        // component with an optional "v" prefix, so strip that "v"
+        let version_start = fixit::offset_in(tag, version);
+        let (has_v, version) = match version.strip_prefix('v') {
+            Some(version) => (true, version),
+            None => (false, version),
+        };
+        match (self.v_prefix, has_v) {
+            (VPrefixPolicy::Require, false) => {
+                return Err(TagError::MissingVPrefix {
+                    tag: tag.to_owned(),
+                    version_start,
+                })
+            }
+            (VPrefixPolicy::Forbid, true) => {
+                return Err(TagError::ForbiddenVPrefix {
+                    tag: tag.to_owned(),
+                    version_start,
+                })
+            }
+            _ => {}
+        }
+        #[cfg(feature = "pep440")]
+        if scheme == VersionScheme::Pep440 {
+            return self.parse_pep440_version(tag, version);
+        }
+        let (epoch, version) = match scheme {
+            VersionScheme::Epoch => lenient::split_epoch(version),
+            _ => (None, version),
+        };
 
-            // If there's an announcing package, validate that the version matches
-            if let Some(pkg_idx) = announcing_package {
-                if let Some(package) = packages.get(pkg_idx) {
-                    if let Some(real_version) = &package.version {
-                        if real_version != &announcing_version {
-                            return Err(TagError::ContradictoryTagVersion {
+        let (version, warnings) = self.parse_version_core(tag, version, scheme)?;
+        let (version, build_number) = match scheme {
+            VersionScheme::BuildNumber => lenient::split_build_number(version),
+            _ => (version, None),
+        };
+        let (version, branch) = self.split_branch_qualifier(version);
+        Ok(TagVersion {
+            version,
+            warnings,
+            epoch,
+            build_number,
+            branch,
+            #[cfg(feature = "pep440")]
+            pep440: None,
+        })
+    }
+
+    /// Parse a version (without any `v` or epoch), leniently if the scheme allows it
+    fn parse_version_core(
+        &self,
+        tag: &str,
+        version: &str,
+        scheme: VersionScheme,
+    ) -> TagResult<(Version, Vec<TagWarning>)> {
+        match version.parse::<Version>() {
+            Ok(parsed) => Ok((parsed, vec![])),
+            Err(details) => {
+                let lenient = matches!(scheme, VersionScheme::Lenient | VersionScheme::CalVer);
+                if self.lenient_versions || lenient {
+                    if let Some((parsed, mut fixups)) = lenient::parse_lenient(version) {
+                        if scheme == VersionScheme::CalVer {
+                            // Short and zero-padded components are just how CalVer looks
+                            fixups.retain(|f| matches!(f, VersionFixup::ExtraComponents { .. }));
+                        }
+                        let warnings = if fixups.is_empty() {
+                            vec![]
+                        } else {
+                            vec![TagWarning::LenientVersion {
+                                original: version.to_owned(),
+                                version: parsed.clone(),
+                                fixups,
+                            }]
+                        };
+                        return Ok((parsed, warnings));
+                    }
+                }
+                Err(TagError::TagVersionParse {
+                    tag: tag.to_owned(),
+                    details,
+                })
+            }
+        }
+    }
+
+    /// Validate and assemble the final result of parsing a tag
+    pub(crate) fn finish_tag(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        announcing_package: Option<usize>,
+        announcing_version: Version,
+        format: Option<TagFormat>,
+        mut warnings: Vec<TagWarning>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        // Register whether we're announcing a prerelease
+        let announcing_prerelease = !announcing_version.pre.is_empty();
+
+        self.check_releasable(packages, tag, announcing_package)?;
+        self.check_placeholders(
+            packages,
+            tag,
+            announcing_package,
+            &announcing_version,
+            &mut warnings,
+        )?;
+        self.check_version_bounds(packages, tag, announcing_package, &announcing_version)?;
+        if announcing_package.is_none() {
+            self.check_unified_version(packages, tag, &announcing_version)?;
+        }
+
+        // If there's an announcing package, validate that the version matches
+        let mut version_adopted = false;
+        if let Some(pkg_idx) = announcing_package {
+            if let Some(package) = packages.get(pkg_idx) {
+                if package.version.is_none() {
+                    match self.unversioned_packages {
+                        UnversionedPolicy::Adopt => version_adopted = true,
+                        UnversionedPolicy::Error => {
+                            return Err(TagError::UnversionedPackage {
                                 tag: tag.to_owned(),
                                 package_name: package.name.clone(),
-                                tag_version: announcing_version,
-                                real_version: real_version.clone(),
                             });
                         }
                     }
                 }
+                if let Some(real_version) = &package.version {
+                    if !self.versions_match(real_version, &announcing_version) {
+                        match self.version_match {
+                            VersionMatchPolicy::Error => {
+                                let tag = tag.to_owned();
+                                let package_name = package.name.clone();
+                                let real_version = real_version.clone();
+                                let tag_version = announcing_version;
+                                let order = tag_version.cmp_precedence(&real_version);
+                                return Err(match order {
+                                    std::cmp::Ordering::Less
+                                        if self.directional_version_mismatch =>
+                                    {
+                                        TagError::StaleTagVersion {
+                                            tag,
+                                            package_name,
+                                            tag_version,
+                                            real_version,
+                                        }
+                                    }
+                                    std::cmp::Ordering::Greater
+                                        if self.directional_version_mismatch =>
+                                    {
+                                        TagError::TagVersionAhead {
+                                            tag,
+                                            package_name,
+                                            tag_version,
+                                            real_version,
+                                        }
+                                    }
+                                    _ => TagError::ContradictoryTagVersion {
+                                        tag,
+                                        package_name,
+                                        tag_version,
+                                        real_version,
+                                    },
+                                });
+                            }
+                            VersionMatchPolicy::Warn => {
+                                warnings.push(TagWarning::ContradictoryTagVersion {
+                                    package_name: package.name.clone(),
+                                    tag_version: announcing_version.clone(),
+                                    real_version: real_version.clone(),
+                                });
+                            }
+                            VersionMatchPolicy::Ignore => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        let release = if let Some(idx) = announcing_package {
+            AnnouncementKind::Package {
+                idx,
+                version: announcing_version,
             }
+        } else {
+            AnnouncementKind::Unified(announcing_version)
+        };
+
+        Ok(PartialAnnouncementTag {
+            tag: tag.to_owned(),
+            prerelease: announcing_prerelease,
+            release,
+            format,
+            style: None,
+            version_adopted,
+            alias: None,
+            epoch: None,
+            build_number: None,
+            branch: None,
+            #[cfg(feature = "pep440")]
+            pep440: None,
+            origin: TagOrigin::Tag,
+            namespace: None,
+            warnings,
+        })
+    }
+
+    /// Check that a unified release's version matches the packages, per our policy
+    fn check_unified_version(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        version: &Version,
+    ) -> TagResult<()> {
+        let releasable = packages.iter().filter(|p| !p.excluded);
+        let mut matching = releasable.clone().map(|p| {
+            p.version
+                .as_ref()
+                .is_some_and(|v| self.versions_match(v, version))
+        });
+        let ok = match self.unified_versions {
+            UnifiedVersionPolicy::Unchecked => true,
+            UnifiedVersionPolicy::Any => matching.any(|m| m),
+            UnifiedVersionPolicy::All => matching.all(|m| m),
+        };
+        if ok {
+            return Ok(());
+        }
+        Err(TagError::UnifiedVersionMismatch {
+            tag: tag.to_owned(),
+            version: version.clone(),
+            require_all: self.unified_versions == UnifiedVersionPolicy::All,
+            packages: releasable
+                .map(|p| PackageVersion {
+                    package_name: p.name.clone(),
+                    version: p.version.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    /// If tags have to name a package, error out on a unified release
+    fn check_package_component(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        version: &Version,
+    ) -> TagResult<()> {
+        if !self.require_package_component {
+            return Ok(());
         }
-        Err(e) => {
-            return Err(TagError::TagVersionParse {
+        let mut releasable = packages.iter().filter(|p| !p.excluded);
+        let (Some(first), Some(_)) = (releasable.next(), releasable.next()) else {
+            return Ok(());
+        };
+        Err(TagError::MissingPackageComponent {
+            tag: tag.to_owned(),
+            example: self.format_tag(Some(first), version),
+        })
+    }
+
+    /// Error out if the tag names a package that's [`Package::excluded`][]
+    pub(crate) fn check_releasable(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: Option<usize>,
+    ) -> TagResult<()> {
+        match package.and_then(|idx| packages.get(idx)) {
+            Some(package) if package.excluded => Err(TagError::PackageNotReleasable {
                 tag: tag.to_owned(),
-                details: e,
+                package_name: package.name.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Complain about placeholder versions in the tag or the package it names, per our policy
+    fn check_placeholders(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: Option<usize>,
+        version: &Version,
+        warnings: &mut Vec<TagWarning>,
+    ) -> TagResult<()> {
+        let package = package.and_then(|idx| packages.get(idx));
+        let placeholder = if is_placeholder_version(version) {
+            // The tag itself is a placeholder
+            Some(None)
+        } else {
+            package
+                .filter(|p| p.has_placeholder_version())
+                .map(|p| Some(p.name.clone()))
+        };
+        let Some(package_name) = placeholder else {
+            return Ok(());
+        };
+        match self.placeholder_versions {
+            PlaceholderPolicy::Allow => Ok(()),
+            PlaceholderPolicy::Warn => {
+                warnings.push(TagWarning::PlaceholderVersion { package_name });
+                Ok(())
+            }
+            PlaceholderPolicy::Error | PlaceholderPolicy::Exclude => {
+                Err(TagError::PlaceholderVersion {
+                    tag: tag.to_owned(),
+                    package_name,
+                })
+            }
+        }
+    }
+
+    /// Get the indices of the packages an announcement would release, in input order
+    ///
+    /// This is [`PartialAnnouncementTag::selected_packages`][], but unified releases select
+    /// packages whose version matches per [`TagParser::build_metadata`][] (like parsing
+    /// checks them), and [`PlaceholderPolicy::Exclude`][] is respected.
+    pub fn selected_packages(
+        &self,
+        announcing: &PartialAnnouncementTag,
+        packages: &[Package],
+    ) -> Vec<usize> {
+        announcing
+            .selected_packages_under(packages, self.build_metadata)
+            .into_iter()
+            .filter(|idx| {
+                self.placeholder_versions != PlaceholderPolicy::Exclude
+                    || !packages[*idx].has_placeholder_version()
             })
+            .collect()
+    }
+
+    /// If the package we found has a version-like name, check if the tag could also
+    /// be read as a unified release, and complain about it
+    fn check_version_like_name(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: usize,
+        warnings: &mut Vec<TagWarning>,
+    ) -> TagResult<()> {
+        let Some(package) = packages.get(package) else {
+            return Ok(());
+        };
+        if !package.has_version_like_name()
+            || self
+                .parse_tag_version(
+                    tag,
+                    unified_version_component(tag),
+                    unified_scheme(packages),
+                )
+                .is_err()
+        {
+            return Ok(());
+        }
+        if self.require_unambiguous_package_names {
+            return Err(TagError::AmbiguousPackageName {
+                tag: tag.to_owned(),
+                package_name: package.name.clone(),
+            });
         }
+        warnings.push(TagWarning::VersionLikePackageName {
+            package_name: package.name.clone(),
+        });
+        Ok(())
     }
 
-    let release = if let Some(idx) = announcing_package {
-        ReleaseType::Package {
-            idx,
-            version: announcing_version,
+    /// If we're asked to, error out if a shorter package name than the one we found
+    /// would also make a valid [`TagFormat::Dash`][] reading of the tag
+    ///
+    /// With packages `app` and `app-1` and [`TagParser::lenient_versions`][] set,
+    /// `app-1-2.0.0` could be `app-1` at `2.0.0` or `app` at `1.0.0-2.0.0`. The longest
+    /// name wins normally (and a [`TagWarning::RejectedCandidate`][] explains why).
+    fn check_overlapping_names(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: usize,
+        format: &TagFormat,
+    ) -> TagResult<()> {
+        if !self.require_unambiguous_package_names || *format != TagFormat::Dash {
+            return Ok(());
+        }
+        let component = unified_version_component(tag);
+        for found in package_prefixes(component, packages, self.name_matcher()) {
+            let idx = found.idx;
+            if idx == package {
+                continue;
+            }
+            let Some(version) = found.rest.strip_prefix(self.separators()) else {
+                continue;
+            };
+            if self
+                .parse_tag_version(tag, version, packages[idx].version_scheme)
+                .is_ok()
+            {
+                return Err(TagError::OverlappingPackageNames {
+                    tag: tag.to_owned(),
+                    package_name: packages[package].name.clone(),
+                    other_package_name: packages[idx].name.clone(),
+                });
+            }
         }
+        Ok(())
+    }
+
+    /// If a package-scoped tag could just as well be a unified release (because every
+    /// package has the tag's version), decide which reading wins
+    pub(crate) fn resolve_scope(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        package: usize,
+        version: &Version,
+    ) -> TagResult<Option<usize>> {
+        let plausibly_unified = packages.len() > 1
+            && unified_version(packages).is_some_and(|v| self.versions_match(v, version));
+        if !plausibly_unified {
+            return Ok(Some(package));
+        }
+        match self.scope_preference {
+            ScopePreference::Package => Ok(Some(package)),
+            ScopePreference::Unified => Ok(None),
+            ScopePreference::Error => Err(TagError::AmbiguousScope {
+                tag: tag.to_owned(),
+                package_name: packages[package].name.clone(),
+                version: version.clone(),
+            }),
+        }
+    }
+
+    /// Check if a package's version matches a tag's version, respecting our build metadata policy
+    pub(crate) fn versions_match(&self, real_version: &Version, tag_version: &Version) -> bool {
+        self.build_metadata
+            .compare(real_version, tag_version)
+            .is_eq()
+    }
+
+    /// Check if the version component of a tag is actually one of our channels
+    pub(crate) fn parse_channel(
+        &self,
+        packages: &[Package],
+        package: Option<usize>,
+        version: &str,
+    ) -> Option<AnnouncementKind> {
+        let series = self.parse_moving_alias(version);
+        if series.is_none() && !self.channels.iter().any(|channel| channel == version) {
+            return None;
+        }
+        // Moving aliases only resolve to versions that are actually in their series
+        let in_series = |version: &Version| series.is_none_or(|series| series.covers(version));
+        if self.resolve_channels {
+            if let Some(idx) = package {
+                if let Some(version) = packages.get(idx).and_then(|p| p.version.clone()) {
+                    if in_series(&version) {
+                        return Some(AnnouncementKind::Package { idx, version });
+                    }
+                }
+            } else if let Some(version) = unified_version(packages) {
+                if in_series(version) {
+                    return Some(AnnouncementKind::Unified(version.clone()));
+                }
+            }
+        }
+        Some(AnnouncementKind::Channel {
+            idx: package,
+            channel: version.to_owned(),
+        })
+    }
+
+    /// Get the characters that can separate a package from its version, see
+    /// [`TagParser::package_separators`][]
+    pub(crate) fn separators(&self) -> &[char] {
+        if self.package_separators.is_empty() {
+            &['-']
+        } else {
+            &self.package_separators
+        }
+    }
+
+    /// Get how we compare package names
+    pub(crate) fn name_matcher(&self) -> NameMatcher<'static> {
+        self.match_options().into()
+    }
+
+    /// Get a template describing how we spell tags in a format (or unified tags, if None),
+    /// like `{package}-v{version}`
+    ///
+    /// Like [`TagParser::format_tag`][], this respects [`TagParser::v_prefix`][] and uses the
+    /// first of [`TagParser::package_separators`][].
+    pub fn tag_template(&self, format: Option<TagFormat>) -> String {
+        let version = if self.v_prefix == VPrefixPolicy::Forbid {
+            "{version}"
+        } else {
+            "v{version}"
+        };
+        match format {
+            None => version.to_owned(),
+            Some(TagFormat::Dash) => format!("{{package}}{}{version}", self.separators()[0]),
+            Some(TagFormat::Slash) => format!("{{package}}/{version}"),
+            Some(TagFormat::Path) => format!("{{path}}/{version}"),
+        }
+    }
+
+    /// Produce a tag for the given package (or a unified release, if None) at the given version
+    ///
+    /// This always uses the first of [`TagParser::formats`][], and respects [`TagParser::v_prefix`][].
+    /// Packages in one of [`TagParser::namespaces`][] get tags in that namespace, formatted
+    /// by its parser. This doesn't check [`TagParser::denied_tags`][], see
+    /// [`TagParser::try_format_tag`][].
+    pub fn format_tag(&self, package: Option<&Package>, version: &Version) -> String {
+        self.format_tag_component(package, &version.to_string())
+    }
+
+    /// Produce a tag for the given package with an arbitrary version component (like `1.2`)
+    pub(crate) fn format_tag_component(&self, package: Option<&Package>, version: &str) -> String {
+        if let Some(namespace) = package.and_then(|p| self.namespace_of(p)) {
+            let tag = self
+                .namespace_parser(namespace)
+                .format_tag_component(package, version);
+            return format!("{}/{tag}", namespace.name);
+        }
+        let version = if self.v_prefix == VPrefixPolicy::Forbid {
+            version.to_owned()
+        } else {
+            format!("v{version}")
+        };
+        let unscoped = package.filter(|_| self.strip_npm_scopes).map(|p| Package {
+            name: unscoped_name(&p.name).to_owned(),
+            ..p.clone()
+        });
+        self.formats
+            .first()
+            .copied()
+            .unwrap_or_default()
+            .format_version(
+                unscoped.as_ref().or(package),
+                &version,
+                self.separators()[0],
+            )
+    }
+}
+
+/// Get a package name without its npm scope (`@my-org/my-app` => `my-app`)
+pub(crate) fn unscoped_name(name: &str) -> &str {
+    match name.strip_prefix('@').and_then(|name| name.split_once('/')) {
+        Some((_, name)) if !name.is_empty() => name,
+        _ => name,
+    }
+}
+
+/// Get the part of a tag that should be the version if it's a unified release
+///
+/// Any slash-delimited prefix is ignored.
+fn unified_version_component(tag: &str) -> &str {
+    tag.rsplit_once('/').map_or(tag, |(_, suffix)| suffix)
+}
+
+/// Get the version every (non-excluded) package agrees on, if there is one
+pub(crate) fn unified_version(packages: &[Package]) -> Option<&Version> {
+    let mut versions = packages
+        .iter()
+        .filter(|p| !p.excluded)
+        .map(|p| p.version.as_ref());
+    let first = versions.next()??;
+    versions.all(|v| v == Some(first)).then_some(first)
+}
+
+/// Get the version scheme every releasable package agrees on, or semver if they don't
+///
+/// This is the scheme unified releases are parsed with.
+pub(crate) fn unified_scheme(packages: &[Package]) -> VersionScheme {
+    let mut schemes = packages
+        .iter()
+        .filter(|p| !p.excluded)
+        .map(|p| p.version_scheme);
+    let first = schemes.next().unwrap_or_default();
+    if schemes.all(|scheme| scheme == first) {
+        first
     } else {
-        ReleaseType::Version(announcing_version)
-    };
+        VersionScheme::Semver
+    }
+}
 
-    Ok(PartialAnnouncementTag {
-        tag: announcement_tag,
-        prerelease: announcing_prerelease,
+/// Assemble the result of parsing a channel tag
+pub(crate) fn channel_tag(
+    tag: &str,
+    release: AnnouncementKind,
+    format: Option<TagFormat>,
+    style: TagStyle,
+) -> PartialAnnouncementTag {
+    PartialAnnouncementTag {
+        tag: tag.to_owned(),
+        prerelease: release.version().is_some_and(|v| !v.pre.is_empty()),
         release,
-    })
+        format,
+        style: Some(style),
+        version_adopted: false,
+        alias: None,
+        epoch: None,
+        build_number: None,
+        branch: None,
+        #[cfg(feature = "pep440")]
+        pep440: None,
+        origin: TagOrigin::Tag,
+        namespace: None,
+        warnings: vec![],
+    }
 }
 
-/// Try to strip-prefix a package name from the given input, preferring whichever one is longest
-/// (to disambiguate situations where you have `my-app` and `my-app-helper`).
+/// Try to strip-prefix a package name (or alias) from the given input, preferring whichever
+/// one is longest (to disambiguate situations where you have `my-app` and `my-app-helper`).
+/// If several packages have the same name, the first one wins, and a package's name wins
+/// over its aliases.
 ///
 /// If a match is found, then the return value is:
 /// * the idx of the package
+/// * the idx of the alias that matched, if it wasn't the package's name
 /// * the rest of the input
-fn strip_prefix_package<'a>(input: &'a str, packages: &[Package]) -> Option<(usize, &'a str)> {
-    let mut result: Option<(usize, &'a str)> = None;
-    for (pkg_id, package) in packages.iter().enumerate() {
-        if let Some(rest) = input.strip_prefix(&package.name) {
-            if let Some((_, best)) = result {
-                if best.len() <= rest.len() {
-                    continue;
-                }
-            }
-            result = Some((pkg_id, rest))
+fn strip_prefix_package<'a>(
+    input: &'a str,
+    packages: &[Package],
+    names: NameMatcher,
+) -> Option<(usize, Option<usize>, &'a str)> {
+    package_prefixes(input, packages, names)
+        .into_iter()
+        .next()
+        .map(|found| (found.idx, found.alias, found.rest))
+}
+
+/// How to compare package names, see [`TagParser::case_insensitive_names`][],
+/// [`TagParser::separator_insensitive_names`][] and [`TagParser::strip_npm_scopes`][]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct NameMatcher<'i> {
+    case_insensitive: bool,
+    separator_insensitive: bool,
+    strip_scopes: bool,
+    /// An index of the names of the packages being matched, to look them up in
+    index: Option<&'i NameIndex>,
+}
+
+impl<'i> NameMatcher<'i> {
+    /// Look names up in an index of the packages being matched, instead of going through
+    /// every package
+    pub(crate) fn with_index(self, index: &NameIndex) -> NameMatcher<'_> {
+        NameMatcher {
+            index: Some(index),
+            ..self
+        }
+    }
+
+    /// Get the index of the packages being matched, if we have one
+    pub(crate) fn index(&self) -> Option<&'i NameIndex> {
+        self.index
+    }
+
+    /// Normalize a string the way names are compared, so equal names are equal strings
+    pub(crate) fn normalize_str<'s>(&self, input: &'s str) -> std::borrow::Cow<'s, str> {
+        if self.case_insensitive || self.separator_insensitive {
+            let bytes = input.bytes().map(|b| self.normalize(b)).collect();
+            String::from_utf8(bytes)
+                .expect("only ASCII is normalized")
+                .into()
+        } else {
+            input.into()
+        }
+    }
+
+    /// Get every name a tag could use for a package, with the idx of the alias each one is
+    /// (None for the package's own name)
+    pub(crate) fn package_names<'p>(
+        &self,
+        package: &'p Package,
+    ) -> impl Iterator<Item = (Option<usize>, &'p str)> {
+        let unscoped = Some(unscoped_name(&package.name))
+            .filter(|name| self.strip_scopes && *name != package.name);
+        std::iter::once(package.name.as_str())
+            .chain(unscoped)
+            .map(|name| (None, name))
+            .chain(
+                package
+                    .aliases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, alias)| (Some(i), alias.as_str())),
+            )
+    }
+
+    /// Strip a package name off the start of the input
+    pub(crate) fn strip_prefix<'a>(&self, input: &'a str, name: &str) -> Option<&'a str> {
+        let candidate = input.get(..name.len())?;
+        let same = candidate
+            .bytes()
+            .zip(name.bytes())
+            .all(|(a, b)| self.normalize(a) == self.normalize(b));
+        same.then(|| &input[name.len()..])
+    }
+
+    fn normalize(&self, byte: u8) -> u8 {
+        let byte = if self.case_insensitive {
+            byte.to_ascii_lowercase()
+        } else {
+            byte
+        };
+        if self.separator_insensitive && byte == b'_' {
+            b'-'
+        } else {
+            byte
         }
     }
-    result
 }
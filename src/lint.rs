@@ -0,0 +1,127 @@
+//! Auditing a repo's existing tags for consistency.
+
+use std::collections::BTreeMap;
+
+use crate::errors::TagErrorKind;
+use crate::history::release_series;
+use crate::{Package, PartialAnnouncementTag, TagFormat, TagParser, TagStyle, Version};
+
+/// A problem found with one of a repo's existing tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagLint {
+    /// The tag with the problem
+    pub tag: String,
+    /// What's wrong with it
+    pub problem: LintProblem,
+}
+
+/// What's wrong with a tag found by [`TagParser::lint_tags`][]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum LintProblem {
+    /// The tag couldn't be parsed at all
+    Unparseable {
+        /// The kind of error parsing produced
+        kind: TagErrorKind,
+    },
+    /// The tag seems to name a package that isn't in the workspace
+    UnknownPackage {
+        /// The name it seems to use
+        package_name: String,
+    },
+    /// The tag is spelled in a different style than expected
+    InconsistentStyle {
+        /// The format we expected
+        expected: TagFormat,
+        /// The style it was actually spelled in
+        found: TagStyle,
+    },
+    /// The tag's version isn't newer than an earlier tag for the same package
+    NonMonotonic {
+        /// The earlier tag with the newest version up to that point
+        previous_tag: String,
+        /// The earlier tag's version
+        previous_version: Version,
+        /// This tag's version
+        version: Version,
+    },
+}
+
+impl TagParser {
+    /// Audit a repo's existing tags, reporting inconsistent styles, unknown packages,
+    /// and versions that go backwards
+    ///
+    /// `tags` should be in the order they were created (oldest first) for the
+    /// non-monotonic check to make sense. Tags are parsed and ordered like
+    /// [`TagParser::check_not_regression`][] parses and orders existing tags, since old
+    /// tags will naturally disagree with the workspace's current versions.
+    ///
+    /// Problems are returned in the order of `tags`.
+    pub fn lint_tags<I>(
+        &self,
+        packages: &[Package],
+        tags: I,
+        expected_format: TagFormat,
+    ) -> Vec<TagLint>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let parser = self.existing_tag_parser();
+        let expected_style = TagStyle::new(Some(expected_format), "");
+        let mut newest = BTreeMap::<Option<usize>, PartialAnnouncementTag>::new();
+        let mut lints = vec![];
+
+        for tag in tags {
+            let tag = tag.as_ref();
+            let mut problem = |problem| {
+                lints.push(TagLint {
+                    tag: tag.to_owned(),
+                    problem,
+                })
+            };
+            let announcing = match parser.parse_tag(packages, tag) {
+                Ok(announcing) => announcing,
+                Err(e) => {
+                    problem(match parser.unknown_package(packages, tag) {
+                        Some(unknown) => LintProblem::UnknownPackage {
+                            package_name: unknown.name.to_owned(),
+                        },
+                        None => LintProblem::Unparseable { kind: e.kind() },
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(style) = &announcing.style {
+                let unstyled = style.without_prefix();
+                if unstyled != &TagStyle::Unified && unstyled != &expected_style {
+                    problem(LintProblem::InconsistentStyle {
+                        expected: expected_format,
+                        found: style.clone(),
+                    });
+                }
+            }
+
+            let Some((series, _)) = release_series(&announcing.release) else {
+                continue;
+            };
+            match newest.get(&series) {
+                Some(previous) if self.cmp_announced(&announcing, previous).is_le() => {
+                    problem(LintProblem::NonMonotonic {
+                        previous_tag: previous.tag.clone(),
+                        previous_version: previous.announced_version(),
+                        version: announcing.announced_version(),
+                    });
+                }
+                _ => {
+                    newest.insert(series, announcing);
+                }
+            }
+        }
+        lints
+    }
+}
@@ -0,0 +1,388 @@
+//! The `axotag` command line tool, for release scripts that want axotag's tag parsing
+//! without embedding Rust.
+//!
+//! Packages are read from the cargo workspace at `--manifest-path` (or the current
+//! directory). Results are printed for humans by default, or as JSON with
+//! `--output-format json` (`check` always prints an array, even for one tag).
+//!
+//! Results go to stdout. For humans, errors and warnings go to stderr; JSON output
+//! includes them in the results instead. Every command exits with status 1 if a tag
+//! fails to parse (or no tag can be suggested), and 2 if its input can't be read.
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use axotag::bump::BumpLevel;
+use axotag::describe::TagDescription;
+use axotag::errors::TagError;
+use axotag::explain::ParseTrace;
+use axotag::{Package, PartialAnnouncementTag, TagParser, TagPreset};
+use clap::{Parser, Subcommand, ValueEnum};
+use miette::Diagnostic;
+use serde_json::{json, Value};
+
+#[derive(Parser)]
+#[command(version, about = "Parse and validate release tags")]
+struct Cli {
+    /// Path to the Cargo.toml of the workspace to read packages from
+    #[arg(long, global = true)]
+    manifest_path: Option<PathBuf>,
+    /// How to print results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Human)]
+    output_format: OutputFormat,
+    /// Read tags the way another release tool makes them
+    #[arg(long, global = true, value_enum)]
+    preset: Option<Preset>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Parse a tag and explain what it announces
+    Parse {
+        /// The tag to parse (or a git ref like `refs/tags/v1.2.3`, or a release URL)
+        tag: String,
+        /// Also explain every reading of the tag that was tried, and why it was moved past
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Suggest the next tag to create
+    Suggest {
+        /// The package to release (defaults to a unified release)
+        #[arg(long)]
+        package: Option<String>,
+        /// How to bump the version
+        #[arg(long, value_enum, default_value_t = Bump::Patch)]
+        bump: Bump,
+        /// The kind of prerelease to make with `--bump prerelease`, like `rc` or `beta`
+        #[arg(long)]
+        label: Option<String>,
+        /// Existing tags to bump from (pass `-` to read them from stdin, one per line)
+        existing_tags: Vec<String>,
+    },
+    /// Check that tags parse, with suggestions for the ones that don't
+    Check {
+        /// The tags to check (pass `-` to read them from stdin, one per line)
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Plain text
+    Human,
+    /// JSON, for other tools to consume
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Preset {
+    /// `my-app-v1.2.3` components, npm scopes left out
+    ReleasePlease,
+    /// `my-app-v1.2.3` workspace packages
+    ReleasePlz,
+    /// `v1.2.3`, with moving `v1` and `v1.2` tags
+    GithubActions,
+    /// `my-app@1.2.3` (and `@my-org/my-app@1.2.3`) monorepo packages
+    Changesets,
+    /// `my-app@1.2.3` in independent mode
+    Lerna,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Bump {
+    /// `1.2.3` => `2.0.0`
+    Major,
+    /// `1.2.3` => `1.3.0`
+    Minor,
+    /// `1.2.3` => `1.2.4`
+    Patch,
+    /// `1.2.3-rc.1` => `1.2.3-rc.2`
+    Prerelease,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let packages = match load_packages(cli.manifest_path.as_deref()) {
+        Ok(packages) => packages,
+        Err(e) => {
+            eprintln!("error: couldn't read the workspace: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let parser = match cli.preset {
+        None => TagParser::default(),
+        Some(preset) => TagParser::from_preset(match preset {
+            Preset::ReleasePlease => TagPreset::ReleasePlease,
+            Preset::ReleasePlz => TagPreset::ReleasePlz,
+            Preset::GithubActions => TagPreset::GithubActions,
+            Preset::Changesets => TagPreset::Changesets,
+            Preset::Lerna => TagPreset::Lerna,
+        }),
+    };
+    let output = cli.output_format;
+
+    match cli.command {
+        Command::Parse { tag, explain } => {
+            let report = Report::new(&parser, &packages, &tag, explain);
+            match output {
+                OutputFormat::Human => report.print_human(),
+                OutputFormat::Json => println!("{}", report.to_json()),
+            }
+            exit_code(&[report])
+        }
+        Command::Check { tags } => {
+            let tags = match read_tags(tags) {
+                Ok(tags) => tags,
+                Err(e) => {
+                    eprintln!("error: couldn't read tags from stdin: {e}");
+                    return ExitCode::from(2);
+                }
+            };
+            let reports = tags
+                .iter()
+                .map(|tag| Report::new(&parser, &packages, tag, false))
+                .collect::<Vec<_>>();
+            print_reports(output, &reports)
+        }
+        Command::Suggest {
+            package,
+            bump,
+            label,
+            existing_tags,
+        } => {
+            let package = match package {
+                Some(name) => match packages.iter().position(|p| p.name == name) {
+                    Some(idx) => Some(idx),
+                    None => {
+                        eprintln!("error: there's no package named {name} in the workspace");
+                        return ExitCode::from(2);
+                    }
+                },
+                None => None,
+            };
+            let bump = match bump {
+                Bump::Major => BumpLevel::Major,
+                Bump::Minor => BumpLevel::Minor,
+                Bump::Patch => BumpLevel::Patch,
+                Bump::Prerelease => BumpLevel::Prerelease { label },
+            };
+            let existing_tags = match read_tags(existing_tags) {
+                Ok(tags) => tags,
+                Err(e) => {
+                    eprintln!("error: couldn't read tags from stdin: {e}");
+                    return ExitCode::from(2);
+                }
+            };
+            match parser.suggest_next_tag(&packages, package, &existing_tags, &bump) {
+                Ok(next) => {
+                    match output {
+                        OutputFormat::Human => println!("{}", next.tag),
+                        OutputFormat::Json => println!("{}", json!(next)),
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    match output {
+                        OutputFormat::Human => eprintln!("error: {e}"),
+                        OutputFormat::Json => println!("{}", json!({ "error": error_json(&e) })),
+                    }
+                    ExitCode::FAILURE
+                }
+            }
+        }
+    }
+}
+
+/// Read the workspace's packages with `cargo metadata`
+fn load_packages(manifest_path: Option<&Path>) -> Result<Vec<Package>, cargo_metadata::Error> {
+    let mut command = cargo_metadata::MetadataCommand::new();
+    command.no_deps();
+    if let Some(manifest_path) = manifest_path {
+        command.manifest_path(manifest_path);
+    }
+    let metadata = command.exec()?;
+    let root = &metadata.workspace_root;
+    let packages = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|package| Package {
+            name: package.name.to_string(),
+            version: Some(package.version.clone()),
+            path: package
+                .manifest_path
+                .parent()
+                .and_then(|dir| dir.strip_prefix(root).ok())
+                .map(|dir| dir.to_string()),
+            excluded: package.metadata["dist"]["dist"] == false,
+            ..Default::default()
+        })
+        .collect();
+    Ok(packages)
+}
+
+/// Expand a lone `-` into the lines of stdin
+fn read_tags(tags: Vec<String>) -> std::io::Result<Vec<String>> {
+    if tags != ["-"] {
+        return Ok(tags);
+    }
+    let lines = std::io::stdin()
+        .lock()
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()?;
+    Ok(lines
+        .into_iter()
+        .map(|line| line.trim().to_owned())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// The result of parsing a tag, with suggestions if it failed
+struct Report<'a> {
+    /// What the user passed in, which may be a git ref or a URL
+    input: &'a str,
+    /// The tag in the input, which is what gets parsed (and fixed)
+    tag: String,
+    result: Result<PartialAnnouncementTag, TagError>,
+    /// What the tag announces, if it parsed
+    description: Option<TagDescription>,
+    /// Packages the tag might have meant, if it failed
+    did_you_mean: Vec<String>,
+    /// How the tag was read, with `--explain`
+    trace: Option<ParseTrace>,
+}
+
+impl<'a> Report<'a> {
+    /// Parse the tag in `input`, recording how it was read if `explain` is set
+    fn new(parser: &TagParser, packages: &[Package], input: &'a str, explain: bool) -> Report<'a> {
+        let (tag, origin) = match axotag::refs::extract_tag(input) {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                return Report {
+                    input,
+                    tag: input.to_owned(),
+                    result: Err(e),
+                    description: None,
+                    did_you_mean: vec![],
+                    trace: None,
+                }
+            }
+        };
+        let (result, trace) = if explain {
+            let (result, trace) = parser.parse_tag_traced(packages, &tag);
+            (result, Some(trace))
+        } else {
+            (parser.parse_tag(packages, &tag), None)
+        };
+        let result = result.map(|announcing| PartialAnnouncementTag {
+            origin,
+            ..announcing
+        });
+        let description =
+            (result.as_ref().ok()).map(|announcing| parser.description(announcing, packages));
+        let did_you_mean = match result {
+            Ok(_) => vec![],
+            Err(_) => parser
+                .did_you_mean(packages, &tag)
+                .into_iter()
+                .map(|idx| packages[idx].name.clone())
+                .collect(),
+        };
+        Report {
+            input,
+            tag,
+            result,
+            description,
+            did_you_mean,
+            trace,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut report = match &self.result {
+            Ok(announcing) => json!({
+                "tag": self.input,
+                "ok": true,
+                "description": self.description,
+                "announcement": announcing,
+            }),
+            Err(e) => json!({
+                "tag": self.input,
+                "ok": false,
+                "error": error_json(e),
+                "did_you_mean": self.did_you_mean,
+                "fixed_tags": e.fix_its().iter().map(|fix| fix.apply(&self.tag)).collect::<Vec<_>>(),
+            }),
+        };
+        if let Some(trace) = &self.trace {
+            report["trace"] = json!(trace);
+        }
+        report
+    }
+
+    fn print_human(&self) {
+        match &self.result {
+            Ok(announcing) => {
+                if let Some(description) = &self.description {
+                    println!("{}: {description}", self.input);
+                }
+                for warning in &announcing.warnings {
+                    eprintln!("{}: warning: {warning}", self.input);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: error: {e}", self.input);
+                if let Some(help) = e.help() {
+                    eprintln!("  help: {help}");
+                }
+                for name in &self.did_you_mean {
+                    eprintln!("  did you mean {name}?");
+                }
+                for fix in e.fix_its() {
+                    eprintln!("  try: {}", fix.apply(&self.tag));
+                }
+            }
+        }
+        if let Some(trace) = &self.trace {
+            for line in trace.to_string().lines() {
+                println!("  trace: {line}");
+            }
+        }
+    }
+}
+
+/// Describe an error for JSON output
+fn error_json(error: &TagError) -> Value {
+    json!({
+        "code": error.code(),
+        "kind": error.kind(),
+        "message": error.to_string(),
+        "help": error.help().map(|help| help.to_string()),
+        "fix_its": error.fix_its(),
+    })
+}
+
+/// Print reports (as a JSON array, however many there are), exiting with a failure if
+/// any of the tags didn't parse
+fn print_reports(output: OutputFormat, reports: &[Report]) -> ExitCode {
+    match output {
+        OutputFormat::Json => {
+            let reports = reports.iter().map(Report::to_json).collect();
+            println!("{}", Value::Array(reports));
+        }
+        OutputFormat::Human => reports.iter().for_each(Report::print_human),
+    }
+    exit_code(reports)
+}
+
+/// Exit with a failure if any of the tags didn't parse
+fn exit_code(reports: &[Report]) -> ExitCode {
+    if reports.iter().all(|r| r.result.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
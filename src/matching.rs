@@ -0,0 +1,110 @@
+//! Finding package names at the start of arbitrary strings.
+//!
+//! This is the name matching [`TagParser::parse_tag`][] does, for things that aren't
+//! tags (like artifact filenames, `my-app-x86_64-unknown-linux-gnu.tar.gz`).
+
+use crate::{NameMatcher, Package, TagParser};
+
+/// How [`match_package_prefix`][] compares package names
+///
+/// [`TagParser::match_options`][] gets the options a parser uses for tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchOptions {
+    /// Whether names match regardless of ASCII case, see [`TagParser::case_insensitive_names`][]
+    pub case_insensitive: bool,
+    /// Whether `-` and `_` are the same, see [`TagParser::separator_insensitive_names`][]
+    pub separator_insensitive: bool,
+    /// Whether npm-scoped names can leave out their scope, see [`TagParser::strip_npm_scopes`][]
+    pub strip_npm_scopes: bool,
+}
+
+/// A package whose name (or alias) starts some input, see [`match_package_prefix`][]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackageMatch<'a> {
+    /// The idx of the package
+    pub idx: usize,
+    /// The idx of the alias that matched, if it wasn't the package's name
+    pub alias: Option<usize>,
+    /// The part of the input that matched, as the input spelled it
+    pub matched: &'a str,
+    /// The rest of the input
+    pub rest: &'a str,
+}
+
+impl PackageMatch<'_> {
+    /// How many bytes of the input matched
+    pub fn match_len(&self) -> usize {
+        self.matched.len()
+    }
+}
+
+/// Find every package whose name (or alias) starts the input, longest match first
+///
+/// Matches of the same length keep their order (packages in input order, a package's name
+/// before its aliases), so the first result is the one [`TagParser::parse_tag`][] would
+/// prefer. Nothing checks what comes after the name, so `my-app` matches `my-apple.zip`
+/// with the rest `le.zip`; check [`PackageMatch::rest`][] for a separator if that matters.
+/// A package can match several times (through its name and aliases).
+pub fn match_package_prefix<'a>(
+    input: &'a str,
+    packages: &[Package],
+    options: MatchOptions,
+) -> Vec<PackageMatch<'a>> {
+    package_prefixes(input, packages, options.into())
+}
+
+impl TagParser {
+    /// Get the options this parser compares package names with, for [`match_package_prefix`][]
+    pub fn match_options(&self) -> MatchOptions {
+        MatchOptions {
+            case_insensitive: self.case_insensitive_names,
+            separator_insensitive: self.separator_insensitive_names,
+            strip_npm_scopes: self.strip_npm_scopes,
+        }
+    }
+}
+
+impl From<MatchOptions> for NameMatcher<'_> {
+    fn from(options: MatchOptions) -> Self {
+        NameMatcher {
+            case_insensitive: options.case_insensitive,
+            separator_insensitive: options.separator_insensitive,
+            strip_scopes: options.strip_npm_scopes,
+            index: None,
+        }
+    }
+}
+
+/// [`match_package_prefix`][], with the matcher we already have
+///
+/// Callers that need more than a prefix (like a separator after it) can fall back to the
+/// shorter matches, so with packages `sdk` and `sdk-v2` the tag `sdk-v2.0.0` can still be
+/// read as `sdk` at `2.0.0`.
+///
+/// If the matcher has an index of the packages, names are looked up in it instead.
+pub(crate) fn package_prefixes<'a>(
+    input: &'a str,
+    packages: &[Package],
+    names: NameMatcher,
+) -> Vec<PackageMatch<'a>> {
+    if let Some(index) = names.index() {
+        return index.prefixes(input);
+    }
+    let mut result = vec![];
+    for (idx, package) in packages.iter().enumerate() {
+        for (alias, name) in names.package_names(package) {
+            if let Some(rest) = names.strip_prefix(input, name) {
+                crate::trace::trace!(package = package.name, name, "name prefixes the input");
+                result.push(PackageMatch {
+                    idx,
+                    alias,
+                    matched: &input[..input.len() - rest.len()],
+                    rest,
+                });
+            }
+        }
+    }
+    // A stable sort, so ties keep their order
+    result.sort_by_key(|found| found.rest.len());
+    result
+}
@@ -0,0 +1,103 @@
+//! What git knows about a tag besides its name, and policies about it.
+//!
+//! axotag doesn't talk to git: callers read a tag's metadata (with `git for-each-ref` or a
+//! git library) into a [`TagMetadata`][], and axotag checks it against
+//! [`TagParser::signatures`][]. Lightweight tags don't have any metadata, so they're
+//! passed as None.
+
+use thiserror::Error;
+
+use crate::errors::{TagError, TagResult};
+use crate::plan::AnnouncementPlan;
+use crate::{Package, TagParser};
+
+/// What an annotated tag records besides the commit it points at
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagMetadata {
+    /// Who made the tag, like `Jane Doe <jane@example.com>`
+    pub tagger: Option<String>,
+    /// When the tag was made, in seconds since the Unix epoch
+    pub date: Option<i64>,
+    /// The tag's message
+    pub message: Option<String>,
+    /// Whether the tag is signed, and whether the signature checked out
+    pub signature: SignatureStatus,
+}
+
+/// Whether a tag is signed, see [`TagMetadata::signature`][]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SignatureStatus {
+    /// There's no signature
+    #[default]
+    #[error("isn't signed")]
+    Unsigned,
+    /// There's a signature, but nobody checked it (or its key isn't known)
+    #[error("has an unverified signature")]
+    Unverified,
+    /// The signature checked out
+    #[error("has a good signature")]
+    Good {
+        /// Who the signing key belongs to, if known
+        signer: Option<String>,
+    },
+    /// The signature doesn't match the tag
+    #[error("has a bad signature")]
+    Bad,
+}
+
+/// What signatures release tags need, see [`TagParser::signatures`][]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignaturePolicy {
+    /// Any tag will do, even a lightweight one
+    #[default]
+    Any,
+    /// Tags have to be signed, but the signature doesn't have to have been verified
+    ///
+    /// Bad signatures still fail.
+    Signed,
+    /// Tags have to have a [`SignatureStatus::Good`][] signature
+    Verified,
+}
+
+impl TagParser {
+    /// Check a tag's metadata (None for a lightweight tag) against [`TagParser::signatures`][]
+    ///
+    /// Fails with [`TagError::SignatureRequired`][] if the tag isn't signed like it needs
+    /// to be.
+    pub fn check_tag_metadata(&self, tag: &str, metadata: Option<&TagMetadata>) -> TagResult<()> {
+        let status = metadata.map(|m| m.signature.clone()).unwrap_or_default();
+        let ok = match self.signatures {
+            SignaturePolicy::Any => true,
+            SignaturePolicy::Signed => matches!(
+                status,
+                SignatureStatus::Unverified | SignatureStatus::Good { .. }
+            ),
+            SignaturePolicy::Verified => matches!(status, SignatureStatus::Good { .. }),
+        };
+        if ok {
+            return Ok(());
+        }
+        Err(TagError::SignatureRequired {
+            tag: tag.to_owned(),
+            status,
+            policy: self.signatures,
+        })
+    }
+
+    /// Parse a tag into an [`AnnouncementPlan`][] like [`TagParser::plan_tag`][], checking
+    /// its metadata (None for a lightweight tag) and recording it in the plan
+    pub fn plan_tag_with_metadata(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        metadata: Option<TagMetadata>,
+    ) -> TagResult<AnnouncementPlan> {
+        let plan = self.plan_tag(packages, tag)?;
+        self.check_tag_metadata(tag, metadata.as_ref())?;
+        Ok(AnnouncementPlan { metadata, ..plan })
+    }
+}
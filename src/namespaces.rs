@@ -0,0 +1,97 @@
+//! Tagging the packages of sub-projects (nested workspaces) under their own namespace.
+//!
+//! A monorepo with a Rust workspace and an npm one can have a `my-app` in both. Putting
+//! each sub-project's packages in a [`Namespace`][] (see [`Package::namespace`][]) lets
+//! tags say which one they mean, like `rust/my-app-v1.2.3` and `js/my-app@1.2.3`, and
+//! each sub-project can keep its ecosystem's tag conventions.
+
+use crate::errors::TagResult;
+use crate::explain::ParseTrace;
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagParser};
+
+/// A sub-project whose tags start with its name, see [`TagParser::namespaces`][]
+#[derive(Debug, Clone, Default)]
+pub struct Namespace {
+    /// The name of the namespace, which tags start with (`js` in `js/my-app@1.2.3`)
+    pub name: String,
+    /// How the rest of tags in this namespace are parsed and formatted, like
+    /// [`TagParser::changesets`][] for an npm sub-project
+    ///
+    /// If None, they're parsed like tags outside the namespace. This parser's own
+    /// namespaces are ignored, namespaces don't nest.
+    pub parser: Option<TagParser>,
+}
+
+impl TagParser {
+    /// If a tag starts with one of our [`TagParser::namespaces`][], parse the rest of it
+    /// against just that namespace's packages
+    ///
+    /// Indices in the result still refer to `packages`. Errors are about the part of the
+    /// tag after the namespace.
+    pub(crate) fn parse_namespaced(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        mut trace: Option<&mut ParseTrace>,
+    ) -> Option<TagResult<PartialAnnouncementTag>> {
+        let (namespace, rest) = self.find_namespace(tag)?;
+        crate::trace::debug!(namespace = namespace.name, "tag is namespaced");
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.namespace = Some(namespace.name.clone());
+        }
+        let (members, subset) = namespace_members(packages, namespace);
+        let result = self
+            .namespace_parser(namespace)
+            .parse_normalized_tag(&subset, rest, None, trace)
+            .map(|mut announcing| {
+                match &mut announcing.release {
+                    AnnouncementKind::Package { idx, .. }
+                    | AnnouncementKind::Channel { idx: Some(idx), .. } => {
+                        *idx = members[*idx];
+                    }
+                    _ => {}
+                }
+                announcing.tag = tag.to_owned();
+                announcing.namespace = Some(namespace.name.clone());
+                announcing
+            });
+        Some(result)
+    }
+
+    /// Find the namespace a tag starts with, and the rest of the tag
+    pub(crate) fn find_namespace<'t>(&self, tag: &'t str) -> Option<(&Namespace, &'t str)> {
+        self.namespaces.iter().find_map(|namespace| {
+            let rest = tag.strip_prefix(&namespace.name)?.strip_prefix('/')?;
+            Some((namespace, rest))
+        })
+    }
+
+    /// Get the namespace a package's tags go in, if it's in one of our [`TagParser::namespaces`][]
+    pub(crate) fn namespace_of(&self, package: &Package) -> Option<&Namespace> {
+        let name = package.namespace.as_deref()?;
+        self.namespaces
+            .iter()
+            .find(|namespace| namespace.name == name)
+    }
+
+    /// Get a parser for the part of a tag after the given namespace
+    pub(crate) fn namespace_parser(&self, namespace: &Namespace) -> TagParser {
+        TagParser {
+            namespaces: vec![],
+            ..namespace.parser.as_ref().unwrap_or(self).clone()
+        }
+    }
+}
+
+/// Get the indices of a namespace's packages, and the packages themselves
+pub(crate) fn namespace_members(
+    packages: &[Package],
+    namespace: &Namespace,
+) -> (Vec<usize>, Vec<Package>) {
+    packages
+        .iter()
+        .enumerate()
+        .filter(|(_, package)| package.namespace.as_deref() == Some(&namespace.name))
+        .map(|(idx, package)| (idx, package.clone()))
+        .unzip()
+}
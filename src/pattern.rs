@@ -0,0 +1,94 @@
+//! Custom regex-based tag patterns, for conventions the built-in [`TagFormat`][]s don't cover.
+//!
+//! [`TagFormat`]: crate::TagFormat
+
+use regex::Regex;
+
+use crate::errors::{TagError, TagResult};
+use crate::grammar::{Confidence, MatchContext, PackageSpan, TagMatch, TagMatcher};
+use crate::TagStyle;
+
+/// A regex describing how to find the package and version in a tag
+///
+/// The regex must have a `version` group, and can have a `package` group. The
+/// `package` group must be the name (or an alias) of one of the packages (respecting
+/// [`TagParser::case_insensitive_names`][] and friends), otherwise the
+/// pattern doesn't apply to the tag (tags without a `package` group, or where that
+/// group didn't participate in the match, are unified releases). The regex has to
+/// match the whole tag.
+///
+/// Versions are parsed the same way as in the built-in formats, so
+/// [`TagParser::v_prefix`][], [`TagParser::lenient_versions`][] and
+/// [`TagParser::channels`][] all apply.
+///
+/// [`TagParser::case_insensitive_names`]: crate::TagParser::case_insensitive_names
+/// [`TagParser::v_prefix`]: crate::TagParser::v_prefix
+/// [`TagParser::lenient_versions`]: crate::TagParser::lenient_versions
+/// [`TagParser::channels`]: crate::TagParser::channels
+#[derive(Debug, Clone)]
+pub struct TagPattern {
+    regex: Regex,
+}
+
+impl TagPattern {
+    /// Compile a pattern like `^release-(?P<package>[a-z-]+)@(?P<version>.+)$`
+    pub fn new(pattern: &str) -> TagResult<TagPattern> {
+        let regex = Regex::new(pattern).map_err(|e| TagError::InvalidTagPattern {
+            pattern: pattern.to_owned(),
+            reason: e.to_string(),
+        })?;
+        if !regex.capture_names().any(|name| name == Some("version")) {
+            return Err(TagError::InvalidTagPattern {
+                pattern: pattern.to_owned(),
+                reason: "it doesn't have a `version` group".to_owned(),
+            });
+        }
+        Ok(TagPattern { regex })
+    }
+
+    /// Get the source of the regex
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+}
+
+impl TagMatcher for TagPattern {
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch> {
+        let captures = self.regex.captures(tag)?;
+        let whole = captures.get(0)?;
+        if whole.start() != 0 || whole.end() != tag.len() {
+            return None;
+        }
+        let version = captures.name("version")?.range();
+        let (package, confidence) = match captures.name("package") {
+            Some(name) => match cx.strip_package_prefix(name.as_str())? {
+                found if found.rest.is_empty() => (
+                    Some(PackageSpan {
+                        idx: found.idx,
+                        alias: found.alias,
+                        range: name.range(),
+                    }),
+                    found.confidence,
+                ),
+                _ => return None,
+            },
+            None => (None, Confidence::Exact),
+        };
+        // Patterns have no arbitrary prefix, and only a separator if the package comes first
+        let separator = match &package {
+            Some(package) if package.range.end <= version.start => package.range.end..version.start,
+            _ => version.start..version.start,
+        };
+        Some(TagMatch {
+            prefix: 0..0,
+            package,
+            separator,
+            version,
+            confidence,
+            format: None,
+            style: TagStyle::Pattern {
+                pattern: self.as_str().to_owned(),
+            },
+        })
+    }
+}
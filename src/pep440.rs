@@ -0,0 +1,373 @@
+//! Python (PEP 440) versions, like `1.2.3.post1` or `1.2rc1`, for packages that use
+//! [`VersionScheme::Pep440`][crate::VersionScheme::Pep440].
+//!
+//! Tags still announce a semver [`Version`][] (see [`Pep440Version::to_semver`][]), which
+//! is what gets checked against the package's declared version. The PEP 440 version
+//! itself ends up in [`PartialAnnouncementTag::pep440`][crate::PartialAnnouncementTag::pep440],
+//! and is what announcements are ordered by.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use miette::Diagnostic;
+use semver::{BuildMetadata, Prerelease, Version};
+use thiserror::Error;
+
+use crate::errors::{TagError, TagResult};
+use crate::lenient::TagVersion;
+use crate::TagParser;
+
+/// A PEP 440 version, normalized
+///
+/// Comparisons follow PEP 440, so `1.2.dev1 < 1.2a1 < 1.2 < 1.2.post1`, and `1.2`
+/// equals `1.2.0`.
+#[derive(Debug, Clone)]
+pub struct Pep440Version {
+    /// The epoch (`2` for `2!1.2.3`), 0 if there wasn't one
+    pub epoch: u64,
+    /// The release components (`[1, 2, 3]` for `1.2.3`)
+    pub release: Vec<u64>,
+    /// The pre-release (`(Rc, 1)` for `1.2rc1`)
+    pub pre: Option<(PreReleaseKind, u64)>,
+    /// The post-release number (`1` for `1.2.3.post1`)
+    pub post: Option<u64>,
+    /// The development release number (`4` for `1.2.3.dev4`)
+    pub dev: Option<u64>,
+    /// The segments of the local version label (`["ubuntu", "1"]` for `1.2.3+ubuntu.1`)
+    pub local: Vec<String>,
+}
+
+/// The kind of a PEP 440 pre-release, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum PreReleaseKind {
+    /// `a` (or `alpha`)
+    Alpha,
+    /// `b` (or `beta`)
+    Beta,
+    /// `rc` (or `c`, `pre`, `preview`)
+    Rc,
+}
+
+/// A version that isn't PEP 440, or that has no semver equivalent
+#[derive(Debug, Clone, Error, Diagnostic, PartialEq, Eq)]
+pub enum Pep440Error {
+    /// The version isn't PEP 440 at all
+    #[error("{version} isn't a PEP 440 version")]
+    Invalid {
+        /// The version
+        version: String,
+    },
+    /// The version has more release components than semver does
+    #[error("{version} has more than 3 release components, so it has no semver equivalent")]
+    TooManyComponents {
+        /// The version
+        version: String,
+    },
+}
+
+impl PreReleaseKind {
+    /// Get the normalized spelling of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PreReleaseKind::Alpha => "a",
+            PreReleaseKind::Beta => "b",
+            PreReleaseKind::Rc => "rc",
+        }
+    }
+}
+
+impl Pep440Version {
+    /// Convert this to semver, for checking against a package's declared version
+    ///
+    /// The first 3 release components become `major.minor.patch` (missing ones are 0),
+    /// pre-releases and dev releases become the prerelease (`1.2rc1.dev3` is
+    /// `1.2.0-rc.1.dev.3`), and post-releases and local labels become build metadata
+    /// (`1.2.3.post1+ubuntu.1` is `1.2.3+post.1.local.ubuntu.1`). The epoch is dropped.
+    ///
+    /// The result isn't ordered like PEP 440 is, so compare [`Pep440Version`][]s instead.
+    pub fn to_semver(&self) -> Result<Version, Pep440Error> {
+        if self.release.len() > 3 {
+            return Err(Pep440Error::TooManyComponents {
+                version: self.to_string(),
+            });
+        }
+        let component = |i: usize| self.release.get(i).copied().unwrap_or(0);
+        let mut pre = vec![];
+        if let Some((kind, n)) = self.pre {
+            pre.push(format!("{}.{n}", kind.as_str()));
+        }
+        if let Some(n) = self.dev {
+            pre.push(format!("dev.{n}"));
+        }
+        let mut build = vec![];
+        if let Some(n) = self.post {
+            build.push(format!("post.{n}"));
+        }
+        if !self.local.is_empty() {
+            build.push(format!("local.{}", self.local.join(".")));
+        }
+        let mut version = Version::new(component(0), component(1), component(2));
+        version.pre = Prerelease::new(&pre.join(".")).expect("PEP 440 labels are semver");
+        version.build = BuildMetadata::new(&build.join(".")).expect("PEP 440 labels are semver");
+        Ok(version)
+    }
+
+    /// Get everything that matters for ordering, per PEP 440
+    fn cmp_key(&self) -> impl Ord + '_ {
+        /// Where the pre-release sorts, dev releases of the final release come first
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        enum PreKey {
+            DevOfFinal,
+            Pre(PreReleaseKind, u64),
+            Final,
+        }
+        /// Local segments, labels come before numbers
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        enum LocalKey<'a> {
+            Label(&'a str),
+            Number(u64),
+        }
+        let zeros = self.release.iter().rev().take_while(|c| **c == 0).count();
+        let release = &self.release[..self.release.len() - zeros];
+        let pre = match self.pre {
+            Some((kind, n)) => PreKey::Pre(kind, n),
+            None if self.post.is_none() && self.dev.is_some() => PreKey::DevOfFinal,
+            None => PreKey::Final,
+        };
+        // Versions without a dev release come after ones with one
+        let dev = (self.dev.is_none(), self.dev);
+        let local = (!self.local.is_empty()).then(|| {
+            self.local
+                .iter()
+                .map(|segment| match segment.parse() {
+                    Ok(n) => LocalKey::Number(n),
+                    Err(_) => LocalKey::Label(segment),
+                })
+                .collect::<Vec<_>>()
+        });
+        (self.epoch, release, pre, self.post, dev, local)
+    }
+}
+
+impl PartialEq for Pep440Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Pep440Version {}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_key().cmp(&other.cmp_key())
+    }
+}
+
+impl fmt::Display for Pep440Version {
+    /// Write the normalized form of the version, like `2!1.2rc1.post3.dev4+ubuntu.1`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release = self
+            .release
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        write!(f, "{release}")?;
+        if let Some((kind, n)) = self.pre {
+            write!(f, "{}{n}", kind.as_str())?;
+        }
+        if let Some(n) = self.post {
+            write!(f, ".post{n}")?;
+        }
+        if let Some(n) = self.dev {
+            write!(f, ".dev{n}")?;
+        }
+        if !self.local.is_empty() {
+            write!(f, "+{}", self.local.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Pep440Version {
+    type Err = Pep440Error;
+
+    /// Parse a PEP 440 version, accepting every spelling PEP 440 normalizes
+    /// (`1.0-RC.1`, `1.0c1`, `1.0-1`, and so on)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || Pep440Error::Invalid {
+            version: input.to_owned(),
+        };
+        let lower = input.to_ascii_lowercase();
+        let mut cursor = Cursor { rest: &lower };
+
+        let mut epoch = 0;
+        if let Some((digits, rest)) = cursor.rest.split_once('!') {
+            epoch = Cursor { rest: digits }.whole_number().ok_or_else(invalid)?;
+            cursor.rest = rest;
+        }
+        let mut release = vec![cursor.number().ok_or_else(invalid)?];
+        while let Some(component) = cursor.attempt(|c| c.eat(".").then(|| c.number()).flatten()) {
+            release.push(component);
+        }
+        let pre = cursor.attempt(|c| {
+            c.separator();
+            let kind = [
+                ("alpha", PreReleaseKind::Alpha),
+                ("a", PreReleaseKind::Alpha),
+                ("beta", PreReleaseKind::Beta),
+                ("b", PreReleaseKind::Beta),
+                ("preview", PreReleaseKind::Rc),
+                ("pre", PreReleaseKind::Rc),
+                ("rc", PreReleaseKind::Rc),
+                ("c", PreReleaseKind::Rc),
+            ]
+            .into_iter()
+            .find_map(|(label, kind)| c.eat(label).then_some(kind))?;
+            Some((kind, c.label_number()))
+        });
+        let post = cursor
+            .attempt(|c| c.eat("-").then(|| c.number()).flatten())
+            .or_else(|| {
+                cursor.attempt(|c| {
+                    c.separator();
+                    ["post", "rev", "r"]
+                        .into_iter()
+                        .find(|label| c.eat(label))?;
+                    Some(c.label_number())
+                })
+            });
+        let dev = cursor.attempt(|c| {
+            c.separator();
+            c.eat("dev").then(|| c.label_number())
+        });
+        let mut local = vec![];
+        if cursor.eat("+") {
+            for segment in cursor.rest.split(['-', '_', '.']) {
+                if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                    return Err(invalid());
+                }
+                local.push(segment.to_owned());
+            }
+            cursor.rest = "";
+        }
+        if !cursor.rest.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Pep440Version {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pep440Version {
+    /// Serialize the normalized form of the version, like semver versions are
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Pep440Version {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Pep440Version".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({ "type": "string" })
+    }
+}
+
+/// What's left of a version we're parsing
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    /// Run a parser, only consuming input if it succeeds
+    fn attempt<T>(&mut self, parse: impl FnOnce(&mut Cursor<'a>) -> Option<T>) -> Option<T> {
+        let start = self.rest;
+        let result = parse(self);
+        if result.is_none() {
+            self.rest = start;
+        }
+        result
+    }
+
+    /// Consume some exact text, if it's next
+    fn eat(&mut self, text: &str) -> bool {
+        match self.rest.strip_prefix(text) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consume one of the separators PEP 440 allows between parts, if it's next
+    fn separator(&mut self) {
+        let _ = self.eat("-") || self.eat("_") || self.eat(".");
+    }
+
+    /// Consume a number
+    fn number(&mut self) -> Option<u64> {
+        let len = self.rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (digits, rest) = self.rest.split_at(len);
+        let number = digits.parse().ok()?;
+        self.rest = rest;
+        Some(number)
+    }
+
+    /// Consume the number after a label like `rc`, which can be separated or left out
+    /// (meaning 0)
+    fn label_number(&mut self) -> u64 {
+        self.attempt(|c| {
+            c.separator();
+            c.number()
+        })
+        .unwrap_or(0)
+    }
+
+    /// Parse the whole input as a number
+    fn whole_number(&mut self) -> Option<u64> {
+        self.number().filter(|_| self.rest.is_empty())
+    }
+}
+
+impl TagParser {
+    /// Parse the version (without any `v`) of a tag for a [`VersionScheme::Pep440`][crate::VersionScheme::Pep440] package
+    pub(crate) fn parse_pep440_version(&self, tag: &str, version: &str) -> TagResult<TagVersion> {
+        let into_error = |details| TagError::Pep440Parse {
+            tag: tag.to_owned(),
+            details,
+        };
+        let pep440 = version.parse::<Pep440Version>().map_err(into_error)?;
+        Ok(TagVersion {
+            version: pep440.to_semver().map_err(into_error)?,
+            warnings: vec![],
+            epoch: (pep440.epoch != 0).then_some(pep440.epoch),
+            build_number: None,
+            branch: None,
+            pep440: Some(pep440),
+        })
+    }
+}
@@ -0,0 +1,263 @@
+//! Self-contained descriptions of what a tag will release, which can be stored and compared.
+
+use std::fmt;
+
+use crate::errors::TagResult;
+use crate::metadata::TagMetadata;
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagFormat, TagParser, Version};
+
+/// What a tag will release, without referring back to the packages
+///
+/// With the `serde` feature this can be stored (say, when a release PR is opened) and
+/// compared to a later plan for the same release with [`AnnouncementPlan::diff`][], to
+/// catch anything that drifted in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AnnouncementPlan {
+    /// The full tag
+    pub tag: String,
+    /// What kind of tag it is
+    pub kind: PlanKind,
+    /// The version the tag announces, if it has one
+    pub version: Option<Version>,
+    /// The packages that will be released, in input order
+    pub packages: Vec<PlannedPackage>,
+    /// Whether it's a prerelease
+    pub prerelease: bool,
+    /// The first of [`TagParser::formats`][] that matched the tag
+    pub format: Option<TagFormat>,
+    /// What git knows about the tag, see [`TagParser::plan_tag_with_metadata`][]
+    ///
+    /// [`AnnouncementPlan::diff`][] ignores this, since a plan made before tagging
+    /// can't have it.
+    pub metadata: Option<TagMetadata>,
+}
+
+/// What kind of tag an [`AnnouncementPlan`][] is for, see [`AnnouncementKind`][]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum PlanKind {
+    /// [`AnnouncementKind::None`][]
+    None,
+    /// [`AnnouncementKind::Unified`][]
+    Unified,
+    /// [`AnnouncementKind::Package`][]
+    Package,
+    /// [`AnnouncementKind::Reserved`][]
+    Reserved,
+    /// [`AnnouncementKind::UnresolvedPackage`][]
+    UnresolvedPackage,
+    /// [`AnnouncementKind::Channel`][]
+    Channel,
+}
+
+/// A package an [`AnnouncementPlan`][] releases
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlannedPackage {
+    /// The package name
+    pub name: String,
+    /// The package's version, if it has one
+    pub version: Option<Version>,
+}
+
+/// What changed between two [`AnnouncementPlan`][]s, see [`AnnouncementPlan::diff`][]
+///
+/// Packages are identified by name, and everything is sorted by package name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlanDiff {
+    /// The tag, if it changed
+    pub tag: Option<Change<String>>,
+    /// The kind of tag, if it changed
+    pub kind: Option<Change<PlanKind>>,
+    /// The announced version, if it changed
+    pub version: Option<Change<Option<Version>>>,
+    /// Whether it's a prerelease, if that changed
+    pub prerelease: Option<Change<bool>>,
+    /// The matched format, if it changed
+    pub format: Option<Change<Option<TagFormat>>>,
+    /// Packages only the new plan releases
+    pub added: Vec<PlannedPackage>,
+    /// Packages only the old plan releases
+    pub removed: Vec<PlannedPackage>,
+    /// Packages both plans release, but at different versions
+    pub changed: Vec<PackageChange>,
+}
+
+/// A value that differs between two plans
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Change<T> {
+    /// The value in the old plan
+    pub before: T,
+    /// The value in the new plan
+    pub after: T,
+}
+
+/// A package whose version differs between two plans
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PackageChange {
+    /// The package name
+    pub name: String,
+    /// The version in the old plan
+    pub before: Option<Version>,
+    /// The version in the new plan
+    pub after: Option<Version>,
+}
+
+impl AnnouncementPlan {
+    /// Compare this plan to a newer one
+    pub fn diff(&self, after: &AnnouncementPlan) -> PlanDiff {
+        let mut diff = PlanDiff {
+            tag: change(&self.tag, &after.tag),
+            kind: change(&self.kind, &after.kind),
+            version: change(&self.version, &after.version),
+            prerelease: change(&self.prerelease, &after.prerelease),
+            format: change(&self.format, &after.format),
+            ..PlanDiff::default()
+        };
+        let find = |plan: &AnnouncementPlan, name: &str| {
+            plan.packages.iter().find(|p| p.name == name).cloned()
+        };
+        for package in &self.packages {
+            match find(after, &package.name) {
+                None => diff.removed.push(package.clone()),
+                Some(new) if new.version != package.version => diff.changed.push(PackageChange {
+                    name: package.name.clone(),
+                    before: package.version.clone(),
+                    after: new.version,
+                }),
+                Some(_) => {}
+            }
+        }
+        for package in &after.packages {
+            if find(self, &package.name).is_none() {
+                diff.added.push(package.clone());
+            }
+        }
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff
+    }
+}
+
+impl PlanDiff {
+    /// Whether the plans are the same
+    pub fn is_empty(&self) -> bool {
+        *self == PlanDiff::default()
+    }
+}
+
+/// One line per change, like `version: 1.2.3 -> 1.2.4` or `+ my-app 1.2.4`
+impl fmt::Display for PlanDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |value: &Option<Version>| match value {
+            Some(version) => version.to_string(),
+            None => "(none)".to_owned(),
+        };
+        let mut lines = vec![];
+        if let Some(Change { before, after }) = &self.tag {
+            lines.push(format!("tag: {before} -> {after}"));
+        }
+        if let Some(Change { before, after }) = &self.kind {
+            lines.push(format!("kind: {before:?} -> {after:?}"));
+        }
+        if let Some(Change { before, after }) = &self.version {
+            lines.push(format!(
+                "version: {} -> {}",
+                optional(before),
+                optional(after)
+            ));
+        }
+        if let Some(Change { before, after }) = &self.prerelease {
+            lines.push(format!("prerelease: {before} -> {after}"));
+        }
+        if let Some(Change { before, after }) = &self.format {
+            lines.push(format!("format: {before:?} -> {after:?}"));
+        }
+        for package in &self.removed {
+            lines.push(format!("- {} {}", package.name, optional(&package.version)));
+        }
+        for package in &self.added {
+            lines.push(format!("+ {} {}", package.name, optional(&package.version)));
+        }
+        for package in &self.changed {
+            lines.push(format!(
+                "~ {} {} -> {}",
+                package.name,
+                optional(&package.before),
+                optional(&package.after)
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+fn change<T: Clone + PartialEq>(before: &T, after: &T) -> Option<Change<T>> {
+    (before != after).then(|| Change {
+        before: before.clone(),
+        after: after.clone(),
+    })
+}
+
+impl PartialAnnouncementTag {
+    /// Describe what this announcement releases, see [`AnnouncementPlan`][]
+    ///
+    /// `packages` must be the ones the tag was parsed with. Packages are selected like
+    /// [`PartialAnnouncementTag::selected_packages`][] selects them, see
+    /// [`TagParser::plan_tag`][] to respect the parser's policies.
+    pub fn plan(&self, packages: &[Package]) -> AnnouncementPlan {
+        self.plan_selected(packages, self.selected_packages(packages))
+    }
+
+    /// Describe what this announcement releases, given the packages it selects
+    fn plan_selected(&self, packages: &[Package], selected: Vec<usize>) -> AnnouncementPlan {
+        let kind = match &self.release {
+            AnnouncementKind::None => PlanKind::None,
+            AnnouncementKind::Unified(_) => PlanKind::Unified,
+            AnnouncementKind::Package { .. } => PlanKind::Package,
+            AnnouncementKind::Reserved { .. } => PlanKind::Reserved,
+            AnnouncementKind::UnresolvedPackage { .. } => PlanKind::UnresolvedPackage,
+            AnnouncementKind::Channel { .. } => PlanKind::Channel,
+        };
+        let packages = selected
+            .into_iter()
+            .map(|idx| PlannedPackage {
+                name: packages[idx].name.clone(),
+                // Packages without a version adopt the tag's
+                version: (packages[idx].version.as_ref())
+                    .or(self.release.version())
+                    .cloned(),
+            })
+            .collect();
+        AnnouncementPlan {
+            tag: self.tag.clone(),
+            kind,
+            version: self.release.version().cloned(),
+            packages,
+            prerelease: self.prerelease,
+            format: self.format,
+            metadata: None,
+        }
+    }
+}
+
+impl TagParser {
+    /// Parse a tag into an [`AnnouncementPlan`][]
+    ///
+    /// Packages are selected with [`TagParser::selected_packages`][].
+    pub fn plan_tag(&self, packages: &[Package], tag: &str) -> TagResult<AnnouncementPlan> {
+        let announcing = self.parse_tag(packages, tag)?;
+        let selected = self.selected_packages(&announcing, packages);
+        Ok(announcing.plan_selected(packages, selected))
+    }
+}
@@ -0,0 +1,193 @@
+//! Settings matching the conventions of other release tools, so repos migrating to
+//! cargo-dist keep their tag history parseable.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::grammar::{MatchContext, PackageSpan, TagMatch, TagMatcher};
+use crate::{TagFormat, TagParser, TagStyle, VPrefixPolicy};
+
+/// One of the presets below, so which one to use can come from config
+///
+/// With the `serde` feature this (de)serializes as its kebab-case name, which is also what
+/// [`FromStr`][] and [`Display`][fmt::Display] use (`release-please`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TagPreset {
+    /// [`TagParser::release_please`][]
+    ReleasePlease,
+    /// [`TagParser::release_plz`][]
+    ReleasePlz,
+    /// [`TagParser::github_actions`][]
+    GithubActions,
+    /// [`TagParser::changesets`][]
+    Changesets,
+    /// [`TagParser::lerna`][]
+    Lerna,
+}
+
+impl TagPreset {
+    /// Every preset
+    pub const ALL: [TagPreset; 5] = [
+        TagPreset::ReleasePlease,
+        TagPreset::ReleasePlz,
+        TagPreset::GithubActions,
+        TagPreset::Changesets,
+        TagPreset::Lerna,
+    ];
+
+    /// Get the preset's kebab-case name
+    pub fn name(&self) -> &'static str {
+        match self {
+            TagPreset::ReleasePlease => "release-please",
+            TagPreset::ReleasePlz => "release-plz",
+            TagPreset::GithubActions => "github-actions",
+            TagPreset::Changesets => "changesets",
+            TagPreset::Lerna => "lerna",
+        }
+    }
+}
+
+impl fmt::Display for TagPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The name given to [`TagPreset::from_str`][] isn't a preset
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{name} isn't a known tag preset")]
+pub struct UnknownPreset {
+    /// The name
+    pub name: String,
+}
+
+impl FromStr for TagPreset {
+    type Err = UnknownPreset;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        TagPreset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| UnknownPreset {
+                name: name.to_owned(),
+            })
+    }
+}
+
+/// Reads tags that join the package and version with `@`, like `my-app@1.2.3` and
+/// `@my-org/my-app@1.2.3` (as changesets and lerna make them)
+///
+/// Matches are reported as [`TagStyle::Custom`][] with the name of the convention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtVersionMatcher {
+    /// The name of the convention, like `changesets`
+    pub convention: String,
+}
+
+impl TagMatcher for AtVersionMatcher {
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch> {
+        let found = cx.strip_package_prefix(tag)?;
+        let version = found.rest.strip_prefix('@')?;
+        let name_end = tag.len() - found.rest.len();
+        Some(TagMatch {
+            prefix: 0..0,
+            package: Some(PackageSpan {
+                idx: found.idx,
+                alias: found.alias,
+                range: 0..name_end,
+            }),
+            separator: name_end..name_end + 1,
+            version: tag.len() - version.len()..tag.len(),
+            confidence: found.confidence,
+            format: None,
+            style: TagStyle::Custom {
+                name: self.convention.clone(),
+            },
+        })
+    }
+}
+
+impl TagParser {
+    /// Get the parser for a preset
+    pub fn from_preset(preset: TagPreset) -> TagParser {
+        match preset {
+            TagPreset::ReleasePlease => TagParser::release_please(),
+            TagPreset::ReleasePlz => TagParser::release_plz(),
+            TagPreset::GithubActions => TagParser::github_actions(),
+            TagPreset::Changesets => TagParser::changesets(),
+            TagPreset::Lerna => TagParser::lerna(),
+        }
+    }
+
+    /// Parse and produce tags the way [release-please](https://github.com/googleapis/release-please) does
+    ///
+    /// release-please tags components as `my-app-v1.2.3` and the root component as
+    /// `v1.2.3`. Components of npm packages are named without their scope, so
+    /// `@my-org/my-app` is tagged `my-app-v1.2.3`.
+    pub fn release_please() -> TagParser {
+        TagParser {
+            formats: vec![TagFormat::Dash],
+            v_prefix: VPrefixPolicy::Require,
+            strip_npm_scopes: true,
+            ..TagParser::default()
+        }
+    }
+
+    /// Parse and produce tags the way [release-plz](https://release-plz.ieni.dev) does
+    ///
+    /// release-plz tags the packages of a workspace as `my-app-v1.2.3`, and a lone
+    /// package as `v1.2.3`.
+    pub fn release_plz() -> TagParser {
+        TagParser {
+            formats: vec![TagFormat::Dash],
+            v_prefix: VPrefixPolicy::Require,
+            ..TagParser::default()
+        }
+    }
+
+    /// Parse and produce tags the way GitHub Actions repos do
+    ///
+    /// Every release gets a full `v1.2.3` tag, plus moving `v1` and `v1.2` tags that
+    /// follow the newest release in their series (see [`TagParser::moving_aliases`][]).
+    pub fn github_actions() -> TagParser {
+        TagParser {
+            formats: vec![TagFormat::Dash],
+            v_prefix: VPrefixPolicy::Require,
+            moving_aliases: true,
+            ..TagParser::default()
+        }
+    }
+
+    /// Parse tags the way [changesets](https://github.com/changesets/changesets) makes them
+    ///
+    /// changesets tags the packages of a monorepo as `my-app@1.2.3` (with any npm scope,
+    /// `@my-org/my-app@1.2.3`) and a lone package as `v1.2.3`. Those are read with an
+    /// [`AtVersionMatcher`][], while new tags are still spelled like cargo-dist spells them
+    /// (`my-app-v1.2.3`), so a repo can switch over without losing its history.
+    pub fn changesets() -> TagParser {
+        TagParser {
+            matchers: vec![Arc::new(AtVersionMatcher {
+                convention: "changesets".to_owned(),
+            })],
+            ..TagParser::default()
+        }
+    }
+
+    /// Parse tags the way [lerna](https://lerna.js.org) makes them
+    ///
+    /// In fixed mode lerna tags every release as `v1.2.3`, and in independent mode it tags
+    /// each package as `my-app@1.2.3`, which is read with an [`AtVersionMatcher`][]. New
+    /// tags are spelled like cargo-dist spells them, like [`TagParser::changesets`][].
+    pub fn lerna() -> TagParser {
+        TagParser {
+            matchers: vec![Arc::new(AtVersionMatcher {
+                convention: "lerna".to_owned(),
+            })],
+            ..TagParser::default()
+        }
+    }
+}
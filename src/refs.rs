@@ -0,0 +1,159 @@
+//! Extracting tags from git refs and forge URLs, for callers (like webhook handlers)
+//! that get those instead of bare tags, and checking that tags are valid git refs.
+
+use crate::errors::{TagError, TagResult};
+use crate::{Package, PartialAnnouncementTag, TagParser};
+
+/// Where a tag was extracted from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TagOrigin {
+    /// It was just a tag
+    #[default]
+    Tag,
+    /// A git ref like `refs/tags/my-app-v1.2.3`
+    Ref,
+    /// A release or tag page, like `https://github.com/org/repo/releases/tag/v1.2.3`
+    Url {
+        /// The host of the URL, like `github.com`
+        host: String,
+        /// The repository the URL is for, like `org/repo` (GitLab groups can nest,
+        /// like `group/subgroup/repo`)
+        repository: String,
+    },
+}
+
+/// Get the tag out of a git ref or a GitHub/GitLab URL, if it isn't already a bare tag
+///
+/// Recognized URLs are GitHub's `{repo}/releases/tag/{tag}` and `{repo}/tree/{tag}`, and
+/// GitLab's `{repo}/-/releases/{tag}` and `{repo}/-/tags/{tag}` (on any host).
+/// Percent-encoded tags are decoded, and query strings and fragments are ignored.
+pub fn extract_tag(input: &str) -> TagResult<(String, TagOrigin)> {
+    if let Some(tag) = input.strip_prefix("refs/tags/") {
+        return Ok((tag.to_owned(), TagOrigin::Ref));
+    }
+    let Some((_, rest)) = input
+        .split_once("://")
+        .filter(|(scheme, _)| matches!(*scheme, "http" | "https"))
+    else {
+        return Ok((input.to_owned(), TagOrigin::Tag));
+    };
+    let unrecognized = || TagError::UnrecognizedTagUrl {
+        url: input.to_owned(),
+    };
+
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (host, path) = rest.split_once('/').ok_or_else(unrecognized)?;
+    const PAGES: &[&str] = &["/-/releases/", "/-/tags/", "/releases/tag/", "/tree/"];
+    let (repository, tag) = PAGES
+        .iter()
+        .find_map(|page| path.split_once(page))
+        .ok_or_else(unrecognized)?;
+    let tag = percent_decode(tag.trim_end_matches('/')).ok_or_else(unrecognized)?;
+    if repository.is_empty() || tag.is_empty() {
+        return Err(unrecognized());
+    }
+    Ok((
+        tag,
+        TagOrigin::Url {
+            host: host.to_owned(),
+            repository: repository.to_owned(),
+        },
+    ))
+}
+
+/// Check that git would accept the tag's name, like `git check-ref-format` does
+///
+/// [`TagParser::parse_tag`][] does this first, so tags git forbids fail with
+/// [`TagError::InvalidGitTag`][] saying what's wrong, instead of failing to match.
+pub fn check_git_tag(tag: &str) -> TagResult<()> {
+    let invalid = |position: usize, reason: &str| {
+        Err(TagError::InvalidGitTag {
+            tag: tag.to_owned(),
+            position,
+            reason: reason.to_owned(),
+        })
+    };
+    if tag.is_empty() {
+        return invalid(0, "it's empty");
+    }
+    if tag == "@" {
+        return invalid(0, "it's just `@`");
+    }
+    for (position, c) in tag.char_indices() {
+        match c {
+            ' ' => return invalid(position, "it contains a space"),
+            c if c.is_ascii_control() => {
+                return invalid(position, "it contains a control character")
+            }
+            '~' | '^' | ':' | '?' | '*' | '[' | '\\' => {
+                return invalid(position, &format!("it contains `{c}`"))
+            }
+            _ => {}
+        }
+    }
+    for sequence in ["..", "@{", "//"] {
+        if let Some(position) = tag.find(sequence) {
+            return invalid(position, &format!("it contains `{sequence}`"));
+        }
+    }
+    if tag.starts_with('/') {
+        return invalid(0, "it starts with `/`");
+    }
+    if tag.ends_with(['/', '.']) {
+        let last = tag.len() - 1;
+        return invalid(last, &format!("it ends with `{}`", &tag[last..]));
+    }
+    let mut position = 0;
+    for component in tag.split('/') {
+        if component.starts_with('.') {
+            return invalid(position, "a slash-delimited part of it starts with `.`");
+        }
+        if component.ends_with(".lock") {
+            return invalid(
+                position + component.len() - ".lock".len(),
+                "a slash-delimited part of it ends with `.lock`",
+            );
+        }
+        position += component.len() + 1;
+    }
+    Ok(())
+}
+
+/// Decode `%XX` escapes, failing if they're malformed or don't make UTF-8
+fn percent_decode(input: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+impl TagParser {
+    /// Parse a tag that might be given as a git ref or a release URL
+    ///
+    /// See [`extract_tag`][] for what's accepted. The result's
+    /// [`PartialAnnouncementTag::tag`][] is the extracted tag, and
+    /// [`PartialAnnouncementTag::origin`][] records where it came from.
+    pub fn parse_tag_input(
+        &self,
+        packages: &[Package],
+        input: &str,
+    ) -> TagResult<PartialAnnouncementTag> {
+        let (tag, origin) = extract_tag(input)?;
+        let mut announcing = self.parse_tag(packages, &tag)?;
+        announcing.origin = origin;
+        Ok(announcing)
+    }
+}
@@ -0,0 +1,95 @@
+//! Cheaply telling whether a tag is any of our business, before parsing it for real.
+
+use crate::compiled::NameIndex;
+use crate::grammar::MatchContext;
+use crate::namespaces::namespace_members;
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagParser};
+
+impl TagParser {
+    /// Check whether a tag would announce any of `packages`, without fully parsing it
+    ///
+    /// This finds the reading of the tag [`TagParser::parse_tag`][] would pick, but skips
+    /// the checks (and warnings) that come after. So it can say true for a tag that fails
+    /// to parse (say, because its version doesn't match its package's), but it never says
+    /// false for one that would release something. Tags for [`Package::excluded`][]
+    /// packages, unified releases no package has the version of, and reserved or denied
+    /// tags aren't relevant.
+    pub fn is_relevant_tag(&self, packages: &[Package], tag: &str) -> bool {
+        #[cfg(feature = "unicode")]
+        if self.normalize_unicode {
+            if let Some((packages, tag)) = crate::unicode::nfc_normalize(packages, tag) {
+                return self.is_relevant_normalized_tag(&packages, &tag, None);
+            }
+        }
+        self.is_relevant_normalized_tag(packages, tag, None)
+    }
+
+    /// [`TagParser::is_relevant_tag`][] for a tag whose unicode has already been normalized
+    pub(crate) fn is_relevant_normalized_tag(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        index: Option<&NameIndex>,
+    ) -> bool {
+        if packages.is_empty()
+            || crate::refs::check_git_tag(tag).is_err()
+            || self.check_not_denied(tag).is_err()
+            || self.reserved_tags.iter().any(|name| name == tag)
+        {
+            return false;
+        }
+        if let Some((namespace, rest)) = self.find_namespace(tag) {
+            let (_, subset) = namespace_members(packages, namespace);
+            return self
+                .namespace_parser(namespace)
+                .is_relevant_normalized_tag(&subset, rest, None);
+        }
+
+        let mut cx = MatchContext::new(self, packages);
+        if let Some(index) = index {
+            cx = cx.with_index(index);
+        }
+        for matcher in self.pipeline() {
+            let Some(found) = cx.run(matcher, tag) else {
+                continue;
+            };
+            let package = found.package.as_ref().map(|span| span.idx);
+            let version = &tag[found.version];
+            // The first reading whose version parses is the one parsing picks
+            let release = match self.parse_channel(packages, package, version) {
+                Some(release) => release,
+                None => {
+                    let scheme = match package {
+                        Some(idx) => packages[idx].version_scheme,
+                        None => crate::unified_scheme(packages),
+                    };
+                    let Ok(parsed) = self.parse_tag_version(tag, version, scheme) else {
+                        continue;
+                    };
+                    match package {
+                        Some(idx) => AnnouncementKind::Package {
+                            idx,
+                            version: parsed.version,
+                        },
+                        None => AnnouncementKind::Unified(parsed.version),
+                    }
+                }
+            };
+            return self.selects_any(packages, release);
+        }
+        // Nothing worked out, but it might still be a typo of one of our packages
+        match self.parse_typo(packages, tag, None) {
+            Some(Ok(announcing)) => !self.selected_packages(&announcing, packages).is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Whether a release would select any packages, see [`TagParser::selected_packages`][]
+    fn selects_any(&self, packages: &[Package], release: AnnouncementKind) -> bool {
+        let announcing = PartialAnnouncementTag {
+            release,
+            ..PartialAnnouncementTag::default()
+        };
+        !self.selected_packages(&announcing, packages).is_empty()
+    }
+}
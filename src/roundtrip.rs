@@ -0,0 +1,68 @@
+//! Checking that the tags we produce parse back to what they were made for.
+
+use crate::errors::TagResult;
+use crate::{
+    unified_version, AnnouncementKind, Package, PartialAnnouncementTag, TagFormat, TagParser,
+};
+
+/// A tag produced by [`TagParser::format_tag`][] that didn't parse back to its release
+#[derive(Debug)]
+pub struct RoundtripFailure {
+    /// The tag that was produced
+    pub tag: String,
+    /// The release it was produced for
+    pub expected: AnnouncementKind,
+    /// What parsing it actually gave
+    pub parsed: TagResult<PartialAnnouncementTag>,
+}
+
+impl TagParser {
+    /// Check that every tag we'd produce for these packages in this format parses back
+    /// to the release it was made for
+    ///
+    /// This formats a tag for each releasable package with a version (and a unified tag,
+    /// if every package agrees on a version), with `format` preferred over the rest of
+    /// [`TagParser::formats`][]. Failures are returned in input order, with the unified
+    /// tag last.
+    pub fn verify_roundtrip(
+        &self,
+        packages: &[Package],
+        format: TagFormat,
+    ) -> Result<(), Vec<RoundtripFailure>> {
+        let mut formats = vec![format];
+        formats.extend(self.formats.iter().filter(|f| **f != format));
+        let parser = TagParser {
+            formats,
+            ..self.clone()
+        };
+
+        let package_releases = packages.iter().enumerate().filter_map(|(idx, package)| {
+            let version = package.version.clone().filter(|_| !package.excluded)?;
+            Some((Some(package), AnnouncementKind::Package { idx, version }))
+        });
+        let unified_release = unified_version(packages)
+            .map(|version| (None, AnnouncementKind::Unified(version.clone())));
+
+        let failures = package_releases
+            .chain(unified_release)
+            .filter_map(|(package, expected)| {
+                let version = expected.version()?;
+                let tag = parser.format_tag(package, version);
+                let parsed = parser.parse_tag(packages, &tag);
+                match &parsed {
+                    Ok(announcing) if announcing.release == expected => None,
+                    _ => Some(RoundtripFailure {
+                        tag,
+                        expected,
+                        parsed,
+                    }),
+                }
+            })
+            .collect::<Vec<_>>();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
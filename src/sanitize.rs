@@ -0,0 +1,100 @@
+//! Spelling tags so they can go in filenames and URLs.
+//!
+//! Tags can contain `/` (`my-app/v1.0.0`), `@` (`@my-org/my-app@1.0.0`), and `+`
+//! (`v1.0.0+build.5`), which make nested directories, or mean something else in a URL.
+//! Both renderings escape characters as `%XX` (their UTF-8 bytes, in uppercase hex), so
+//! they can be turned back into the tag with [`tag_from_filename`][] and [`tag_from_url`][].
+
+/// Spell a tag so it can be used as a file or directory name on any platform
+///
+/// Path separators, characters Windows doesn't allow (`<>:"|?*`), `@`, `+`, `%`, and
+/// control characters are escaped. So is the first character of names Windows reserves
+/// for devices (`CON`, `nul.txt`, `COM1`), even though no sensible tag is one of those.
+pub fn sanitize_for_filename(tag: &str) -> String {
+    let reserved = is_reserved_windows_name(tag);
+    let mut out = String::with_capacity(tag.len());
+    for (i, c) in tag.char_indices() {
+        let unsafe_char = matches!(
+            c,
+            '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' | '@' | '+' | '%'
+        ) || c.is_control();
+        if unsafe_char || (reserved && i == 0) {
+            escape(&mut out, c);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Spell a tag so it can be used as a single URL path segment (or query value)
+///
+/// Everything but ASCII letters, digits, and `-._~` is escaped.
+pub fn sanitize_for_url(tag: &str) -> String {
+    let mut out = String::with_capacity(tag.len());
+    for c in tag.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') {
+            out.push(c);
+        } else {
+            escape(&mut out, c);
+        }
+    }
+    out
+}
+
+/// Get the tag back from [`sanitize_for_filename`][]
+///
+/// Returns None if an escape is malformed, or doesn't produce UTF-8.
+pub fn tag_from_filename(name: &str) -> Option<String> {
+    unescape(name)
+}
+
+/// Get the tag back from [`sanitize_for_url`][]
+///
+/// Returns None if an escape is malformed, or doesn't produce UTF-8.
+pub fn tag_from_url(segment: &str) -> Option<String> {
+    unescape(segment)
+}
+
+/// Escape every byte of a character as `%XX`
+fn escape(out: &mut String, c: char) {
+    let mut buf = [0; 4];
+    for byte in c.encode_utf8(&mut buf).bytes() {
+        out.push_str(&format!("%{byte:02X}"));
+    }
+}
+
+/// Undo [`escape`][]
+fn unescape(input: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(after.get(..2)?).ok()?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &after[2..];
+        } else {
+            bytes.push(byte);
+            rest = after;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Whether Windows would treat this as a device instead of a file
+fn is_reserved_windows_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name);
+    let upper = stem.to_ascii_uppercase();
+    match upper.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" => true,
+        _ => {
+            let (Some(base), Some(digit)) = (upper.get(..3), upper.get(3..)) else {
+                return false;
+            };
+            matches!(base, "COM" | "LPT") && matches!(digit.as_bytes(), [b'1'..=b'9'])
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! JSON Schemas for axotag's serialized output, so tools in other languages can
+//! consume it with type safety.
+//!
+//! Every type with a `serde` serialization also implements [`schemars::JsonSchema`][],
+//! these are just the ones most tools want.
+
+use schemars::{schema_for, Schema};
+
+use crate::describe::TagDescription;
+use crate::PartialAnnouncementTag;
+
+/// Get the schema of a serialized [`PartialAnnouncementTag`][]
+pub fn announcement_schema() -> Schema {
+    schema_for!(PartialAnnouncementTag)
+}
+
+/// Get the schema of a serialized [`TagDescription`][]
+pub fn description_schema() -> Schema {
+    schema_for!(TagDescription)
+}
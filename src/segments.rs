@@ -0,0 +1,137 @@
+//! Deciding what a slash-delimited segment of a tag is, when it could be several things.
+//!
+//! In `nightly/v1.2.3`, `nightly` could be a package (in [`TagFormat::Slash`][]), a
+//! maintenance branch (with [`TagParser::series_branches`][], for names like `release-1.x`),
+//! or one of [`TagParser::allowed_prefixes`][]. When more than one of those applies the
+//! tag fails to parse, unless [`TagParser::segment_order`][] says which one wins.
+//!
+//! [`TagFormat::Slash`]: crate::TagFormat::Slash
+
+use std::fmt;
+
+use crate::branches::MaintenanceBranch;
+use crate::errors::{TagError, TagResult};
+use crate::explain::ParseTrace;
+use crate::grammar::{MatchContext, TagMatcher, UnifiedMatcher};
+use crate::{Package, PartialAnnouncementTag, TagParser, TagStyle, TagWarning};
+
+/// Something a slash-delimited segment of a tag can be, see [`TagParser::segment_order`][]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SegmentKind {
+    /// The package of a [`TagFormat::Slash`][crate::TagFormat::Slash] or
+    /// [`TagFormat::Path`][crate::TagFormat::Path] tag
+    Package,
+    /// A maintenance branch, see [`TagParser::series_branches`][]
+    Branch,
+    /// One of [`TagParser::allowed_prefixes`][]
+    Prefix,
+}
+
+impl SegmentKind {
+    /// The suggested [`TagParser::segment_order`][]: packages, then branches, then prefixes
+    pub const DEFAULT_ORDER: [SegmentKind; 3] = [
+        SegmentKind::Package,
+        SegmentKind::Branch,
+        SegmentKind::Prefix,
+    ];
+}
+
+impl fmt::Display for SegmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SegmentKind::Package => "a package",
+            SegmentKind::Branch => "a maintenance branch",
+            SegmentKind::Prefix => "an allowed prefix",
+        })
+    }
+}
+
+impl TagParser {
+    /// If we read a tag's last slash-delimited segment as its package, check whether that
+    /// segment is also a branch or an allowed prefix, and re-read the tag as a unified
+    /// release if one of those wins
+    pub(crate) fn resolve_package_segment(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        announcing: PartialAnnouncementTag,
+        trace: Option<&mut ParseTrace>,
+    ) -> TagResult<PartialAnnouncementTag> {
+        let by_segment = matches!(
+            announcing.style.as_ref().map(TagStyle::without_prefix),
+            Some(TagStyle::PackageSlash | TagStyle::PackagePath)
+        );
+        if !by_segment || announcing.release.package_idx().is_none() {
+            return Ok(announcing);
+        }
+        // What the prefix would be if the tag were unified
+        let Some((segment, _)) = tag.rsplit_once('/') else {
+            return Ok(announcing);
+        };
+        let mut kinds = vec![SegmentKind::Package];
+        if self.series_branches && MaintenanceBranch::from_prefix(segment).is_some() {
+            kinds.push(SegmentKind::Branch);
+        }
+        if self.lists_prefix(segment) {
+            kinds.push(SegmentKind::Prefix);
+        }
+        let mut warnings = vec![];
+        let mut announcing = match self.resolve_segment(tag, segment, &kinds, &mut warnings)? {
+            SegmentKind::Package => announcing,
+            kind @ (SegmentKind::Branch | SegmentKind::Prefix) => {
+                if let Some(trace) = trace {
+                    trace.reinterpret(tag, segment, kind);
+                }
+                self.parse_unified(packages, tag)?
+            }
+        };
+        announcing.warnings.extend(warnings);
+        Ok(announcing)
+    }
+
+    /// Pick what a segment is out of the kinds it could be, per [`TagParser::segment_order`][]
+    pub(crate) fn resolve_segment(
+        &self,
+        tag: &str,
+        segment: &str,
+        kinds: &[SegmentKind],
+        warnings: &mut Vec<TagWarning>,
+    ) -> TagResult<SegmentKind> {
+        if let [kind] = kinds {
+            return Ok(*kind);
+        }
+        match self.segment_order.iter().find(|kind| kinds.contains(kind)) {
+            Some(kind) => {
+                warnings.push(TagWarning::AmbiguousSegment {
+                    segment: segment.to_owned(),
+                    chosen: *kind,
+                });
+                Ok(*kind)
+            }
+            None => Err(TagError::AmbiguousSegment {
+                tag: tag.to_owned(),
+                segment: segment.to_owned(),
+                kinds: kinds.to_vec(),
+            }),
+        }
+    }
+
+    /// Whether a prefix is one of [`TagParser::allowed_prefixes`][] (not just allowed
+    /// because anything is)
+    pub(crate) fn lists_prefix(&self, prefix: &str) -> bool {
+        self.allowed_prefixes
+            .as_ref()
+            .is_some_and(|allowed| allowed.iter().any(|a| a.trim_end_matches('/') == prefix))
+    }
+
+    /// Read a tag as a unified release, skipping every other matcher
+    fn parse_unified(&self, packages: &[Package], tag: &str) -> TagResult<PartialAnnouncementTag> {
+        let cx = MatchContext::new(self, packages);
+        let found = UnifiedMatcher
+            .match_tag(&cx, tag)
+            .expect("the unified reading of a tag always matches");
+        self.read_match(packages, tag, found)?
+    }
+}
@@ -0,0 +1,193 @@
+//! Fuzzy matching of package names, for did-you-mean suggestions and typo tolerance.
+
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::errors::TagResult;
+use crate::explain::{ParseTrace, TypoCorrection};
+use crate::{unified_scheme, Package, PartialAnnouncementTag, TagParser, TagWarning};
+
+/// A way of scoring how similar two package names are
+///
+/// Scores go from 0.0 (nothing alike) to 1.0 (identical). The default compares
+/// Levenshtein distance to the length of the longer name, but hosts can plug in
+/// whatever suits their naming conventions.
+#[derive(Clone)]
+pub struct Similarity {
+    score: Arc<ScoreFn>,
+}
+
+/// A function scoring how similar two names are
+type ScoreFn = dyn Fn(&str, &str) -> f64 + Send + Sync;
+
+impl Similarity {
+    /// Use a custom scoring function
+    pub fn new(score: impl Fn(&str, &str) -> f64 + Send + Sync + 'static) -> Similarity {
+        Similarity {
+            score: Arc::new(score),
+        }
+    }
+
+    /// Score how similar two names are
+    pub fn score(&self, a: &str, b: &str) -> f64 {
+        (self.score)(a, b)
+    }
+}
+
+impl Default for Similarity {
+    /// Constructs a Similarity based on Levenshtein distance
+    fn default() -> Similarity {
+        Similarity::new(levenshtein_similarity)
+    }
+}
+
+impl fmt::Debug for Similarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Similarity").finish_non_exhaustive()
+    }
+}
+
+/// Score two strings by Levenshtein distance relative to the longer one
+fn levenshtein_similarity(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    1.0 - row[b.len()] as f64 / longest as f64
+}
+
+/// A part of a tag that looks like it names a package we don't have
+pub(crate) struct UnknownPackage<'a> {
+    /// The name as written in the tag
+    pub(crate) name: &'a str,
+    /// Where the name is in the tag
+    pub(crate) range: Range<usize>,
+}
+
+impl TagParser {
+    /// Suggest packages the tag might have meant, most similar first
+    ///
+    /// This only looks at tags shaped like `some-package-v1.2.3` or `some-package/v1.2.3`
+    /// where `some-package` isn't one of the packages. Packages need to score at least
+    /// [`TagParser::similarity_threshold`][] under [`TagParser::similarity`][] to be
+    /// suggested, and ties are broken by input order.
+    pub fn did_you_mean(&self, packages: &[Package], tag: &str) -> Vec<usize> {
+        let Some(unknown) = self.unknown_package(packages, tag) else {
+            return vec![];
+        };
+        let mut scored = packages
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.excluded)
+            .map(|(idx, p)| (idx, self.similarity.score(unknown.name, &p.name)))
+            .filter(|(_, score)| *score >= self.similarity_threshold)
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a_idx, a), (b_idx, b)| b.total_cmp(a).then(a_idx.cmp(b_idx)));
+        scored.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    /// If a tag looks like it names a package we don't have, find that name
+    pub(crate) fn unknown_package<'a>(
+        &self,
+        packages: &[Package],
+        tag: &'a str,
+    ) -> Option<UnknownPackage<'a>> {
+        let names = self.name_matcher();
+        let is_known = |name: &str| {
+            packages.iter().any(|p| {
+                names
+                    .package_names(p)
+                    .any(|(_, known)| names.strip_prefix(name, known) == Some(""))
+            })
+        };
+        let scheme = unified_scheme(packages);
+        let is_version = |version: &str| self.parse_tag_version(version, version, scheme).is_ok();
+
+        let (prefix, component) = tag.rsplit_once('/').unwrap_or(("", tag));
+        let component_start = tag.len() - component.len();
+        // `some-package-v1.2.3`
+        let dashed = component
+            .match_indices(self.separators())
+            .map(|(idx, sep)| (&component[..idx], &component[idx + sep.len()..]))
+            .find(|(name, version)| !name.is_empty() && is_version(version))
+            .map(|(name, _)| UnknownPackage {
+                name,
+                range: component_start..component_start + name.len(),
+            });
+        // `some-package/v1.2.3`
+        let slashed = || {
+            let name = prefix.rsplit_once('/').map_or(prefix, |(_, name)| name);
+            (!name.is_empty() && is_version(component)).then(|| UnknownPackage {
+                name,
+                range: prefix.len() - name.len()..prefix.len(),
+            })
+        };
+        dashed
+            .or_else(slashed)
+            .filter(|unknown| !is_known(unknown.name))
+    }
+
+    /// If typo tolerance is on, try to parse a tag that named a package we don't have
+    /// as if it named the most similar package instead
+    pub(crate) fn parse_typo(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        trace: Option<&mut ParseTrace>,
+    ) -> Option<TagResult<PartialAnnouncementTag>> {
+        if !self.typo_tolerance {
+            return None;
+        }
+        let idx = *self.did_you_mean(packages, tag).first()?;
+        self.parse_corrected(packages, tag, idx, trace)
+    }
+
+    /// Parse a tag that named a package we don't have as if it named the given package
+    pub(crate) fn parse_corrected(
+        &self,
+        packages: &[Package],
+        tag: &str,
+        idx: usize,
+        trace: Option<&mut ParseTrace>,
+    ) -> Option<TagResult<PartialAnnouncementTag>> {
+        let unknown = self.unknown_package(packages, tag)?;
+        let package = packages.get(idx)?;
+        let mut corrected = tag.to_owned();
+        corrected.replace_range(unknown.range.clone(), &package.name);
+
+        let parser = TagParser {
+            typo_tolerance: false,
+            ..self.clone()
+        };
+        let mut corrected_trace = trace.is_some().then(ParseTrace::default);
+        let result = parser.parse_tag_recording(packages, &corrected, corrected_trace.as_mut());
+        if let (Some(trace), Some(corrected_trace)) = (trace, corrected_trace) {
+            trace.typo = Some(Box::new(TypoCorrection {
+                tag: corrected,
+                package_name: package.name.clone(),
+                trace: corrected_trace,
+            }));
+        }
+        Some(result.map(|mut announcing| {
+            announcing.tag = tag.to_owned();
+            announcing.warnings.push(TagWarning::TypoCorrected {
+                package_name: package.name.clone(),
+                found: unknown.name.to_owned(),
+            });
+            announcing
+        }))
+    }
+}
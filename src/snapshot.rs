@@ -0,0 +1,84 @@
+//! Snapshots of the packages a parse depended on, so it can be reproduced after the
+//! workspace has changed (or the results used without the workspace at all).
+
+use crate::errors::TagResult;
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag, TagParser, Version};
+
+/// Everything about a workspace's packages that parsing looks at
+///
+/// With the `serde` feature this can be stored alongside a parse result, then fed back
+/// to [`TagParser::parse_tag_in_snapshot`][] to see how the tag was read at the time.
+/// Missing fields deserialize to their defaults, so old snapshots stay readable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct WorkspaceSnapshot {
+    /// The packages, in their original order (so indices in results still apply)
+    pub packages: Vec<Package>,
+}
+
+impl WorkspaceSnapshot {
+    /// Snapshot some packages
+    pub fn new(packages: &[Package]) -> WorkspaceSnapshot {
+        WorkspaceSnapshot {
+            packages: packages.to_vec(),
+        }
+    }
+}
+
+/// A parsed tag along with what it announces, so it can be kept without the packages
+///
+/// [`PartialAnnouncementTag`][] refers to packages by their index, which means nothing
+/// once the package list is gone; this carries the package's name and the version by value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ResolvedAnnouncement {
+    /// The parsed tag
+    pub announcing: PartialAnnouncementTag,
+    /// The name of the package being announced, or None if the tag isn't for one
+    /// package (like unified releases)
+    pub package_name: Option<String>,
+    /// The version being announced, if the tag has one (unresolved channels don't)
+    pub version: Option<Version>,
+}
+
+impl PartialAnnouncementTag {
+    /// Bundle this with the name of its package and its version
+    ///
+    /// `packages` must be the ones the tag was parsed with.
+    pub fn into_resolved(self, packages: &[Package]) -> ResolvedAnnouncement {
+        let package_name = match &self.release {
+            AnnouncementKind::UnresolvedPackage { name, .. } => Some(name.clone()),
+            release => release
+                .package_idx()
+                .and_then(|idx| packages.get(idx))
+                .map(|p| p.name.clone()),
+        };
+        ResolvedAnnouncement {
+            package_name,
+            version: self.release.version().cloned(),
+            announcing: self,
+        }
+    }
+}
+
+impl TagParser {
+    /// Parse a tag into a [`ResolvedAnnouncement`][], which doesn't refer back to `packages`
+    pub fn parse_tag_resolved(
+        &self,
+        packages: &[Package],
+        tag: &str,
+    ) -> TagResult<ResolvedAnnouncement> {
+        Ok(self.parse_tag(packages, tag)?.into_resolved(packages))
+    }
+
+    /// Parse a tag against a snapshot of a workspace, instead of the current packages
+    pub fn parse_tag_in_snapshot(
+        &self,
+        snapshot: &WorkspaceSnapshot,
+        tag: &str,
+    ) -> TagResult<PartialAnnouncementTag> {
+        self.parse_tag(&snapshot.packages, tag)
+    }
+}
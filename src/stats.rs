@@ -0,0 +1,71 @@
+//! Parsing tags in bulk, and statistics about how that went.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::errors::{TagErrorKind, TagResult};
+use crate::{Package, PartialAnnouncementTag, TagFormat, TagParser};
+
+/// The results of parsing a bunch of tags at once
+#[derive(Debug)]
+pub struct BulkParse {
+    /// The result of parsing each tag, in the order the tags were given
+    pub results: Vec<TagResult<PartialAnnouncementTag>>,
+    /// Aggregate statistics about the results
+    pub stats: ParseStats,
+}
+
+/// Aggregate statistics about a bunch of parsed tags
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ParseStats {
+    /// How many tags we tried to parse
+    pub parsed: usize,
+    /// How many of those parsed successfully
+    pub succeeded: usize,
+    /// How many of those failed, by kind of error (sorted by kind)
+    pub errors: BTreeMap<TagErrorKind, usize>,
+    /// How many successfully parsed tags matched each format (sorted by format)
+    pub formats: BTreeMap<TagFormat, usize>,
+    /// How long the whole thing took
+    pub elapsed: Duration,
+}
+
+impl TagParser {
+    /// Parse every tag in `tags` against the same packages, collecting statistics
+    pub fn parse_tags<I>(&self, packages: &[Package], tags: I) -> BulkParse
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let start = Instant::now();
+        let mut stats = ParseStats::default();
+        let mut results = vec![];
+        for tag in tags {
+            let result = self.parse_tag(packages, tag.as_ref());
+            stats.record(&result);
+            results.push(result);
+        }
+        stats.elapsed = start.elapsed();
+        BulkParse { results, stats }
+    }
+}
+
+impl ParseStats {
+    /// Add a single parse result to the statistics
+    pub fn record(&mut self, result: &TagResult<PartialAnnouncementTag>) {
+        self.parsed += 1;
+        match result {
+            Ok(announcing) => {
+                self.succeeded += 1;
+                if let Some(format) = announcing.format {
+                    *self.formats.entry(format).or_default() += 1;
+                }
+            }
+            Err(e) => {
+                *self.errors.entry(e.kind()).or_default() += 1;
+            }
+        }
+    }
+}
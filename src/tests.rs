@@ -1,6 +1,35 @@
 //! Tests for tag parsing
 //!
-use crate::{parse_tag, Package, ReleaseType, Version};
+use crate::actions::MovingAlias;
+use crate::bump::{next_version, BumpLevel};
+use crate::conflicts::{check_tag_conflicts, check_tag_conflicts_with_branches, TagConflict};
+#[cfg(feature = "corpus")]
+use crate::corpus::{CorpusOutcome, TagCorpus};
+use crate::describe::DescribedScope;
+use crate::diff::VersionMismatch;
+use crate::ecosystems::{
+    check_docker_tag, from_docker_tag, to_deb_version, to_docker_tag, to_homebrew_version,
+    to_msi_version, to_rpm_version, DistChannel, DockerTagProblem,
+};
+use crate::errors::{TagError, TagErrorKind};
+use crate::grammar::{Confidence, MatchContext, PackageSpan, TagMatch, TagMatcher};
+use crate::lenient::VersionFixup;
+use crate::lint::{LintProblem, TagLint};
+use crate::matching::{match_package_prefix, MatchOptions};
+use crate::plan::{Change, PackageChange, PlanKind, PlannedPackage};
+use crate::refs::{check_git_tag, extract_tag};
+use crate::sanitize::{sanitize_for_filename, sanitize_for_url, tag_from_filename, tag_from_url};
+use crate::snapshot::{ResolvedAnnouncement, WorkspaceSnapshot};
+use crate::title::{render_announcement_title, AnnouncementTemplates};
+use crate::{
+    parse_tag, parse_tag_with_warnings, AnnouncementKind, BuildMetadataPolicy,
+    EmptyWorkspacePolicy, Namespace, Package, PartialAnnouncementTag, PlaceholderPolicy,
+    RegressionPolicy, RejectionReason, ScopePreference, SegmentKind, Similarity, TagFormat,
+    TagOrigin, TagParser, TagPreset, TagStyle, TagWarning, UnifiedVersionPolicy, UnversionedPolicy,
+    VPrefixPolicy, Version, VersionBounds, VersionMatchPolicy, VersionScheme,
+};
+#[cfg(feature = "arbitrary")]
+use proptest::strategy::Strategy;
 
 #[test]
 fn parse_one() {
@@ -9,6 +38,7 @@ fn parse_one() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("{version}");
 
@@ -16,7 +46,7 @@ fn parse_one() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -26,6 +56,7 @@ fn parse_one_v() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("v{version}");
 
@@ -33,7 +64,7 @@ fn parse_one_v() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -43,6 +74,7 @@ fn parse_one_package_v() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay-v{version}");
 
@@ -50,7 +82,10 @@ fn parse_one_package_v() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -60,6 +95,7 @@ fn parse_one_package() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay-{version}");
 
@@ -67,7 +103,10 @@ fn parse_one_package() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -77,6 +116,7 @@ fn parse_one_v_alpha() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("v{version}");
 
@@ -84,7 +124,7 @@ fn parse_one_v_alpha() {
 
     assert!(announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -94,6 +134,7 @@ fn parse_one_package_v_alpha() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay-v{version}");
 
@@ -101,7 +142,10 @@ fn parse_one_package_v_alpha() {
 
     assert!(announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -111,6 +155,7 @@ fn parse_one_prefix_slashv() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("release/v{version}");
 
@@ -118,7 +163,7 @@ fn parse_one_prefix_slashv() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -128,6 +173,7 @@ fn parse_one_prefix_slash() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("release/{version}");
 
@@ -135,7 +181,7 @@ fn parse_one_prefix_slash() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -145,6 +191,7 @@ fn parse_one_prefix_slash_package_v() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("release/axolotlsay-v{version}");
 
@@ -152,7 +199,10 @@ fn parse_one_prefix_slash_package_v() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -162,6 +212,7 @@ fn parse_one_prefix_slash_package_slashv() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("releases/axolotlsay/v{version}");
 
@@ -169,7 +220,10 @@ fn parse_one_prefix_slash_package_slashv() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -179,6 +233,7 @@ fn parse_one_package_slashv() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay/v{version}");
 
@@ -186,7 +241,10 @@ fn parse_one_package_slashv() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -196,6 +254,7 @@ fn parse_one_prefix_slash_package_slash() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("releases/axolotlsay/{version}");
 
@@ -203,7 +262,10 @@ fn parse_one_prefix_slash_package_slash() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -213,6 +275,7 @@ fn parse_one_prefix_many_slash_package_slash() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("blah/blah/releases/axolotlsay/{version}");
 
@@ -220,7 +283,10 @@ fn parse_one_prefix_many_slash_package_slash() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -230,6 +296,7 @@ fn parse_one_package_slash() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay/{version}");
 
@@ -237,7 +304,10 @@ fn parse_one_package_slash() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
 }
 
 #[test]
@@ -248,14 +318,17 @@ fn parse_unified_v() {
         Package {
             name: "axolotlsay".to_owned(),
             version: Some(version.clone()),
+            ..Default::default()
         },
         Package {
             name: "otherapp".to_owned(),
             version: Some(version.clone()),
+            ..Default::default()
         },
         Package {
             name: "whatever".to_owned(),
             version: Some(version.clone()),
+            ..Default::default()
         },
     ];
     let tag = format!("v{version}");
@@ -264,7 +337,7 @@ fn parse_unified_v() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -275,14 +348,17 @@ fn parse_disjoint_v() {
         Package {
             name: "axolotlsay".to_owned(),
             version: Some(version.clone()),
+            ..Default::default()
         },
         Package {
             name: "otherapp".to_owned(),
             version: Some(version.clone()),
+            ..Default::default()
         },
         Package {
             name: "whatever".to_owned(),
             version: "2.0.0".parse().ok(),
+            ..Default::default()
         },
     ];
     let tag = format!("v{version}");
@@ -291,7 +367,7 @@ fn parse_disjoint_v() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -301,6 +377,7 @@ fn ok_parse_one_package_slash() {
     let packages = vec![Package {
         name: "asdsadas".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay/{version}");
 
@@ -308,7 +385,7 @@ fn ok_parse_one_package_slash() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Version(version));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
 }
 
 #[test]
@@ -319,6 +396,7 @@ fn fail_parse_one_package() {
     let packages = vec![Package {
         name: "asdsadas".to_owned(),
         version: Some(version.clone()),
+        ..Default::default()
     }];
     let tag = format!("axolotlsay-v{version}");
 
@@ -332,6 +410,7 @@ fn fail_parse_one_package_version_backup() {
     let packages = vec![Package {
         name: "axolotlsay".to_owned(),
         version: None,
+        ..Default::default()
     }];
     let tag = format!("axolotlsay-v{version}");
 
@@ -339,5 +418,6168 @@ fn fail_parse_one_package_version_backup() {
 
     assert!(!announcing.prerelease);
     assert_eq!(announcing.tag, tag);
-    assert_eq!(announcing.release, ReleaseType::Package { idx: 0, version });
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_reports_format() {
+    // "axolotlsay/v1.0.0" with the default formats
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let tag = format!("axolotlsay/v{version}");
+
+    let announcing = parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(announcing.format, Some(TagFormat::Slash));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_unified_reports_first_format() {
+    // "v1.0.0" looks the same in every format, so it reports the preferred one
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        formats: vec![TagFormat::Slash, TagFormat::Dash],
+        ..TagParser::default()
+    };
+    let tag = format!("v{version}");
+
+    let announcing = parser.parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(announcing.format, Some(TagFormat::Slash));
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
+}
+
+#[test]
+fn parse_formats_in_order() {
+    // "axolotlsay/axolotlsay-v1.0.0" can only be read as a dash-format tag,
+    // even though the slash format finds the package first
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        formats: vec![TagFormat::Slash, TagFormat::Dash],
+        ..TagParser::default()
+    };
+    let tag = format!("axolotlsay/axolotlsay-v{version}");
+
+    let announcing = parser.parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(announcing.format, Some(TagFormat::Dash));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn fail_parse_unaccepted_format() {
+    // "axolotlsay-v1.0.0" when only the slash format is accepted
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        formats: vec![TagFormat::Slash],
+        ..TagParser::default()
+    };
+    let tag = format!("axolotlsay-v{version}");
+
+    let err = parser.parse_tag(&packages, &tag).unwrap_err();
+
+    assert!(matches!(err, TagError::TagVersionParse { .. }));
+}
+
+#[test]
+fn format_uses_first_format() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let package = Package {
+        name: "axolotlsay".to_owned(),
+        ..Default::default()
+    };
+    let parser = TagParser {
+        formats: vec![TagFormat::Slash, TagFormat::Dash],
+        ..TagParser::default()
+    };
+
+    assert_eq!(
+        parser.format_tag(Some(&package), &version),
+        "axolotlsay/v1.0.0"
+    );
+    assert_eq!(parser.format_tag(None, &version), "v1.0.0");
+    assert_eq!(
+        TagParser::default().format_tag(Some(&package), &version),
+        "axolotlsay-v1.0.0"
+    );
+}
+
+#[test]
+fn parse_channel() {
+    // "latest" in a one package workspace
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let tag = "latest";
+
+    let announcing = parse_tag(&packages, tag).unwrap();
+
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.tag, tag);
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Channel {
+            idx: None,
+            channel: "latest".to_owned()
+        }
+    );
+}
+
+#[test]
+fn parse_package_channel() {
+    // "axolotlsay-stable" in a one package workspace
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let tag = "axolotlsay-stable";
+
+    let announcing = parse_tag(&packages, tag).unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Channel {
+            idx: Some(0),
+            channel: "stable".to_owned()
+        }
+    );
+}
+
+#[test]
+fn parse_resolved_channels() {
+    // "latest" and "axolotlsay/latest" resolved against the workspace
+    let version = "1.0.0-prerelease.1".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        resolve_channels: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "latest").unwrap();
+    assert!(announcing.prerelease);
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(version.clone())
+    );
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay/latest").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Slash));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_unresolvable_channel() {
+    // "latest" in a disjoint workspace can't be resolved to one version
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        resolve_channels: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "latest").unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Channel {
+            idx: None,
+            channel: "latest".to_owned()
+        }
+    );
+}
+
+#[test]
+fn fail_parse_legacy_format() {
+    // "axolotlsay/v1.0.0" when the slash format is only accepted for old tags
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        reject_legacy_formats: true,
+        ..TagParser::default()
+    };
+    let tag = format!("axolotlsay/v{version}");
+
+    let err = parser.parse_tag(&packages, &tag).unwrap_err();
+
+    let TagError::LegacyTagFormat {
+        format, canonical, ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(format, TagFormat::Slash);
+    assert_eq!(canonical, "axolotlsay-v1.0.0");
+}
+
+#[test]
+fn parse_legacy_format_allowed() {
+    // unified tags and tags in the preferred format are fine
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        reject_legacy_formats: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "releases/v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(version.clone())
+    );
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_tags_stats() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let tags = [
+        "v1.0.0",
+        "axolotlsay/v1.0.0",
+        "axolotlsay-v1.0.0",
+        "axolotlsay-v2.0.0",
+        "nonsense",
+    ];
+
+    let bulk = TagParser::default().parse_tags(&packages, tags);
+
+    assert_eq!(bulk.results.len(), 5);
+    assert_eq!(bulk.results[0].as_ref().unwrap().tag, "v1.0.0");
+    assert_eq!(bulk.stats.parsed, 5);
+    assert_eq!(bulk.stats.succeeded, 3);
+    assert_eq!(
+        bulk.stats.errors.iter().collect::<Vec<_>>(),
+        vec![
+            (&TagErrorKind::ContradictoryTagVersion, &1),
+            (&TagErrorKind::TagVersionParse, &1)
+        ]
+    );
+    assert_eq!(
+        bulk.stats.formats.iter().collect::<Vec<_>>(),
+        vec![(&TagFormat::Dash, &2), (&TagFormat::Slash, &1)]
+    );
+}
+
+#[test]
+fn parse_version_mismatch_warn() {
+    // "axolotlsay-v0.9.0" for a hotfix when the package is at 1.0.0
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Warn,
+        ..TagParser::default()
+    };
+    let version = "0.9.0".parse::<Version>().unwrap();
+    let tag = format!("axolotlsay-v{version}");
+
+    let announcing = parser.parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: version.clone()
+        }
+    );
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::ContradictoryTagVersion {
+            package_name: "axolotlsay".to_owned(),
+            tag_version: version,
+            real_version: "1.0.0".parse().unwrap(),
+        }]
+    );
+}
+
+#[test]
+fn parse_version_mismatch_ignore() {
+    // "axolotlsay-v0.9.0" when the package is at 1.0.0, and we don't care
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Ignore,
+        ..TagParser::default()
+    };
+    let version = "0.9.0".parse::<Version>().unwrap();
+    let tag = format!("axolotlsay-v{version}");
+
+    let announcing = parser.parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+    assert!(announcing.warnings.is_empty());
+}
+
+#[test]
+fn fail_parse_version_mismatch() {
+    // "axolotlsay-v0.9.0" when the package is at 1.0.0
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let err = parse_tag(&packages, "axolotlsay-v0.9.0").unwrap_err();
+
+    assert!(matches!(err, TagError::ContradictoryTagVersion { .. }));
+}
+
+#[test]
+fn parse_tags_keeps_input_order() {
+    let packages = vec![
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let tags = ["otherapp-v2.0.0", "bad", "axolotlsay/v1.0.0", "v3.0.0"];
+
+    let bulk = TagParser::default().parse_tags(&packages, tags);
+
+    let parsed = bulk
+        .results
+        .iter()
+        .map(|r| r.as_ref().ok().map(|a| a.tag.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        parsed,
+        vec![
+            Some("otherapp-v2.0.0"),
+            None,
+            Some("axolotlsay/v1.0.0"),
+            Some("v3.0.0")
+        ]
+    );
+    // Formats are sorted regardless of the order they were seen in
+    assert_eq!(
+        bulk.stats.formats.keys().collect::<Vec<_>>(),
+        vec![&TagFormat::Dash, &TagFormat::Slash]
+    );
+}
+
+#[test]
+fn parse_duplicate_package_names() {
+    // if two packages have the same name, the first one wins
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_lenient_versions() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: None,
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        lenient_versions: true,
+        ..TagParser::default()
+    };
+    let cases = [
+        ("v1.2", "1.2.0", vec![VersionFixup::MissingComponents]),
+        ("v1.02.3", "1.2.3", vec![VersionFixup::LeadingZeros]),
+        (
+            "1.2.3.4",
+            "1.2.3",
+            vec![VersionFixup::ExtraComponents {
+                dropped: "4".to_owned(),
+            }],
+        ),
+        (
+            "axolotlsay-v01.2-rc.1",
+            "1.2.0-rc.1",
+            vec![VersionFixup::MissingComponents, VersionFixup::LeadingZeros],
+        ),
+    ];
+
+    for (tag, expected, expected_fixups) in cases {
+        let announcing = parser.parse_tag(&packages, tag).unwrap();
+        let expected = expected.parse::<Version>().unwrap();
+        assert_eq!(announcing.release.version(), Some(&expected), "{tag}");
+        let [TagWarning::LenientVersion { fixups, .. }] = &announcing.warnings[..] else {
+            panic!("unexpected warnings for {tag}: {:?}", announcing.warnings);
+        };
+        assert_eq!(fixups, &expected_fixups, "{tag}");
+    }
+}
+
+#[test]
+fn parse_strict_versions_no_warnings() {
+    let parser = TagParser {
+        lenient_versions: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&[], "v1.2.3").unwrap();
+
+    assert!(announcing.warnings.is_empty());
+}
+
+#[test]
+fn fail_parse_lenient_versions() {
+    // lenient mode is opt-in, and still rejects things that aren't versions at all
+    let tags = ["v1.2", "v1.x.3", "v1.2.3-"];
+    let parser = TagParser {
+        lenient_versions: true,
+        ..TagParser::default()
+    };
+
+    assert!(parse_tag(&[], tags[0]).is_err());
+    for tag in &tags[1..] {
+        assert!(parser.parse_tag(&[], tag).is_err(), "{tag}");
+    }
+}
+
+#[test]
+fn selected_packages_unified() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "whatever".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "v1.0.0").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![0, 2]);
+
+    let announcing = parse_tag(&packages, "otherapp-v2.0.0").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![1]);
+}
+
+#[test]
+fn diff_tag_unified() {
+    // a PR bumps otherapp to 1.1.0 and adds a new package at 1.0.0
+    let before = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let after = vec![
+        Package {
+            name: "newapp".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.1.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let diff = TagParser::default().diff_tag("v1.0.0", &before, &after);
+
+    assert!(!diff.is_empty());
+    assert_eq!(diff.added, vec!["newapp".to_owned()]);
+    assert_eq!(diff.removed, vec!["otherapp".to_owned()]);
+    assert!(diff.mismatches_introduced.is_empty());
+}
+
+#[test]
+fn diff_tag_mismatch_introduced() {
+    // a PR bumps axolotlsay past the version the tag names
+    let before = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let after = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.1.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let diff = TagParser::default().diff_tag("axolotlsay-v1.0.0", &before, &after);
+
+    assert!(diff.before.is_ok());
+    assert!(diff.after.is_err());
+    assert_eq!(diff.removed, vec!["axolotlsay".to_owned()]);
+    assert_eq!(
+        diff.mismatches_introduced,
+        vec![VersionMismatch {
+            package_name: "axolotlsay".to_owned(),
+            tag_version: "1.0.0".parse().unwrap(),
+            real_version: "1.1.0".parse().unwrap(),
+        }]
+    );
+    assert!(diff.mismatches_resolved.is_empty());
+
+    let diff = TagParser::default().diff_tag("axolotlsay-v1.0.0", &before, &before);
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn parse_v_prefix_required() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        v_prefix: VPrefixPolicy::Require,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+
+    let err = parser.parse_tag(&packages, "axolotlsay-1.0.0").unwrap_err();
+    assert!(matches!(err, TagError::MissingVPrefix { .. }));
+    let err = parser.parse_tag(&packages, "1.0.0").unwrap_err();
+    assert!(matches!(err, TagError::MissingVPrefix { .. }));
+}
+
+#[test]
+fn parse_v_prefix_forbidden() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        v_prefix: VPrefixPolicy::Forbid,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "1.0.0").unwrap();
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
+
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.0.0")
+        .unwrap_err();
+    assert!(matches!(err, TagError::ForbiddenVPrefix { .. }));
+    let err = parser.parse_tag(&packages, "v1.0.0").unwrap_err();
+    assert!(matches!(err, TagError::ForbiddenVPrefix { .. }));
+}
+
+#[test]
+fn format_v_prefix() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let package = Package {
+        name: "axolotlsay".to_owned(),
+        ..Default::default()
+    };
+    let forbid = TagParser {
+        v_prefix: VPrefixPolicy::Forbid,
+        ..TagParser::default()
+    };
+    let require = TagParser {
+        v_prefix: VPrefixPolicy::Require,
+        ..TagParser::default()
+    };
+
+    assert_eq!(
+        forbid.format_tag(Some(&package), &version),
+        "axolotlsay-1.0.0"
+    );
+    assert_eq!(forbid.format_tag(None, &version), "1.0.0");
+    assert_eq!(require.format_tag(None, &version), "v1.0.0");
+}
+
+#[test]
+fn parse_build_metadata() {
+    // "axolotlsay-v1.0.0+build.42" when the package has the same metadata
+    let version = "1.0.0+build.42".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+    let tag = format!("axolotlsay-v{version}");
+
+    let announcing = parse_tag(&packages, &tag).unwrap();
+
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.build_metadata().unwrap().as_str(), "build.42");
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+
+    let announcing = parse_tag(&packages, "v1.0.0").unwrap();
+    assert_eq!(announcing.build_metadata(), None);
+}
+
+#[test]
+fn fail_parse_build_metadata_mismatch() {
+    // "axolotlsay-v1.0.0+build.42" when the package has no metadata
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let err = parse_tag(&packages, "axolotlsay-v1.0.0+build.42").unwrap_err();
+
+    assert!(matches!(err, TagError::ContradictoryTagVersion { .. }));
+}
+
+#[test]
+fn parse_build_metadata_ignored() {
+    // "axolotlsay-v1.0.0+build.42" when the package has other metadata, and we don't care
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0+build.41".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        build_metadata: BuildMetadataPolicy::Ignore,
+        ..TagParser::default()
+    };
+
+    let announcing = parser
+        .parse_tag(&packages, "axolotlsay-v1.0.0+build.42")
+        .unwrap();
+
+    assert_eq!(announcing.build_metadata().unwrap().as_str(), "build.42");
+
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.0.1+build.41")
+        .unwrap_err();
+    assert!(matches!(err, TagError::ContradictoryTagVersion { .. }));
+}
+
+#[test]
+fn selected_packages_build_metadata_ignored() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        build_metadata: BuildMetadataPolicy::Ignore,
+        unified_versions: UnifiedVersionPolicy::All,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "v1.2.3+build.1").unwrap();
+
+    assert_eq!(parser.selected_packages(&announcing, &packages), vec![0, 1]);
+    // Without the parser, versions have to match exactly
+    assert!(announcing.selected_packages(&packages).is_empty());
+    let plan = parser.plan_tag(&packages, "v1.2.3+build.1").unwrap();
+    assert_eq!(plan.packages.len(), 2);
+    assert!(parser
+        .diff_tag("v1.2.3+build.1", &packages[..1], &packages)
+        .added
+        .contains(&"otherapp".to_owned()));
+}
+
+#[test]
+fn version_like_package_names() {
+    let names = [
+        ("v1", true),
+        ("2", true),
+        ("1.2.3", true),
+        ("v", false),
+        ("v1-cli", false),
+        ("axolotlsay", false),
+    ];
+    for (name, expected) in names {
+        let package = Package {
+            name: name.to_owned(),
+            version: None,
+            ..Default::default()
+        };
+        assert_eq!(package.has_version_like_name(), expected, "{name}");
+    }
+}
+
+#[test]
+fn parse_version_like_package() {
+    // a package literally named "v1"
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "v1".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+
+    // unambiguous package release
+    let announcing = parse_tag(&packages, "v1-v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: version.clone()
+        }
+    );
+    assert!(announcing.warnings.is_empty());
+
+    // unambiguous unified release
+    let announcing = parse_tag(&packages, "v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(version.clone())
+    );
+    assert!(!announcing
+        .warnings
+        .iter()
+        .any(|w| matches!(w, TagWarning::VersionLikePackageName { .. })));
+
+    // could also be a unified release with a "v1/" prefix, the package wins
+    let announcing = parse_tag(&packages, "v1/v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::VersionLikePackageName {
+            package_name: "v1".to_owned()
+        }]
+    );
+}
+
+#[test]
+fn fail_parse_ambiguous_package_name() {
+    // "v1/v1.0.0" with a package named "v1", when we want explicit disambiguation
+    let packages = vec![Package {
+        name: "v1".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        require_unambiguous_package_names: true,
+        ..TagParser::default()
+    };
+
+    let err = parser.parse_tag(&packages, "v1/v1.0.0").unwrap_err();
+    assert!(matches!(err, TagError::AmbiguousPackageName { .. }));
+
+    assert!(parser.parse_tag(&packages, "v1-v1.0.0").is_ok());
+}
+
+#[test]
+fn parse_scope_preference() {
+    // "axolotlsay-v1.0.0" in a unified workspace
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+    ];
+    let tag = "axolotlsay-v1.0.0";
+
+    let announcing = parse_tag(&packages, tag).unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: version.clone()
+        }
+    );
+
+    let parser = TagParser {
+        scope_preference: ScopePreference::Unified,
+        ..TagParser::default()
+    };
+    let announcing = parser.parse_tag(&packages, tag).unwrap();
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
+    assert_eq!(announcing.selected_packages(&packages), vec![0, 1]);
+
+    let parser = TagParser {
+        scope_preference: ScopePreference::Error,
+        ..TagParser::default()
+    };
+    let err = parser.parse_tag(&packages, tag).unwrap_err();
+    assert!(matches!(err, TagError::AmbiguousScope { .. }));
+}
+
+#[test]
+fn parse_scope_preference_unambiguous() {
+    // "axolotlsay-v1.0.0" in a disjoint workspace is only a package release
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        scope_preference: ScopePreference::Error,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_package_path() {
+    // "crates/app/v1.0.0" where the package in crates/app is named axolotlsay
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            path: Some("crates/other".to_owned()),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(version.clone()),
+            path: Some("crates/app/".to_owned()),
+            ..Default::default()
+        },
+    ];
+    let tag = format!("crates/app/v{version}");
+
+    let announcing = parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(announcing.format, Some(TagFormat::Path));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 1, version }
+    );
+}
+
+#[test]
+fn parse_package_path_nested() {
+    // "releases/crates/app/cli/v1.0.0" should pick the deepest matching path
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "2.0.0".parse().ok(),
+            path: Some("cli".to_owned()),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay-cli".to_owned(),
+            version: Some(version.clone()),
+            path: Some("crates/app/cli".to_owned()),
+            ..Default::default()
+        },
+    ];
+    let tag = format!("releases/crates/app/cli/v{version}");
+
+    let announcing = parse_tag(&packages, &tag).unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 1, version }
+    );
+}
+
+#[test]
+fn parse_package_path_partial_component() {
+    // "crates/myapp/v1.0.0" shouldn't match the path "app"
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        path: Some("app".to_owned()),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "crates/myapp/v1.0.0").unwrap();
+
+    assert_eq!(announcing.release, AnnouncementKind::Unified(version));
+}
+
+#[test]
+fn format_package_path() {
+    let version = "1.0.0".parse::<Version>().unwrap();
+    let package = Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        path: Some("crates\\app".to_owned()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        TagFormat::Path.format(Some(&package), &version),
+        "crates/app/v1.0.0"
+    );
+    let package = Package {
+        path: None,
+        ..package
+    };
+    assert_eq!(
+        TagFormat::Path.format(Some(&package), &version),
+        "axolotlsay/v1.0.0"
+    );
+}
+
+#[test]
+fn error_kind() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let err = parse_tag(&packages, "axolotlsay-v2.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::ContradictoryTagVersion);
+
+    let err = parse_tag(&packages, "axolotlsay-vnope").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+}
+
+#[test]
+fn parse_go_major_version_path() {
+    // "mymod/v2.1.0" for a Go module living at mymod/v2
+    let version = "2.1.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "example.com/mymod/v2".to_owned(),
+        version: Some(version.clone()),
+        path: Some("mymod/v2".to_owned()),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "mymod/v2.1.0").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Path));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: version.clone()
+        }
+    );
+    assert_eq!(
+        TagFormat::Path.format(Some(&packages[0]), &version),
+        "mymod/v2.1.0"
+    );
+
+    // the full path works too
+    let announcing = parse_tag(&packages, "mymod/v2/v2.1.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+}
+
+#[test]
+fn parse_go_major_version_mismatch() {
+    // "mymod/v3.0.0" doesn't belong to the module at mymod/v2
+    let packages = vec![Package {
+        name: "example.com/mymod/v2".to_owned(),
+        version: "2.1.0".parse().ok(),
+        path: Some("mymod/v2".to_owned()),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "mymod/v3.0.0").unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("3.0.0".parse().unwrap())
+    );
+}
+
+#[test]
+fn parse_go_major_version_marker() {
+    // "axolotlsay/v2/v2.1.0" where v2 is a major version marker, not a package
+    let version = "2.1.0".parse::<Version>().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(version.clone()),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "axolotlsay/v2/v2.1.0").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Slash));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package { idx: 0, version }
+    );
+
+    // the marker has to agree with the version, otherwise it's just an arbitrary prefix
+    let announcing = parse_tag(&packages, "axolotlsay/v3/v2.1.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("2.1.0".parse().unwrap())
+    );
+}
+
+#[test]
+fn describe_package() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "axolotlsay-v1.2.3").unwrap();
+
+    assert_eq!(
+        announcing.describe(&packages),
+        "announcing package `axolotlsay` at 1.2.3 (prerelease: no), matched format `{package}-v{version}`"
+    );
+    let description = announcing.description(&packages);
+    assert_eq!(description.scope, DescribedScope::Package);
+    assert_eq!(description.packages, vec!["axolotlsay".to_owned()]);
+    assert_eq!(description.format, Some(TagFormat::Dash));
+}
+
+#[test]
+fn describe_unified() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3-rc.1".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.2.3-rc.1".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "releases/v1.2.3-rc.1").unwrap();
+
+    assert_eq!(
+        announcing.describe(&packages),
+        "announcing 2 packages (`axolotlsay`, `otherapp`) at 1.2.3-rc.1 (prerelease: yes), matched format `v{version}`"
+    );
+}
+
+#[test]
+fn describe_channel() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "axolotlsay/latest").unwrap();
+
+    assert_eq!(
+        announcing.describe(&packages),
+        "announcing package `axolotlsay` on channel `latest` (prerelease: no), matched format `{package}/v{version}`"
+    );
+    assert_eq!(
+        PartialAnnouncementTag::default().describe(&packages),
+        "announcing nothing"
+    );
+}
+
+#[test]
+fn describe_with_parser_settings() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        v_prefix: VPrefixPolicy::Forbid,
+        package_separators: vec!['@'],
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay@1.2.3").unwrap();
+    assert_eq!(
+        parser.describe(&announcing, &packages),
+        "announcing package `axolotlsay` at 1.2.3 (prerelease: no), matched format `{package}@{version}`"
+    );
+    let announcing = parser.parse_tag(&packages, "1.2.3").unwrap();
+    assert_eq!(
+        parser
+            .description(&announcing, &packages)
+            .template
+            .as_deref(),
+        Some("{version}")
+    );
+}
+
+#[test]
+fn parse_rejected_candidates() {
+    // "axolotlsay-helper-v1.0.0" where axolotlsay also prefix-matches
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay-helper".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotl".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "axolotlsay-helper-v1.0.0").unwrap();
+
+    assert_eq!(
+        announcing.warnings,
+        vec![
+            TagWarning::RejectedCandidate {
+                package_name: "axolotlsay".to_owned(),
+                reason: RejectionReason::LongerMatch {
+                    package_name: "axolotlsay-helper".to_owned()
+                },
+            },
+            TagWarning::RejectedCandidate {
+                package_name: "axolotl".to_owned(),
+                reason: RejectionReason::LongerMatch {
+                    package_name: "axolotlsay-helper".to_owned()
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_rejected_candidate_separator() {
+    // "v1.0.0" with a package named "v1", explaining why it isn't a release of v1
+    let packages = vec![Package {
+        name: "v1".to_owned(),
+        version: "2.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "v1.0.0").unwrap();
+
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("1.0.0".parse().unwrap())
+    );
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::RejectedCandidate {
+            package_name: "v1".to_owned(),
+            reason: RejectionReason::WrongSeparator { found: '.' },
+        }]
+    );
+}
+
+#[test]
+fn parse_reports_style() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        path: Some("crates/app".to_owned()),
+        ..Default::default()
+    }];
+    let cases = [
+        ("v1.0.0", TagStyle::Unified),
+        ("axolotlsay-v1.0.0", TagStyle::PackageDash),
+        ("axolotlsay/v1.0.0", TagStyle::PackageSlash),
+        ("crates/app/v1.0.0", TagStyle::PackagePath),
+        ("latest", TagStyle::Unified),
+        (
+            "releases/v1.0.0",
+            TagStyle::SlashPrefixed {
+                prefix: "releases".to_owned(),
+                style: Box::new(TagStyle::Unified),
+            },
+        ),
+        (
+            "blah/releases/axolotlsay/v1.0.0",
+            TagStyle::SlashPrefixed {
+                prefix: "blah/releases".to_owned(),
+                style: Box::new(TagStyle::PackageSlash),
+            },
+        ),
+        (
+            "releases/crates/app/v1.0.0",
+            TagStyle::SlashPrefixed {
+                prefix: "releases".to_owned(),
+                style: Box::new(TagStyle::PackagePath),
+            },
+        ),
+    ];
+
+    for (tag, expected) in cases {
+        let announcing = parse_tag(&packages, tag).unwrap();
+        assert_eq!(announcing.style, Some(expected), "{tag}");
+    }
+}
+
+#[test]
+fn style_without_prefix() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "releases/axolotlsay-v1.0.0").unwrap();
+    let style = announcing.style.unwrap();
+
+    assert_eq!(style.prefix(), Some("releases"));
+    assert_eq!(style.without_prefix(), &TagStyle::PackageDash);
+}
+
+#[test]
+fn dist_channels() {
+    let cases = [
+        ("1.0.0", DistChannel::Stable, "latest", None),
+        (
+            "1.0.0-beta.2",
+            DistChannel::Prerelease {
+                label: "beta".to_owned(),
+            },
+            "beta",
+            Some("--pre"),
+        ),
+        (
+            "1.0.0-RC1",
+            DistChannel::Prerelease {
+                label: "rc".to_owned(),
+            },
+            "rc",
+            Some("--pre"),
+        ),
+        (
+            "1.0.0-1",
+            DistChannel::Prerelease {
+                label: "prerelease".to_owned(),
+            },
+            "prerelease",
+            Some("--pre"),
+        ),
+    ];
+
+    for (version, channel, dist_tag, flag) in cases {
+        let version = version.parse::<Version>().unwrap();
+        let actual = DistChannel::for_version(&version);
+        assert_eq!(actual, channel, "{version}");
+        assert_eq!(actual.npm_dist_tag(), dist_tag, "{version}");
+        assert_eq!(actual.homebrew_flag(), flag, "{version}");
+    }
+}
+
+#[test]
+fn announcement_dist_channel() {
+    let announcing = parse_tag(&[], "v1.0.0-alpha.1").unwrap();
+
+    assert_eq!(
+        announcing.dist_channel(),
+        Some(DistChannel::Prerelease {
+            label: "alpha".to_owned()
+        })
+    );
+    assert_eq!(parse_tag(&[], "latest").unwrap().dist_channel(), None);
+}
+
+#[test]
+fn homebrew_versions() {
+    let cases = [
+        ("1.2.3", "1.2.3"),
+        ("1.2.3-rc.1", "1.2.3-rc.1"),
+        ("1.2.3+build.4", "1.2.3"),
+        ("1.2.3-rc.1+build.4", "1.2.3-rc.1"),
+    ];
+
+    for (version, expected) in cases {
+        let version = version.parse::<Version>().unwrap();
+        assert_eq!(to_homebrew_version(&version), expected);
+    }
+}
+
+#[test]
+fn lint_tags() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "0.3.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let tags = [
+        "v0.1.0",
+        "axolotlsay-v1.0.0",
+        "otherapp-v0.2.0",
+        "axolotlsay/v1.1.0",
+        "oldapp-v0.1.0",
+        "axolotlsay-v1.0.5",
+        "v0.1.0-rc.1",
+        "garbage",
+        "releases/otherapp-v0.3.0",
+    ];
+
+    let lints = TagParser::default().lint_tags(&packages, tags, TagFormat::Dash);
+
+    assert_eq!(
+        lints,
+        vec![
+            TagLint {
+                tag: "axolotlsay/v1.1.0".to_owned(),
+                problem: LintProblem::InconsistentStyle {
+                    expected: TagFormat::Dash,
+                    found: TagStyle::PackageSlash,
+                },
+            },
+            TagLint {
+                tag: "oldapp-v0.1.0".to_owned(),
+                problem: LintProblem::UnknownPackage {
+                    package_name: "oldapp".to_owned()
+                },
+            },
+            TagLint {
+                tag: "axolotlsay-v1.0.5".to_owned(),
+                problem: LintProblem::NonMonotonic {
+                    previous_tag: "axolotlsay/v1.1.0".to_owned(),
+                    previous_version: "1.1.0".parse().unwrap(),
+                    version: "1.0.5".parse().unwrap(),
+                },
+            },
+            TagLint {
+                tag: "v0.1.0-rc.1".to_owned(),
+                problem: LintProblem::NonMonotonic {
+                    previous_tag: "v0.1.0".to_owned(),
+                    previous_version: "0.1.0".parse().unwrap(),
+                    version: "0.1.0-rc.1".parse().unwrap(),
+                },
+            },
+            TagLint {
+                tag: "garbage".to_owned(),
+                problem: LintProblem::Unparseable {
+                    kind: TagErrorKind::TagVersionParse
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn lint_tags_like_history() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            version_scheme: VersionScheme::BuildNumber,
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        unified_versions: UnifiedVersionPolicy::All,
+        ..TagParser::default()
+    };
+    let tags = [
+        "v0.9.0",
+        "axolotlsay-v1.2.3-build.1",
+        "axolotlsay-v1.2.3-build.2",
+        "axolotlsay-v1.2.3-build.2",
+    ];
+
+    let lints = parser.lint_tags(&packages, tags, TagFormat::Dash);
+
+    // Only re-announcing the same build goes backwards
+    assert_eq!(
+        lints,
+        vec![TagLint {
+            tag: "axolotlsay-v1.2.3-build.2".to_owned(),
+            problem: LintProblem::NonMonotonic {
+                previous_tag: "axolotlsay-v1.2.3-build.2".to_owned(),
+                previous_version: "1.2.3".parse().unwrap(),
+                version: "1.2.3".parse().unwrap(),
+            },
+        }]
+    );
+}
+
+#[test]
+fn next_versions() {
+    let rc = |label: &str| BumpLevel::Prerelease {
+        label: Some(label.to_owned()),
+    };
+    let pre = BumpLevel::Prerelease { label: None };
+    let cases = [
+        ("1.2.3", BumpLevel::Major, "2.0.0"),
+        ("1.2.3", BumpLevel::Minor, "1.3.0"),
+        ("1.2.3", BumpLevel::Patch, "1.2.4"),
+        ("1.2.3", pre.clone(), "1.2.4-rc.1"),
+        ("1.2.3", rc("beta"), "1.2.4-beta.1"),
+        ("1.2.3-rc.1", pre.clone(), "1.2.3-rc.2"),
+        ("1.2.3-rc.9", rc("rc"), "1.2.3-rc.10"),
+        ("1.2.3-beta.2", rc("rc"), "1.2.3-rc.1"),
+        ("1.2.3-rc.1", rc("beta"), "1.2.4-beta.1"),
+        ("1.2.3-alpha", pre.clone(), "1.2.3-alpha.1"),
+        ("1.2.3-rc.1", BumpLevel::Patch, "1.2.3"),
+        ("1.3.0-rc.1", BumpLevel::Minor, "1.3.0"),
+        ("1.2.3-rc.1", BumpLevel::Minor, "1.3.0"),
+        ("2.0.0-rc.1", BumpLevel::Major, "2.0.0"),
+        ("2.1.0-rc.1", BumpLevel::Major, "3.0.0"),
+        ("1.2.3+build.1", BumpLevel::Patch, "1.2.4"),
+    ];
+
+    for (current, bump, expected) in cases {
+        let current = current.parse::<Version>().unwrap();
+        assert_eq!(
+            next_version(&current, &bump).unwrap().to_string(),
+            expected,
+            "{current} {bump:?}"
+        );
+    }
+
+    let err = next_version(&Version::new(1, 2, 3), &rc("my rc")).unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::InvalidPrereleaseLabel);
+    let maxed = "1.2.3-rc.18446744073709551615".parse::<Version>().unwrap();
+    let err = next_version(&maxed, &pre).unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::PrereleaseCounterOverflow);
+
+    // Every other component can overflow too
+    let max = u64::MAX;
+    let overflows = [
+        (Version::new(max, 0, 0), BumpLevel::Major, "major"),
+        (Version::new(1, max, 0), BumpLevel::Minor, "minor"),
+        (Version::new(1, 2, max), BumpLevel::Patch, "patch"),
+        (Version::new(1, 2, max), rc("rc"), "patch"),
+    ];
+    for (current, bump, expected) in overflows {
+        match next_version(&current, &bump) {
+            Err(TagError::VersionOverflow { component, .. }) => {
+                assert_eq!(component, expected, "{current} {bump:?}")
+            }
+            other => panic!("{current} {bump:?}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn suggest_next_tag() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "3.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let tags = [
+        "axolotlsay-v1.0.0",
+        "axolotlsay-v1.1.0-rc.1",
+        "otherapp-v3.0.0",
+        "axolotlsay-v0.9.0",
+        "v0.5.0",
+    ];
+    let parser = TagParser::default();
+
+    let next = parser
+        .suggest_next_tag(
+            &packages,
+            Some(0),
+            tags,
+            &BumpLevel::Prerelease { label: None },
+        )
+        .unwrap();
+    assert_eq!(next.tag, "axolotlsay-v1.1.0-rc.2");
+    assert_eq!(next.previous_tag.as_deref(), Some("axolotlsay-v1.1.0-rc.1"));
+
+    let next = parser
+        .suggest_next_tag(&packages, Some(0), tags, &BumpLevel::Minor)
+        .unwrap();
+    assert_eq!(next.tag, "axolotlsay-v1.1.0");
+
+    let next = parser
+        .suggest_next_tag(&packages, None, tags, &BumpLevel::Patch)
+        .unwrap();
+    assert_eq!(next.tag, "v0.5.1");
+
+    // a newer unified release released every package
+    let next = parser
+        .suggest_next_tag(
+            &packages,
+            Some(0),
+            ["axolotlsay-v1.0.0", "v5.0.0"],
+            &BumpLevel::Minor,
+        )
+        .unwrap();
+    assert_eq!(next.tag, "axolotlsay-v5.1.0");
+    assert_eq!(next.previous_tag.as_deref(), Some("v5.0.0"));
+
+    // no tags, so bump from the package's version
+    let next = parser
+        .suggest_next_tag(&packages, Some(1), [] as [&str; 0], &BumpLevel::Major)
+        .unwrap();
+    assert_eq!(next.tag, "otherapp-v4.0.0");
+    assert_eq!(next.previous_tag, None);
+}
+
+#[test]
+fn fail_suggest_next_tag() {
+    // a disjoint workspace with no unified tags has nothing to bump
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "3.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let err = TagParser::default()
+        .suggest_next_tag(&packages, None, ["axolotlsay-v1.0.0"], &BumpLevel::Patch)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        TagError::NoVersionToBump { package_name: None }
+    ));
+}
+
+#[test]
+fn msi_versions() {
+    let cases = [
+        ("1.2.3", "1.2.3"),
+        ("1.2.3-rc.1", "1.2.3"),
+        ("1.2.3+build.4", "1.2.3"),
+        ("255.255.65535", "255.255.65535"),
+    ];
+    for (version, expected) in cases {
+        let version = version.parse::<Version>().unwrap();
+        assert_eq!(to_msi_version(&version).unwrap(), expected);
+    }
+
+    for (version, expected) in [
+        ("256.0.0", "major"),
+        ("1.256.0", "minor"),
+        ("1.2.65536", "patch"),
+    ] {
+        let version = version.parse::<Version>().unwrap();
+        let err = to_msi_version(&version).unwrap_err();
+        let TagError::MsiVersionOverflow { component, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(component, expected);
+    }
+}
+
+#[test]
+fn linux_package_versions() {
+    let cases = [
+        ("1.2.3", "1.2.3", "1.2.3"),
+        ("1.2.3-rc.1", "1.2.3~rc.1", "1.2.3~rc.1"),
+        (
+            "1.2.3-pre-release.2",
+            "1.2.3~pre.release.2",
+            "1.2.3~pre.release.2",
+        ),
+        ("1.2.3+build.4", "1.2.3+build.4", "1.2.3"),
+        ("1.2.3-rc.1+build-4", "1.2.3~rc.1+build.4", "1.2.3~rc.1"),
+    ];
+
+    for (version, deb, rpm) in cases {
+        let version = version.parse::<Version>().unwrap();
+        assert_eq!(to_deb_version(&version), deb, "{version}");
+        assert_eq!(to_rpm_version(&version), rpm, "{version}");
+    }
+}
+
+#[test]
+fn check_not_regression() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "0.1.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let existing = ["axolotlsay-v1.0.0", "axolotlsay-v1.1.0", "otherapp-v2.0.0"];
+    let parser = TagParser::default();
+
+    let announcing = parser
+        .check_not_regression(&packages, "axolotlsay-v1.2.0", existing)
+        .unwrap();
+    assert!(announcing.warnings.is_empty());
+
+    // another package having a newer version doesn't matter
+    assert!(parser
+        .check_not_regression(&packages, "otherapp-v0.1.0", ["axolotlsay-v9.0.0"])
+        .is_ok());
+}
+
+#[test]
+fn fail_check_not_regression() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let existing = ["axolotlsay-v1.0.0", "axolotlsay/v1.1.0"];
+
+    let err = TagParser::default()
+        .check_not_regression(&packages, "axolotlsay-v1.0.0", existing)
+        .unwrap_err();
+
+    let TagError::VersionRegression {
+        existing_tag,
+        existing_version,
+        ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(existing_tag, "axolotlsay/v1.1.0");
+    assert_eq!(existing_version, "1.1.0".parse().unwrap());
+}
+
+#[test]
+fn check_not_regression_warn() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        regressions: RegressionPolicy::Warn,
+        ..TagParser::default()
+    };
+
+    let announcing = parser
+        .check_not_regression(&packages, "axolotlsay/v1.0.0", ["axolotlsay-v1.0.0"])
+        .unwrap();
+
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::VersionRegression {
+            version: "1.0.0".parse().unwrap(),
+            existing_tag: "axolotlsay-v1.0.0".to_owned(),
+            existing_version: "1.0.0".parse().unwrap(),
+        }]
+    );
+}
+
+#[test]
+fn check_not_regression_existing_tags_predate_policies() {
+    use crate::PrereleasePolicy;
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let regresses = |parser: TagParser, new_tag: &str, existing_tag: &str| {
+        let err = parser
+            .check_not_regression(&packages, new_tag, [existing_tag])
+            .unwrap_err();
+        assert_eq!(err.kind(), TagErrorKind::VersionRegression, "{new_tag}");
+    };
+
+    // No package has 2.0.0 any more, but it was still released
+    regresses(
+        TagParser {
+            unified_versions: UnifiedVersionPolicy::All,
+            ..TagParser::default()
+        },
+        "v1.0.0",
+        "v2.0.0",
+    );
+    regresses(
+        TagParser {
+            unified_versions: UnifiedVersionPolicy::Any,
+            ..TagParser::default()
+        },
+        "v1.0.0",
+        "v2.0.0",
+    );
+    // 2.0.0 was released before the ceiling went in
+    regresses(
+        TagParser {
+            version_bounds: vec![VersionBounds {
+                package_name: Some("axolotlsay".to_owned()),
+                floor: None,
+                ceiling: Some(Version::new(2, 0, 0)),
+            }],
+            ..TagParser::default()
+        },
+        "axolotlsay-v1.0.0",
+        "axolotlsay-v2.0.0",
+    );
+    // ...and before the repo moved to a new format
+    regresses(
+        TagParser {
+            reject_legacy_formats: true,
+            ..TagParser::default()
+        },
+        "axolotlsay-v1.0.0",
+        "axolotlsay/v2.0.0",
+    );
+    regresses(
+        TagParser {
+            directional_version_mismatch: true,
+            ..TagParser::default()
+        },
+        "axolotlsay-v1.0.0",
+        "axolotlsay-v2.0.0",
+    );
+
+    // Tags for packages that are unversioned (or placeholders) now were still releases
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: None,
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(0, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        unversioned_packages: UnversionedPolicy::Error,
+        placeholder_versions: PlaceholderPolicy::Error,
+        ..TagParser::default()
+    };
+    let latest = parser.latest_releases(
+        &packages,
+        ["axolotlsay-v1.0.0", "otherapp-v0.5.0"],
+        PrereleasePolicy::Skip,
+    );
+    assert_eq!(
+        latest.into_iter().collect::<Vec<_>>(),
+        vec![(0, Version::new(1, 0, 0)), (1, Version::new(0, 5, 0))]
+    );
+
+    // Packages that share a version now didn't necessarily back then, so a package's tag
+    // stays scoped to that package
+    let packages = vec![
+        Package {
+            name: "a".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "b".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    for scope_preference in [ScopePreference::Unified, ScopePreference::Error] {
+        let parser = TagParser {
+            scope_preference,
+            ..TagParser::default()
+        };
+        let latest = parser.latest_releases(&packages, ["a-v2.0.0"], PrereleasePolicy::Skip);
+        assert_eq!(
+            latest.into_iter().collect::<Vec<_>>(),
+            vec![(0, Version::new(2, 0, 0))],
+            "{scope_preference:?}"
+        );
+    }
+}
+
+#[test]
+fn check_not_regression_unified_releases() {
+    use crate::PrereleasePolicy;
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    // A unified release released every package...
+    let latest = parser.latest_releases(&packages, ["v2.0.0"], PrereleasePolicy::Skip);
+    assert_eq!(latest[&0], Version::new(2, 0, 0));
+    let err = parser
+        .check_not_regression(&packages, "axolotlsay-v1.0.0", ["v2.0.0"])
+        .unwrap_err();
+    let TagError::VersionRegression { existing_tag, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(existing_tag, "v2.0.0");
+
+    // ...and so a unified release can't take the packages it releases back either
+    let err = parser
+        .check_not_regression(&packages, "v1.0.0", ["axolotlsay-v2.0.0"])
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::VersionRegression);
+    assert!(parser
+        .check_not_regression(
+            &packages,
+            "axolotlsay-v1.0.0",
+            ["v0.9.0", "otherapp-v9.0.0"]
+        )
+        .is_ok());
+}
+
+#[test]
+fn check_prerelease_first() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            ..Default::default()
+        },
+    ];
+    let existing = ["axolotlsay-v1.0.0-rc.1", "otherapp-v0.9.0-beta.1", "v0.1.0"];
+
+    // Off by default
+    assert!(TagParser::default()
+        .check_prerelease_first(&packages, "otherapp-v1.0.0", existing)
+        .is_ok());
+
+    let parser = TagParser {
+        require_prereleases: true,
+        ..TagParser::default()
+    };
+    assert!(parser
+        .check_prerelease_first(&packages, "axolotlsay-v1.0.0", existing)
+        .is_ok());
+    // Prereleases themselves don't need one
+    assert!(parser
+        .check_prerelease_first(&packages, "otherapp-v1.0.0-rc.1", existing)
+        .is_ok());
+
+    // Another package's (or version's) prerelease doesn't count
+    let err = parser
+        .check_prerelease_first(&packages, "otherapp-v1.0.0", existing)
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::MissingPrerelease);
+    let err = parser
+        .check_prerelease_first(&packages, "v1.0.0", existing)
+        .unwrap_err();
+    let TagError::MissingPrerelease { tag, version } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(tag, "v1.0.0");
+    assert_eq!(version, Version::new(1, 0, 0));
+}
+
+#[test]
+fn same_release_build_metadata() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Ignore,
+        ..TagParser::default()
+    };
+    let a = parser
+        .parse_tag(&packages, "axolotlsay-v1.0.0+build.1")
+        .unwrap();
+    let b = parser
+        .parse_tag(&packages, "axolotlsay/v1.0.0+build.2")
+        .unwrap();
+
+    assert!(!a.same_release(&b, BuildMetadataPolicy::Exact));
+    assert!(a.same_release(&b, BuildMetadataPolicy::Ignore));
+    assert!(!a.same_release(
+        &parser.parse_tag(&packages, "v1.0.0+build.1").unwrap(),
+        BuildMetadataPolicy::Ignore
+    ));
+
+    let parser = TagParser {
+        build_metadata: BuildMetadataPolicy::Ignore,
+        ..parser
+    };
+    let unique = parser.dedup_announcements([a.clone(), b]);
+    assert_eq!(unique, vec![a]);
+}
+
+#[test]
+fn fail_check_not_regression_build_metadata() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0+build.2".parse().ok(),
+        ..Default::default()
+    }];
+    let existing = ["axolotlsay-v1.0.0+build.1"];
+
+    // Exact build metadata makes this a different (newer) release...
+    assert!(TagParser::default()
+        .check_not_regression(&packages, "axolotlsay-v1.0.0+build.2", existing)
+        .is_ok());
+
+    // ...but by semver precedence it's the same release
+    let err = TagParser {
+        build_metadata: BuildMetadataPolicy::Ignore,
+        ..TagParser::default()
+    }
+    .check_not_regression(&packages, "axolotlsay-v1.0.0+build.2", existing)
+    .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::VersionRegression);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn parse_custom_pattern() {
+    use crate::TagPattern;
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "2.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        patterns: vec![TagPattern::new(r"release@(?P<package>[a-z]+)==(?P<version>.+)").unwrap()],
+        ..TagParser::default()
+    };
+
+    let announcing = parser
+        .parse_tag(&packages, "release@otherapp==v2.0.0")
+        .unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 1,
+            version: "2.0.0".parse().unwrap()
+        }
+    );
+    assert_eq!(
+        announcing.style,
+        Some(TagStyle::Pattern {
+            pattern: r"release@(?P<package>[a-z]+)==(?P<version>.+)".to_owned()
+        })
+    );
+    assert_eq!(announcing.format, None);
+
+    // An unknown package means the pattern doesn't apply, so the built-in formats get a go
+    let err = parser
+        .parse_tag(&packages, "release@nothing==v2.0.0")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+
+    // Tags the pattern doesn't match still parse normally
+    let announcing = parser.parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Dash));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn parse_custom_pattern_unified() {
+    use crate::TagPattern;
+
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        patterns: vec![TagPattern::new(r"(?:(?P<package>[a-z]+)@)?(?P<version>v.+)").unwrap()],
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("1.0.0".parse().unwrap())
+    );
+    let announcing = parser.parse_tag(&packages, "axolotlsay@v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: "1.0.0".parse().unwrap()
+        }
+    );
+    // Version checks still apply
+    let err = parser
+        .parse_tag(&packages, "axolotlsay@v2.0.0")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::ContradictoryTagVersion);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn fail_custom_pattern_without_version() {
+    use crate::TagPattern;
+
+    let err = TagPattern::new(r"(?P<package>[a-z]+)").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::InvalidTagPattern);
+    let err = TagPattern::new(r"(?P<version>").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::InvalidTagPattern);
+}
+
+#[test]
+fn parse_insensitive_names() {
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+
+    let err = TagParser::default()
+        .parse_tag(&packages, "My_App-v1.2.3")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+
+    let parser = TagParser {
+        case_insensitive_names: true,
+        separator_insensitive_names: true,
+        ..TagParser::default()
+    };
+    for tag in ["My_App-v1.2.3", "my_app/v1.2.3", "MY-APP-v1.2.3"] {
+        let announcing = parser.parse_tag(&packages, tag).unwrap();
+        assert_eq!(
+            announcing.release,
+            AnnouncementKind::Package {
+                idx: 0,
+                version: "1.2.3".parse().unwrap()
+            },
+            "{tag}"
+        );
+    }
+
+    // Each option only does its own thing
+    let parser = TagParser {
+        separator_insensitive_names: true,
+        ..TagParser::default()
+    };
+    assert!(parser.parse_tag(&packages, "my_app-v1.2.3").is_ok());
+    assert!(parser.parse_tag(&packages, "My_App-v1.2.3").is_err());
+}
+
+#[test]
+fn parse_placeholder_versions() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "internal".to_owned(),
+            version: "0.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    assert!(packages[1].has_placeholder_version());
+    assert!(!packages[0].has_placeholder_version());
+
+    // Allowed by default
+    TagParser::default()
+        .parse_tag(&packages, "internal-v0.0.0")
+        .unwrap();
+
+    let parser = TagParser {
+        placeholder_versions: PlaceholderPolicy::Warn,
+        version_match: VersionMatchPolicy::Ignore,
+        ..TagParser::default()
+    };
+    let announcing = parser.parse_tag(&packages, "internal-v1.0.0").unwrap();
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::PlaceholderVersion {
+            package_name: Some("internal".to_owned())
+        }]
+    );
+    let announcing = parser.parse_tag(&packages, "v0.0.0").unwrap();
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::PlaceholderVersion { package_name: None }]
+    );
+}
+
+#[test]
+fn fail_parse_placeholder_versions() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "internal".to_owned(),
+            version: "0.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        placeholder_versions: PlaceholderPolicy::Error,
+        ..TagParser::default()
+    };
+
+    let err = parser.parse_tag(&packages, "internal-v0.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::PlaceholderVersion);
+    // The placeholder is reported before the version mismatch
+    let err = parser.parse_tag(&packages, "internal-v1.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::PlaceholderVersion);
+    parser.parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+}
+
+#[test]
+fn selected_packages_exclude_placeholders() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "internal".to_owned(),
+            version: "0.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        placeholder_versions: PlaceholderPolicy::Exclude,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "latest").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![0, 1]);
+    assert_eq!(parser.selected_packages(&announcing, &packages), vec![0]);
+
+    let err = parser.parse_tag(&packages, "internal-v0.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::PlaceholderVersion);
+}
+
+#[test]
+fn parse_version_bounds() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "3.1.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "0.4.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Ignore,
+        version_bounds: vec![
+            // 2.x is frozen
+            VersionBounds {
+                package_name: Some("axolotlsay".to_owned()),
+                floor: "3.0.0".parse().ok(),
+                ceiling: None,
+            },
+            // helper stays pre-1.0
+            VersionBounds {
+                package_name: Some("helper".to_owned()),
+                floor: None,
+                ceiling: "1.0.0".parse().ok(),
+            },
+        ],
+        ..TagParser::default()
+    };
+
+    parser.parse_tag(&packages, "axolotlsay-v3.1.0").unwrap();
+    parser.parse_tag(&packages, "helper-v0.9.9").unwrap();
+    // Other packages' bounds don't matter
+    parser.parse_tag(&packages, "axolotlsay-v9.0.0").unwrap();
+
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v2.9.1")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::VersionBelowFloor);
+    // A prerelease of the ceiling is still below it
+    parser.parse_tag(&packages, "helper-v1.0.0-rc.1").unwrap();
+    let err = parser.parse_tag(&packages, "helper-v1.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::VersionAboveCeiling);
+}
+
+#[test]
+fn fail_parse_unified_version_bounds() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "2.5.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "2.5.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        version_bounds: vec![VersionBounds {
+            package_name: Some("helper".to_owned()),
+            floor: "3.0.0".parse().ok(),
+            ceiling: None,
+        }],
+        ..TagParser::default()
+    };
+
+    // A unified release would release helper too
+    let err = parser.parse_tag(&packages, "v2.5.0").unwrap_err();
+    let TagError::VersionBelowFloor { package_name, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(package_name.as_deref(), Some("helper"));
+}
+
+#[test]
+fn parse_package_alias() {
+    let packages = vec![Package {
+        name: "foo-cli".to_owned(),
+        version: "1.2.3".parse().ok(),
+        aliases: vec!["foo".to_owned()],
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "foo-v1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: "1.2.3".parse().unwrap()
+        }
+    );
+    assert_eq!(announcing.alias.as_deref(), Some("foo"));
+
+    let announcing = parse_tag(&packages, "foo/v1.2.3").unwrap();
+    assert_eq!(announcing.alias.as_deref(), Some("foo"));
+
+    // The package's own name is preferred, and isn't an alias
+    let announcing = parse_tag(&packages, "foo-cli-v1.2.3").unwrap();
+    assert_eq!(announcing.alias, None);
+
+    // New tags always use the real name
+    assert_eq!(
+        TagParser::default().format_tag(packages.first(), &"1.2.4".parse().unwrap()),
+        "foo-cli-v1.2.4"
+    );
+}
+
+#[test]
+fn parse_package_alias_longest() {
+    // An alias of one package can be a longer match than another package's name
+    let packages = vec![
+        Package {
+            name: "foo".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "foo-cli".to_owned(),
+            version: "2.0.0".parse().ok(),
+            aliases: vec!["foo-tool".to_owned()],
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "foo-tool-v2.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 1,
+            version: "2.0.0".parse().unwrap()
+        }
+    );
+    assert_eq!(announcing.alias.as_deref(), Some("foo-tool"));
+}
+
+#[test]
+fn fail_parse_excluded_package() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "xtask".to_owned(),
+            version: "1.0.0".parse().ok(),
+            excluded: true,
+            ..Default::default()
+        },
+    ];
+
+    let err = parse_tag(&packages, "xtask-v1.0.0").unwrap_err();
+    let TagError::PackageNotReleasable { package_name, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(package_name, "xtask");
+    let err = parse_tag(&packages, "xtask-latest").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::PackageNotReleasable);
+}
+
+#[test]
+fn selected_packages_skip_excluded() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "xtask".to_owned(),
+            version: "1.0.0".parse().ok(),
+            excluded: true,
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let announcing = parse_tag(&packages, "v1.0.0").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![0, 2]);
+    let announcing = parse_tag(&packages, "latest").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![0, 2]);
+}
+
+#[test]
+fn parse_reserved_tag() {
+    let packages = vec![Package {
+        name: "nightly".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        reserved_tags: vec!["nightly".to_owned(), "edge".to_owned()],
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "edge").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Reserved {
+            name: "edge".to_owned()
+        }
+    );
+    assert_eq!(announcing.selected_packages(&packages), Vec::<usize>::new());
+    assert_eq!(
+        announcing.description(&packages).scope,
+        DescribedScope::Reserved
+    );
+
+    // Only whole tags are reserved
+    let announcing = parser.parse_tag(&packages, "nightly-v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: "1.0.0".parse().unwrap()
+        }
+    );
+
+    // Without the reservation it's just a bad version
+    let err = parse_tag(&packages, "edge").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+}
+
+#[test]
+fn parse_unversioned_package_adopts() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: None,
+        ..Default::default()
+    }];
+
+    let announcing = parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert!(announcing.version_adopted);
+
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let announcing = parse_tag(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert!(!announcing.version_adopted);
+}
+
+#[test]
+fn fail_parse_unversioned_package() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: None,
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        unversioned_packages: UnversionedPolicy::Error,
+        ..TagParser::default()
+    };
+
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.0.0")
+        .unwrap_err();
+    let TagError::UnversionedPackage { package_name, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(package_name, "axolotlsay");
+}
+
+#[test]
+fn fix_its() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let fixed = |parser: &TagParser, tag: &str| {
+        let err = parser.parse_tag(&packages, tag).unwrap_err();
+        err.fix_its()
+            .into_iter()
+            .map(|fix| fix.apply(tag))
+            .collect::<Vec<_>>()
+    };
+
+    let require = TagParser {
+        v_prefix: VPrefixPolicy::Require,
+        ..TagParser::default()
+    };
+    assert_eq!(
+        fixed(&require, "axolotlsay-1.0.0"),
+        vec!["axolotlsay-v1.0.0"]
+    );
+    assert_eq!(fixed(&require, "releases/1.0.0"), vec!["releases/v1.0.0"]);
+
+    let forbid = TagParser {
+        v_prefix: VPrefixPolicy::Forbid,
+        ..TagParser::default()
+    };
+    assert_eq!(
+        fixed(&forbid, "axolotlsay-v1.0.0"),
+        vec!["axolotlsay-1.0.0"]
+    );
+
+    let legacy = TagParser {
+        reject_legacy_formats: true,
+        ..TagParser::default()
+    };
+    assert_eq!(
+        fixed(&legacy, "axolotlsay/v1.0.0"),
+        vec!["axolotlsay-v1.0.0"]
+    );
+
+    assert_eq!(
+        fixed(&TagParser::default(), "axolotlsay-v1.2.0"),
+        vec!["axolotlsay-v1.0.0"]
+    );
+
+    let err = parse_tag(&packages, "axolotlsay-vnope").unwrap_err();
+    assert_eq!(err.fix_its(), vec![]);
+}
+
+#[test]
+fn disambiguate_scope() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    let choices = TagParser::default()
+        .disambiguate(&packages, "axolotlsay-v1.0.0")
+        .unwrap();
+    let labels = choices
+        .candidates
+        .iter()
+        .map(|c| c.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec!["just `axolotlsay` at 1.0.0", "every package at 1.0.0"]
+    );
+    assert_eq!(choices.recommended, 0);
+
+    let parser = TagParser {
+        scope_preference: ScopePreference::Unified,
+        ..TagParser::default()
+    };
+    let choices = parser.disambiguate(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert_eq!(choices.recommended().label, "every package at 1.0.0");
+
+    // Refusing to pick still recommends something
+    let parser = TagParser {
+        scope_preference: ScopePreference::Error,
+        ..TagParser::default()
+    };
+    let choices = parser.disambiguate(&packages, "axolotlsay-v1.0.0").unwrap();
+    assert_eq!(choices.recommended, 0);
+}
+
+#[test]
+fn disambiguate_version_like_name() {
+    let packages = vec![Package {
+        name: "v1".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+
+    let choices = TagParser::default()
+        .disambiguate(&packages, "v1/v1.0.0")
+        .unwrap();
+    assert_eq!(choices.candidates.len(), 2);
+    assert_eq!(
+        choices.candidates[1].announcement.release,
+        AnnouncementKind::Unified("1.0.0".parse().unwrap())
+    );
+
+    // Unambiguous tags don't need a picker
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+    assert_eq!(parser.disambiguate(&packages, "axolotlsay/v1.0.0"), None);
+    assert_eq!(parser.disambiguate(&packages, "v1.0.0"), None);
+    assert_eq!(parser.disambiguate(&packages, "nope"), None);
+}
+
+#[test]
+fn parse_tag_with_warnings_oddities() {
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: "1.2.3".parse().ok(),
+        aliases: vec!["app".to_owned()],
+        ..Default::default()
+    }];
+
+    let (announcing, warnings) = parse_tag_with_warnings(&packages, "my-app-v1.2.3").unwrap();
+    assert!(announcing.warnings.is_empty());
+    assert!(warnings.is_empty());
+
+    let (announcing, warnings) = parse_tag_with_warnings(&packages, "releases/app-v1.2.3").unwrap();
+    assert!(announcing.warnings.is_empty());
+    assert_eq!(
+        warnings,
+        vec![
+            TagWarning::UnusualPrefix {
+                prefix: "releases".to_owned()
+            },
+            TagWarning::AliasUsed {
+                package_name: "my-app".to_owned(),
+                alias: "app".to_owned()
+            },
+        ]
+    );
+
+    let parser = TagParser {
+        case_insensitive_names: true,
+        separator_insensitive_names: true,
+        ..TagParser::default()
+    };
+    let (_, warnings) = parser
+        .parse_tag_with_warnings(&packages, "My_App/v1.2.3")
+        .unwrap();
+    assert_eq!(
+        warnings,
+        vec![TagWarning::InexactPackageName {
+            package_name: "my-app".to_owned(),
+            found: "My_App".to_owned()
+        }]
+    );
+}
+
+#[test]
+fn did_you_mean() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay-helper".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    assert_eq!(parser.did_you_mean(&packages, "axolotlasy-v1.0.0"), vec![0]);
+    assert_eq!(parser.did_you_mean(&packages, "othrapp/v1.0.0"), vec![2]);
+    // Known packages and unified tags don't need suggestions
+    assert_eq!(
+        parser.did_you_mean(&packages, "axolotlsay-v1.0.0"),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        parser.did_you_mean(&packages, "v1.0.0"),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        parser.did_you_mean(&packages, "nothing-like-it-v1.0.0"),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn did_you_mean_custom_similarity() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    // Only care about the first letter
+    let parser = TagParser {
+        similarity: Similarity::new(|a, b| f64::from(u8::from(a.get(..1) == b.get(..1)))),
+        ..TagParser::default()
+    };
+
+    assert_eq!(parser.did_you_mean(&packages, "axe-v1.0.0"), vec![0]);
+    assert_eq!(
+        parser.did_you_mean(&packages, "say-v1.0.0"),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn parse_typo_tolerance() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        typo_tolerance: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "axolotlasy-v1.0.0").unwrap();
+    assert_eq!(announcing.tag, "axolotlasy-v1.0.0");
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: "1.0.0".parse().unwrap()
+        }
+    );
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::TypoCorrected {
+            package_name: "axolotlsay".to_owned(),
+            found: "axolotlasy".to_owned()
+        }]
+    );
+
+    // Off by default
+    parse_tag(&packages, "axolotlasy-v1.0.0").unwrap_err();
+}
+
+#[test]
+fn disambiguate_typos() {
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "my-api".to_owned(),
+            version: "1.0.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        typo_tolerance: true,
+        ..TagParser::default()
+    };
+
+    let choices = parser.disambiguate(&packages, "my-apq-v1.0.0").unwrap();
+    let labels = choices
+        .candidates
+        .iter()
+        .map(|c| c.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec!["just `my-app` at 1.0.0", "just `my-api` at 1.0.0"]
+    );
+    assert_eq!(choices.recommended, 0);
+}
+
+#[test]
+fn parse_unified_version_policy() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "0.4.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+
+    // Unchecked by default
+    parse_tag(&packages, "v9.9.9").unwrap();
+
+    let any = TagParser {
+        unified_versions: UnifiedVersionPolicy::Any,
+        ..TagParser::default()
+    };
+    any.parse_tag(&packages, "v1.2.3").unwrap();
+    let err = any.parse_tag(&packages, "v9.9.9").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The provided announcement tag (v9.9.9) announces 9.9.9, but no package has that version (axolotlsay 1.2.3, helper 0.4.0)"
+    );
+
+    let all = TagParser {
+        unified_versions: UnifiedVersionPolicy::All,
+        ..TagParser::default()
+    };
+    let err = all.parse_tag(&packages, "v1.2.3").unwrap_err();
+    let TagError::UnifiedVersionMismatch {
+        packages: found, ..
+    } = err
+    else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[1].package_name, "helper");
+    assert_eq!(found[1].version, "0.4.0".parse().ok());
+}
+
+#[test]
+fn parse_release_please_preset() {
+    let packages = vec![
+        Package {
+            name: "@my-org/my-app".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "0.4.0".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::release_please();
+
+    let (announcing, warnings) = parser
+        .parse_tag_with_warnings(&packages, "my-app-v1.2.3")
+        .unwrap();
+    assert_eq!(warnings, vec![]);
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: "1.2.3".parse().unwrap()
+        }
+    );
+    let announcing = parser.parse_tag(&packages, "helper-v0.4.0").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Dash));
+    assert_eq!(
+        parser.format_tag(packages.first(), &"1.3.0".parse().unwrap()),
+        "my-app-v1.3.0"
+    );
+
+    // release-please always uses a v
+    let err = parser.parse_tag(&packages, "helper-0.4.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::MissingVPrefix);
+}
+
+#[test]
+fn parse_release_plz_preset() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser::release_plz();
+
+    let announcing = parser.parse_tag(&packages, "v1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("1.2.3".parse().unwrap())
+    );
+    parser.parse_tag(&packages, "axolotlsay-v1.2.3").unwrap();
+    assert_eq!(
+        parser.format_tag(packages.first(), &"1.2.4".parse().unwrap()),
+        "axolotlsay-v1.2.4"
+    );
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize_announcement() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let announcing = parse_tag(&packages, "axolotlsay-v1.2.3").unwrap();
+    let json = serde_json::to_value(&announcing).unwrap();
+    assert_eq!(
+        json["release"],
+        serde_json::json!({ "Package": { "idx": 0, "version": "1.2.3" } })
+    );
+    assert_eq!(json["format"], "Dash");
+    assert_eq!(json["style"], "PackageDash");
+}
+
+#[test]
+#[cfg(feature = "schema")]
+fn announcement_schema_matches_serialization() {
+    let schema = serde_json::to_value(crate::schema::announcement_schema()).unwrap();
+    let properties = schema["properties"].as_object().unwrap();
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let announcing = parse_tag(&packages, "axolotlsay-v1.2.3").unwrap();
+    let json = serde_json::to_value(&announcing).unwrap();
+    for field in json.as_object().unwrap().keys() {
+        assert!(
+            properties.contains_key(field),
+            "{field} isn't in the schema"
+        );
+    }
+    assert_eq!(schema["title"], "PartialAnnouncementTag");
+}
+
+#[test]
+fn parse_github_actions_moving_aliases() {
+    let packages = vec![Package {
+        name: "setup-axolotl".to_owned(),
+        version: "1.4.2".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser::github_actions();
+
+    let announcing = parser.parse_tag(&packages, "v1").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Channel {
+            idx: None,
+            channel: "v1".to_owned()
+        }
+    );
+    assert_eq!(
+        announcing.moving_alias(),
+        Some(MovingAlias {
+            major: 1,
+            minor: None
+        })
+    );
+    let announcing = parser.parse_tag(&packages, "setup-axolotl-v1.4").unwrap();
+    assert_eq!(
+        announcing.moving_alias(),
+        Some(MovingAlias {
+            major: 1,
+            minor: Some(4)
+        })
+    );
+    assert_eq!(announcing.selected_packages(&packages), vec![0]);
+
+    let announcing = parser.parse_tag(&packages, "v1.4.2").unwrap();
+    assert_eq!(announcing.moving_alias(), None);
+    assert_eq!(
+        parser.moving_alias_tags(&packages, &announcing),
+        vec!["v1", "v1.4"]
+    );
+    let announcing = parser.parse_tag(&packages, "setup-axolotl-v1.4.2").unwrap();
+    assert_eq!(
+        parser.moving_alias_tags(&packages, &announcing),
+        vec!["setup-axolotl-v1", "setup-axolotl-v1.4"]
+    );
+
+    // Prereleases don't move anything
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Ignore,
+        ..parser
+    };
+    let announcing = parser.parse_tag(&packages, "v2.0.0-rc.1").unwrap();
+    assert_eq!(
+        parser.moving_alias_tags(&packages, &announcing),
+        Vec::<String>::new()
+    );
+
+    // Without the preset these are just bad versions
+    parse_tag(&packages, "v1").unwrap_err();
+    // And leading zeros aren't aliases
+    TagParser::github_actions()
+        .parse_tag(&packages, "v01")
+        .unwrap_err();
+}
+
+#[test]
+fn resolve_moving_aliases() {
+    let packages = vec![Package {
+        name: "setup-axolotl".to_owned(),
+        version: "1.4.2".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        resolve_channels: true,
+        ..TagParser::github_actions()
+    };
+    let announcing = parser.parse_tag(&packages, "v1").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified("1.4.2".parse().unwrap())
+    );
+    // v2 doesn't cover the workspace's version, so it stays a moving alias
+    let announcing = parser.parse_tag(&packages, "v2").unwrap();
+    assert_eq!(
+        announcing.moving_alias(),
+        Some(MovingAlias {
+            major: 2,
+            minor: None
+        })
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+proptest::proptest! {
+    #[test]
+    fn fuzz_valid_tags_parse(
+        (packages, tag) in crate::fuzz::packages().prop_flat_map(|packages| {
+            (proptest::strategy::Just(packages.clone()), crate::fuzz::valid_tags(packages))
+        })
+    ) {
+        let announcing = parse_tag(&packages, &tag).unwrap();
+        proptest::prop_assert_eq!(announcing.tag, tag);
+    }
+
+    #[test]
+    fn fuzz_near_valid_tags_dont_panic(
+        (packages, tag) in crate::fuzz::packages().prop_flat_map(|packages| {
+            (proptest::strategy::Just(packages.clone()), crate::fuzz::near_valid_tags(packages))
+        })
+    ) {
+        let _ = TagParser::default().parse_tag_with_warnings(&packages, &tag);
+        let _ = TagParser::default().disambiguate(&packages, &tag);
+    }
+
+    #[test]
+    fn fuzz_announcing_tags_are_relevant(
+        (packages, tag) in crate::fuzz::packages().prop_flat_map(|packages| {
+            (proptest::strategy::Just(packages.clone()), crate::fuzz::near_valid_tags(packages))
+        })
+    ) {
+        if let Ok(announcing) = parse_tag(&packages, &tag) {
+            if !announcing.selected_packages(&packages).is_empty() {
+                proptest::prop_assert!(crate::is_relevant_tag(&packages, &tag));
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn render_tag_recipes() {
+    use crate::fuzz::{TagMutation, TagRecipe};
+
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let recipe = TagRecipe {
+        package: Some(0),
+        format: TagFormat::Dash,
+        v_prefix: true,
+        prefix: false,
+        fallback_version: (0, 1, 0, None),
+        mutation: None,
+    };
+    assert_eq!(recipe.render(&packages), "axolotlsay-v1.2.3");
+    let mutated = |mutation| {
+        TagRecipe {
+            mutation: Some(mutation),
+            ..recipe.clone()
+        }
+        .render(&packages)
+    };
+    assert_eq!(mutated(TagMutation::ToggleV), "axolotlsay-1.2.3");
+    assert_eq!(mutated(TagMutation::DoubleV), "axolotlsay-vv1.2.3");
+    assert_eq!(mutated(TagMutation::TypoName), "axolotlsaxy-v1.2.3");
+    assert_eq!(mutated(TagMutation::WrongSeparator), "axolotlsay_v1.2.3");
+    assert_eq!(mutated(TagMutation::TruncateVersion), "axolotlsay-v1.2");
+    assert_eq!(mutated(TagMutation::ExtraComponent), "axolotlsay-v1.2.3.4");
+    assert_eq!(mutated(TagMutation::LeadingZero), "axolotlsay-v1.02.3");
+    assert_eq!(mutated(TagMutation::Uppercase), "AXOLOTLSAY-V1.2.3");
+
+    let unified = TagRecipe {
+        package: None,
+        prefix: true,
+        ..recipe
+    };
+    assert_eq!(unified.render(&packages), "releases/v1.2.3");
+}
+
+#[test]
+fn parse_tag_in_snapshot() {
+    let mut packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+    let snapshot = WorkspaceSnapshot::new(&packages);
+    let before = parser.parse_tag(&packages, "v1.2.3").unwrap();
+
+    // The workspace moves on, but the snapshot still reads the tag the same way
+    packages[0].version = "2.0.0".parse().ok();
+    packages.remove(1);
+    assert_ne!(
+        parser
+            .parse_tag(&packages, "v1.2.3")
+            .unwrap()
+            .selected_packages(&packages),
+        vec![0, 1]
+    );
+    let replayed = parser.parse_tag_in_snapshot(&snapshot, "v1.2.3").unwrap();
+    assert_eq!(replayed, before);
+    assert_eq!(replayed.selected_packages(&snapshot.packages), vec![0, 1]);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize_workspace_snapshot() {
+    let snapshot = WorkspaceSnapshot::new(&[Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        aliases: vec!["axosay".to_owned()],
+        ..Default::default()
+    }]);
+    let blob = serde_json::to_string(&snapshot).unwrap();
+    let restored: WorkspaceSnapshot = serde_json::from_str(&blob).unwrap();
+    assert_eq!(restored, snapshot);
+
+    // Fields that didn't exist when a snapshot was made get their defaults
+    let old: WorkspaceSnapshot =
+        serde_json::from_str(r#"{"packages": [{"name": "axolotlsay", "version": "1.2.3"}]}"#)
+            .unwrap();
+    assert_eq!(old.packages[0].aliases, Vec::<String>::new());
+    assert!(!old.packages[0].excluded);
+}
+
+#[test]
+fn verify_roundtrip() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            path: Some("crates/axolotlsay".to_owned()),
+            ..Default::default()
+        },
+        Package {
+            name: "axolotlsay-helper".to_owned(),
+            version: "1.2.3-rc.1".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+    for format in [TagFormat::Dash, TagFormat::Slash, TagFormat::Path] {
+        parser.verify_roundtrip(&packages, format).unwrap();
+    }
+
+    // A version-like package name can't be told apart from a unified release
+    let packages = vec![Package {
+        name: "v1".to_owned(),
+        version: "1.0.0".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        require_unambiguous_package_names: true,
+        ..TagParser::default()
+    };
+    let failures = parser
+        .verify_roundtrip(&packages, TagFormat::Slash)
+        .unwrap_err();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].tag, "v1/v1.0.0");
+    assert_eq!(
+        failures[0].parsed.as_ref().unwrap_err().kind(),
+        TagErrorKind::AmbiguousPackageName
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+proptest::proptest! {
+    #[test]
+    fn fuzz_roundtrip(packages in crate::fuzz::packages()) {
+        for format in [TagFormat::Dash, TagFormat::Slash, TagFormat::Path] {
+            let result = TagParser::default().verify_roundtrip(&packages, format);
+            proptest::prop_assert!(result.is_ok(), "{format:?}: {result:?}");
+        }
+    }
+}
+
+#[test]
+fn share_parser_across_threads() {
+    let packages = std::sync::Arc::new(vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }]);
+    let parser = std::sync::Arc::new(TagParser {
+        typo_tolerance: true,
+        ..TagParser::default()
+    });
+    let workers = ["axolotlsay-v1.2.3", "axolotlsya-v1.2.3", "v1.2.3"].map(|tag| {
+        let (parser, packages) = (parser.clone(), packages.clone());
+        std::thread::spawn(move || parser.parse_tag(&packages, tag).unwrap().release)
+    });
+    for worker in workers {
+        assert_ne!(worker.join().unwrap(), AnnouncementKind::None);
+    }
+}
+
+#[test]
+fn render_announcement_titles() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+    ];
+    let announcing = parse_tag(&packages, "axolotlsay-v1.2.3").unwrap();
+    assert_eq!(
+        render_announcement_title(&announcing, &packages),
+        "axolotlsay 1.2.3"
+    );
+    let announcing = parse_tag(&packages, "v1.2.3").unwrap();
+    assert_eq!(
+        render_announcement_title(&announcing, &packages),
+        "Release 1.2.3 (2 packages)"
+    );
+    let announcing = parse_tag(&packages, "latest").unwrap();
+    assert_eq!(
+        render_announcement_title(&announcing, &packages),
+        "Release latest (2 packages)"
+    );
+
+    let templates = AnnouncementTemplates {
+        unified_title: "{tag}: {packages} at {version}".to_owned(),
+        body_line: "- `{package}` {version}".to_owned(),
+        ..AnnouncementTemplates::default()
+    };
+    let announcing = parse_tag(&packages[..1], "v1.2.3").unwrap();
+    assert_eq!(
+        templates.render_title(&announcing, &packages[..1]),
+        "v1.2.3: 1 package at 1.2.3"
+    );
+    let announcing = parse_tag(&packages, "v1.2.3").unwrap();
+    assert_eq!(
+        templates.render_body(&announcing, &packages),
+        "- `axolotlsay` 1.2.3\n- `helper` 1.2.3"
+    );
+
+    // What gets filled in isn't filled in again, and unknown placeholders are left alone
+    let packages = vec![Package {
+        name: "{packages}".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let announcing = PartialAnnouncementTag {
+        tag: "{version}-v1.2.3".to_owned(),
+        release: AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(1, 2, 3),
+        },
+        ..PartialAnnouncementTag::default()
+    };
+    let templates = AnnouncementTemplates {
+        package_title: "{{tag}} {package} {version} {other}".to_owned(),
+        ..AnnouncementTemplates::default()
+    };
+    assert_eq!(
+        templates.render_title(&announcing, &packages),
+        "{{version}-v1.2.3} {packages} 1.2.3 {other}"
+    );
+}
+
+#[test]
+fn parse_tag_refs_and_urls() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+
+    let announcing = parser
+        .parse_tag_input(&packages, "refs/tags/axolotlsay-v1.2.3")
+        .unwrap();
+    assert_eq!(announcing.tag, "axolotlsay-v1.2.3");
+    assert_eq!(announcing.origin, TagOrigin::Ref);
+
+    let announcing = parser
+        .parse_tag_input(
+            &packages,
+            "https://github.com/axodotdev/axolotlsay/releases/tag/axolotlsay-v1.2.3",
+        )
+        .unwrap();
+    assert_eq!(announcing.tag, "axolotlsay-v1.2.3");
+    assert_eq!(
+        announcing.origin,
+        TagOrigin::Url {
+            host: "github.com".to_owned(),
+            repository: "axodotdev/axolotlsay".to_owned()
+        }
+    );
+
+    let announcing = parser
+        .parse_tag_input(
+            &packages,
+            "https://gitlab.com/axo/tools/axolotlsay/-/tags/axolotlsay%2Fv1.2.3?ref=feed#top",
+        )
+        .unwrap();
+    assert_eq!(announcing.tag, "axolotlsay/v1.2.3");
+    assert_eq!(
+        announcing.origin,
+        TagOrigin::Url {
+            host: "gitlab.com".to_owned(),
+            repository: "axo/tools/axolotlsay".to_owned()
+        }
+    );
+
+    let announcing = parser.parse_tag_input(&packages, "v1.2.3").unwrap();
+    assert_eq!(announcing.origin, TagOrigin::Tag);
+
+    let err = parser
+        .parse_tag_input(&packages, "https://github.com/axodotdev/axolotlsay/pulls")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::UnrecognizedTagUrl);
+    assert_eq!(
+        extract_tag("https://github.com/o/r/releases/tag/v1.2.3%E")
+            .unwrap_err()
+            .kind(),
+        TagErrorKind::UnrecognizedTagUrl
+    );
+    // from_str_radix would take the sign
+    assert_eq!(
+        extract_tag("https://github.com/o/r/releases/tag/v1.2.3%+1")
+            .unwrap_err()
+            .kind(),
+        TagErrorKind::UnrecognizedTagUrl
+    );
+}
+
+#[test]
+fn parse_per_package_version_schemes() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: "1.2.3".parse().ok(),
+            ..Default::default()
+        },
+        Package {
+            name: "axo-calendar".to_owned(),
+            version: "2024.1.5".parse().ok(),
+            version_scheme: VersionScheme::CalVer,
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    let (announcing, warnings) = parser
+        .parse_tag_with_warnings(&packages, "axo-calendar-v2024.01.05")
+        .unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 1,
+            version: "2024.1.5".parse().unwrap()
+        }
+    );
+    assert_eq!(warnings, vec![]);
+
+    // The semver package doesn't get that leeway
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.02.3")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+    // And neither do unified releases, since the packages disagree on a scheme
+    parser.parse_tag(&packages, "v2024.01.05").unwrap_err();
+
+    // Extra components are still worth mentioning
+    let packages = vec![Package {
+        name: "axo-calendar".to_owned(),
+        version: "24.4.1".parse().ok(),
+        version_scheme: VersionScheme::CalVer,
+        ..Default::default()
+    }];
+    let (_, warnings) = parser
+        .parse_tag_with_warnings(&packages, "v24.04.1.2")
+        .unwrap();
+    assert!(matches!(
+        &warnings[..],
+        [TagWarning::LenientVersion { fixups, .. }]
+            if fixups == &vec![VersionFixup::ExtraComponents { dropped: "2".to_owned() }]
+    ));
+
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.0".parse().ok(),
+        version_scheme: VersionScheme::Lenient,
+        ..Default::default()
+    }];
+    let (_, warnings) = parser
+        .parse_tag_with_warnings(&packages, "axolotlsay-v1.2")
+        .unwrap();
+    assert!(matches!(&warnings[..], [TagWarning::LenientVersion { .. }]));
+}
+
+#[test]
+fn parse_directional_version_mismatch() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        directional_version_mismatch: true,
+        ..TagParser::default()
+    };
+
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.2.2")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::StaleTagVersion);
+    assert_eq!(
+        err.fix_its()[0].apply("axolotlsay-v1.2.2"),
+        "axolotlsay-v1.2.3"
+    );
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.3.0")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionAhead);
+    assert_eq!(err.fix_its(), vec![]);
+    assert_eq!(
+        miette::Diagnostic::help(&err).unwrap().to_string(),
+        "bump axolotlsay to 1.3.0 before tagging"
+    );
+
+    // Build metadata isn't older or newer, just different
+    let err = parser
+        .parse_tag(&packages, "axolotlsay-v1.2.3+build.1")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::ContradictoryTagVersion);
+
+    // Without the option, it's all the same error
+    let err = parse_tag(&packages, "axolotlsay-v1.2.2").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::ContradictoryTagVersion);
+}
+
+#[test]
+fn sort_announcements_newest_first() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        version_match: VersionMatchPolicy::Ignore,
+        ..TagParser::default()
+    };
+    let tags = [
+        "helper-v0.1.0",
+        "axolotlsay-v1.0.0-rc.1",
+        "latest",
+        "axolotlsay-v1.0.0",
+        "v2.0.0",
+        "axolotlsay-v0.9.0",
+        "axolotlsay-v1.0.0-rc.2",
+        "v1.0.0",
+    ];
+    let mut announcements = tags
+        .iter()
+        .map(|tag| parser.parse_tag(&packages, tag).unwrap())
+        .collect::<Vec<_>>();
+    parser.sort_newest_first(&mut announcements);
+    let sorted = announcements
+        .iter()
+        .map(|a| a.tag.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sorted,
+        vec![
+            "v2.0.0",
+            "v1.0.0",
+            "axolotlsay-v1.0.0",
+            "axolotlsay-v1.0.0-rc.2",
+            "axolotlsay-v1.0.0-rc.1",
+            "axolotlsay-v0.9.0",
+            "helper-v0.1.0",
+            "latest",
+        ]
+    );
+
+    let previous = |tag| {
+        let announcing = parser.parse_tag(&packages, tag).unwrap();
+        parser
+            .previous_announcement(&announcing, &announcements)
+            .map(|a| a.tag.clone())
+    };
+    assert_eq!(
+        previous("axolotlsay-v1.0.0"),
+        Some("axolotlsay-v1.0.0-rc.2".to_owned())
+    );
+    assert_eq!(
+        previous("axolotlsay-v1.1.0"),
+        Some("axolotlsay-v1.0.0".to_owned())
+    );
+    assert_eq!(previous("v2.0.0"), Some("v1.0.0".to_owned()));
+    assert_eq!(previous("helper-v0.1.0"), None);
+    assert_eq!(previous("latest"), None);
+}
+
+/// Reads changesets-style `my-app@1.2.3` tags
+#[derive(Debug)]
+struct AtSignMatcher;
+
+impl TagMatcher for AtSignMatcher {
+    fn match_tag(&self, cx: &MatchContext<'_>, tag: &str) -> Option<TagMatch> {
+        let found = cx.strip_package_prefix(tag)?;
+        let version = found.rest.strip_prefix('@')?;
+        let name_end = tag.len() - found.rest.len();
+        Some(TagMatch {
+            prefix: 0..0,
+            package: Some(PackageSpan {
+                idx: found.idx,
+                alias: found.alias,
+                range: 0..name_end,
+            }),
+            separator: name_end..name_end + 1,
+            version: tag.len() - version.len()..tag.len(),
+            confidence: found.confidence,
+            format: None,
+            style: TagStyle::Custom {
+                name: "changesets".to_owned(),
+            },
+        })
+    }
+}
+
+#[test]
+fn custom_matcher() {
+    let packages = vec![
+        Package {
+            name: "@axo/app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        matchers: vec![std::sync::Arc::new(AtSignMatcher)],
+        case_insensitive_names: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "@axo/app@1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(1, 2, 3)
+        }
+    );
+    assert_eq!(
+        announcing.style,
+        Some(TagStyle::Custom {
+            name: "changesets".to_owned()
+        })
+    );
+    assert_eq!(announcing.format, None);
+
+    // Everything after the matcher is shared with the built-in formats
+    let announcing = parser.parse_tag(&packages, "Helper@latest").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Channel {
+            idx: Some(1),
+            channel: "latest".to_owned()
+        }
+    );
+    let err = parser.parse_tag(&packages, "helper@0.2.0").unwrap_err();
+    assert!(matches!(err, TagError::ContradictoryTagVersion { .. }));
+    let (_, warnings) = parser
+        .parse_tag_with_warnings(&packages, "HELPER@0.1.0")
+        .unwrap();
+    assert!(warnings.contains(&TagWarning::InexactPackageName {
+        package_name: "helper".to_owned(),
+        found: "HELPER".to_owned(),
+    }));
+
+    // The built-in formats still work
+    let announcing = parser.parse_tag(&packages, "helper-v0.1.0").unwrap();
+    assert_eq!(announcing.format, Some(TagFormat::Dash));
+}
+
+/// Returns the same (broken) match for every tag
+#[derive(Debug)]
+struct BrokenMatcher(TagMatch);
+
+impl TagMatcher for BrokenMatcher {
+    fn match_tag(&self, _: &MatchContext<'_>, _: &str) -> Option<TagMatch> {
+        Some(self.0.clone())
+    }
+}
+
+#[test]
+fn broken_matchers_are_ignored() {
+    use crate::explain::AttemptOutcome;
+    use crate::grammar::PipelineStage;
+
+    let packages = vec![Package {
+        name: "åxolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let tag = "åxolotlsay-v1.0.0";
+    let valid = TagMatch {
+        prefix: 0..0,
+        package: Some(PackageSpan {
+            idx: 0,
+            alias: None,
+            range: 0..11,
+        }),
+        separator: 11..12,
+        version: 12..tag.len(),
+        confidence: Confidence::Exact,
+        format: None,
+        style: TagStyle::Custom {
+            name: "broken".to_owned(),
+        },
+    };
+    let span = valid.package.clone().unwrap();
+    let broken = [
+        TagMatch {
+            package: Some(PackageSpan {
+                idx: 1,
+                ..span.clone()
+            }),
+            ..valid.clone()
+        },
+        TagMatch {
+            package: Some(PackageSpan {
+                alias: Some(0),
+                ..span.clone()
+            }),
+            ..valid.clone()
+        },
+        TagMatch {
+            package: Some(PackageSpan {
+                range: 0..1,
+                ..span.clone()
+            }),
+            ..valid.clone()
+        },
+        TagMatch {
+            version: 12..100,
+            ..valid.clone()
+        },
+        TagMatch {
+            prefix: 100..100,
+            ..valid.clone()
+        },
+    ];
+
+    for found in broken {
+        let parser = TagParser {
+            matchers: vec![std::sync::Arc::new(BrokenMatcher(found.clone()))],
+            ..TagParser::default()
+        };
+        // The built-in formats read the tag instead
+        let announcing = parser.parse_tag(&packages, tag).unwrap();
+        assert_eq!(announcing.format, Some(TagFormat::Dash), "{found:?}");
+        assert!(parser.is_relevant_tag(&packages, tag));
+        assert!(!parser.tag_matches(&packages, tag).contains(&found));
+        let (_, trace) = parser.parse_tag_traced(&packages, tag);
+        assert_eq!(trace.attempts[0].stage, PipelineStage::Matcher(0));
+        assert_eq!(trace.attempts[0].outcome, AttemptOutcome::NoMatch);
+    }
+
+    // The same match is fine when it follows the rules
+    let parser = TagParser {
+        matchers: vec![std::sync::Arc::new(BrokenMatcher(valid))],
+        ..TagParser::default()
+    };
+    let announcing = parser.parse_tag(&packages, tag).unwrap();
+    assert_eq!(announcing.format, None);
+}
+
+#[test]
+fn tag_matches_spans() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        path: Some("crates/axolotlsay".to_owned()),
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+    let tag = "releases/axolotlsay-v1.0.0";
+    let matches = parser.tag_matches(&packages, tag);
+    assert_eq!(matches.len(), 2);
+    let dash = &matches[0];
+    assert_eq!(&tag[dash.prefix.clone()], "releases");
+    assert_eq!(
+        dash.package,
+        Some(PackageSpan {
+            idx: 0,
+            alias: None,
+            range: 9..19
+        })
+    );
+    assert_eq!(&tag[dash.separator.clone()], "-");
+    assert_eq!(&tag[dash.version.clone()], "v1.0.0");
+    assert_eq!(dash.confidence, Confidence::Exact);
+    assert_eq!(dash.format, Some(TagFormat::Dash));
+    // The unified reading always comes last, even though it won't parse
+    let unified = &matches[1];
+    assert_eq!(unified.package, None);
+    assert_eq!(&tag[unified.version.clone()], "axolotlsay-v1.0.0");
+
+    // Go-style major-version markers end up in the separator
+    let tag = "crates/axolotlsay/v2/v2.0.0";
+    let matches = parser.tag_matches(&packages, tag);
+    let slash = matches
+        .iter()
+        .find(|m| m.format == Some(TagFormat::Slash))
+        .unwrap();
+    assert_eq!(&tag[slash.prefix.clone()], "crates");
+    assert_eq!(
+        &tag[slash.package.as_ref().unwrap().range.clone()],
+        "axolotlsay"
+    );
+    assert_eq!(&tag[slash.separator.clone()], "/v2/");
+    assert_eq!(&tag[slash.version.clone()], "v2.0.0");
+}
+
+#[test]
+fn tag_conflicts() {
+    let existing = ["v1.0.0", "My-App-v1.0.0", "releases/v2.0.0", "v1.0.0"];
+
+    assert_eq!(check_tag_conflicts("v1.1.0", existing), vec![]);
+    assert_eq!(
+        check_tag_conflicts("v1.0.0", existing),
+        vec![TagConflict::Exists]
+    );
+    assert_eq!(
+        check_tag_conflicts("my-app-v1.0.0", existing),
+        vec![TagConflict::DifferentCase {
+            existing_tag: "My-App-v1.0.0".to_owned()
+        }]
+    );
+    assert_eq!(
+        check_tag_conflicts("releases", existing),
+        vec![TagConflict::PathCollision {
+            existing_tag: "releases/v2.0.0".to_owned()
+        }]
+    );
+    assert_eq!(
+        check_tag_conflicts("v1.0.0/hotfix", existing),
+        vec![TagConflict::PathCollision {
+            existing_tag: "v1.0.0".to_owned()
+        }]
+    );
+    assert_eq!(
+        check_tag_conflicts("main", Vec::<String>::new()),
+        vec![TagConflict::BranchLike]
+    );
+
+    // The repository's actual branches replace the common names, but not git's own refs
+    let branches = ["refs/heads/main", "stable"];
+    let conflicts = |tag| check_tag_conflicts_with_branches(tag, Vec::<String>::new(), branches);
+    assert_eq!(conflicts("stable"), vec![TagConflict::BranchLike]);
+    assert_eq!(conflicts("main"), vec![TagConflict::BranchLike]);
+    assert_eq!(conflicts("develop"), vec![]);
+    assert_eq!(conflicts("HEAD"), vec![TagConflict::BranchLike]);
+}
+
+#[test]
+fn parse_tag_resolved() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    // The result outlives the packages
+    let resolved = {
+        let packages = packages.clone();
+        parser
+            .parse_tag_resolved(&packages, "helper-v0.1.0")
+            .unwrap()
+    };
+    assert_eq!(resolved.package_name.as_deref(), Some("helper"));
+    assert_eq!(resolved.version, Some(Version::new(0, 1, 0)));
+    assert_eq!(resolved.announcing.tag, "helper-v0.1.0");
+
+    let ResolvedAnnouncement {
+        package_name,
+        version,
+        ..
+    } = parser
+        .parse_tag_resolved(&packages, "helper-latest")
+        .unwrap();
+    assert_eq!(package_name.as_deref(), Some("helper"));
+    assert_eq!(version, None);
+
+    let resolved = parser.parse_tag_resolved(&packages, "v2.0.0").unwrap();
+    assert_eq!(resolved.package_name, None);
+    assert_eq!(resolved.version, Some(Version::new(2, 0, 0)));
+}
+
+#[test]
+fn empty_workspace_policies() {
+    // By default only unified releases work
+    let announcing = parse_tag(&[], "v1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 2, 3))
+    );
+    assert!(parse_tag(&[], "my-app-v1.2.3").is_err());
+
+    let parser = TagParser {
+        empty_workspace: EmptyWorkspacePolicy::Error,
+        ..TagParser::default()
+    };
+    let err = parser.parse_tag(&[], "v1.2.3").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::NoPackages);
+
+    let parser = TagParser {
+        empty_workspace: EmptyWorkspacePolicy::Unresolved,
+        ..TagParser::default()
+    };
+    let announcing = parser
+        .parse_tag(&[], "releases/my-app-v1.2.3-rc.1")
+        .unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::UnresolvedPackage {
+            name: "my-app".to_owned(),
+            version: "1.2.3-rc.1".parse().unwrap(),
+        }
+    );
+    assert!(announcing.prerelease);
+    assert_eq!(announcing.format, Some(TagFormat::Dash));
+    assert_eq!(
+        announcing.describe(&[]),
+        "announcing package `my-app` at 1.2.3-rc.1 (prerelease: yes), matched format `{package}-v{version}`"
+    );
+    // Slash-style names look just like prefixes, so those stay unified
+    let announcing = parser.parse_tag(&[], "my-app/v1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 2, 3))
+    );
+}
+
+#[test]
+fn git_forbidden_tags() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let reason = |tag: &str| match parse_tag(&packages, tag).unwrap_err() {
+        TagError::InvalidGitTag {
+            position, reason, ..
+        } => (position, reason),
+        err => panic!("unexpected error for {tag:?}: {err}"),
+    };
+
+    assert_eq!(
+        reason("axolotlsay-v1.0.0 "),
+        (17, "it contains a space".to_owned())
+    );
+    assert_eq!(
+        reason("axolotlsay-v1.0.0^"),
+        (17, "it contains `^`".to_owned())
+    );
+    assert_eq!(reason("v1..0"), (2, "it contains `..`".to_owned()));
+    assert_eq!(reason("v1.0.0."), (6, "it ends with `.`".to_owned()));
+    assert_eq!(
+        reason("releases/.v1.0.0"),
+        (9, "a slash-delimited part of it starts with `.`".to_owned())
+    );
+    assert_eq!(
+        reason("v1.0.0.lock"),
+        (
+            6,
+            "a slash-delimited part of it ends with `.lock`".to_owned()
+        )
+    );
+    assert_eq!(reason(""), (0, "it's empty".to_owned()));
+
+    assert!(check_git_tag("releases/axolotlsay-v1.0.0+build.1").is_ok());
+    // Non-ASCII is fine by git
+    assert!(check_git_tag("café-v1.0.0").is_ok());
+}
+
+#[cfg(feature = "unicode")]
+#[test]
+fn normalize_unicode() {
+    // "café" with a combining accent
+    let packages = vec![Package {
+        name: "cafe\u{301}".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let precomposed = "caf\u{e9}-v1.0.0";
+    assert!(TagParser::default()
+        .parse_tag(&packages, precomposed)
+        .is_err());
+
+    let parser = TagParser {
+        normalize_unicode: true,
+        ..TagParser::default()
+    };
+    let announcing = parser.parse_tag(&packages, precomposed).unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(1, 0, 0)
+        }
+    );
+    assert_eq!(announcing.tag, precomposed);
+}
+
+#[cfg(feature = "corpus")]
+#[test]
+fn tag_corpus() {
+    let dir = std::env::temp_dir().join(format!("axotag-corpus-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let tags = dir.join("tags.txt");
+    let manifest = dir.join("packages.json");
+    std::fs::write(
+        &tags,
+        "axolotlsay-v1.0.0\n\nv1.0.0\nbad\naxolotlsay-v1.0.0\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &manifest,
+        r#"{"packages": [{"name": "axolotlsay", "version": "1.0.0"}]}"#,
+    )
+    .unwrap();
+    let corpus = TagCorpus::read(&tags, &manifest).unwrap();
+    #[cfg(feature = "tokio")]
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let read = runtime.block_on(TagCorpus::read_async(&tags, &manifest));
+        assert_eq!(read.unwrap(), corpus);
+        let missing = runtime.block_on(TagCorpus::read_async(dir.join("nope"), &manifest));
+        assert!(matches!(
+            missing,
+            Err(crate::corpus::CorpusError::Io { .. })
+        ));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(corpus.packages[0].name, "axolotlsay");
+    assert_eq!(corpus.tags.len(), 4);
+
+    let baseline = TagParser::default().run_corpus(&corpus);
+    assert_eq!(
+        baseline.to_string(),
+        "3 tags: 2 parsed, 1 failed (1 TagVersionParse)"
+    );
+    // Baselines survive a round trip through JSON
+    let json = serde_json::to_string(&baseline).unwrap();
+    let baseline = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        TagParser::default().run_corpus(&corpus).compare(&baseline),
+        vec![]
+    );
+
+    let parser = TagParser {
+        formats: vec![TagFormat::Slash],
+        ..TagParser::default()
+    };
+    // Unified tags report the (new) first format as well
+    let changes = parser.run_corpus(&corpus).compare(&baseline);
+    let changed = changes.iter().map(|c| c.tag.as_str()).collect::<Vec<_>>();
+    assert_eq!(changed, vec!["axolotlsay-v1.0.0", "v1.0.0"]);
+    assert!(matches!(changes[0].before, Some(CorpusOutcome::Parsed(_))));
+    assert!(matches!(
+        changes[0].after,
+        Some(CorpusOutcome::Failed { .. })
+    ));
+}
+
+#[test]
+fn require_package_component() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        require_package_component: true,
+        ..TagParser::default()
+    };
+
+    let err = parser.parse_tag(&packages, "v1.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::MissingPackageComponent);
+    assert_eq!(
+        err.fix_its()[0].apply("v1.0.0"),
+        "axolotlsay-v1.0.0".to_owned()
+    );
+    assert!(parser.parse_tag(&packages, "helper-v1.0.0").is_ok());
+    assert!(parser.parse_tag(&packages, "latest").is_ok());
+
+    // One releasable package can't be ambiguous
+    let packages = vec![
+        packages[0].clone(),
+        Package {
+            excluded: true,
+            ..packages[1].clone()
+        },
+    ];
+    assert!(parser.parse_tag(&packages, "v1.0.0").is_ok());
+}
+
+#[test]
+fn allowed_prefixes() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let announcing = parse_tag(&packages, "releases/axolotlsay-v1.0.0").unwrap();
+    assert_eq!(announcing.prefix(), Some("releases"));
+    assert_eq!(parse_tag(&packages, "v1.0.0").unwrap().prefix(), None);
+
+    let parser = TagParser {
+        allowed_prefixes: Some(vec!["releases/".to_owned(), "nightly".to_owned()]),
+        ..TagParser::default()
+    };
+    let announcing = parser
+        .parse_tag(&packages, "nightly/axolotlsay-v1.0.0")
+        .unwrap();
+    assert_eq!(announcing.prefix(), Some("nightly"));
+    assert!(announcing.warnings.is_empty());
+
+    let err = parser
+        .parse_tag(&packages, "oops/axolotlsay-v1.0.0")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The provided announcement tag (oops/axolotlsay-v1.0.0) has the prefix oops/, which isn't allowed (allowed: releases/, nightly/)"
+    );
+    assert_eq!(
+        err.fix_its()[0].apply("oops/axolotlsay-v1.0.0"),
+        "axolotlsay-v1.0.0"
+    );
+
+    // No prefixes at all
+    let parser = TagParser {
+        allowed_prefixes: Some(vec![]),
+        ..TagParser::default()
+    };
+    let err = parser.parse_tag(&packages, "releases/v1.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::UnexpectedPrefix);
+    assert!(parser.parse_tag(&packages, "v1.0.0").is_ok());
+}
+
+#[test]
+fn build_number_scheme() {
+    let packages = vec![Package {
+        name: "firmware".to_owned(),
+        version: Some(Version::new(1, 2, 3)),
+        version_scheme: VersionScheme::BuildNumber,
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+
+    let announcing = parser
+        .parse_tag(&packages, "firmware-v1.2.3-build.456")
+        .unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(1, 2, 3)
+        }
+    );
+    assert_eq!(announcing.build_number, Some(456));
+    assert!(!announcing.prerelease);
+
+    // Counters can follow a real prerelease
+    let unversioned = vec![Package {
+        version: None,
+        ..packages[0].clone()
+    }];
+    let announcing = parser
+        .parse_tag(&unversioned, "firmware-v1.2.3-rc.1.build.7")
+        .unwrap();
+    assert_eq!(announcing.release.version().unwrap().pre.as_str(), "rc.1");
+    assert_eq!(announcing.build_number, Some(7));
+    assert!(announcing.prerelease);
+
+    // Only the last identifiers count, and only with this scheme
+    let announcing = parser
+        .parse_tag(&unversioned, "firmware-v1.2.3-build.7.rc.1")
+        .unwrap();
+    assert_eq!(announcing.build_number, None);
+    let semver = vec![Package {
+        version_scheme: VersionScheme::Semver,
+        ..packages[0].clone()
+    }];
+    assert!(parser
+        .parse_tag(&semver, "firmware-v1.2.3-build.456")
+        .is_err());
+
+    // Later builds of the same version are newer
+    let mut announcements = ["firmware-v1.2.3-build.9", "firmware-v1.2.3-build.10"]
+        .map(|tag| parser.parse_tag(&packages, tag).unwrap());
+    parser.sort_newest_first(&mut announcements);
+    assert_eq!(announcements[0].tag, "firmware-v1.2.3-build.10");
+    assert!(!announcements[0].same_release(&announcements[1], BuildMetadataPolicy::Exact));
+    assert!(parser
+        .check_not_regression(
+            &packages,
+            "firmware-v1.2.3-build.11",
+            ["firmware-v1.2.3-build.10"]
+        )
+        .is_ok());
+    assert!(parser
+        .check_not_regression(
+            &packages,
+            "firmware-v1.2.3-build.10",
+            ["firmware-v1.2.3-build.10"]
+        )
+        .is_err());
+}
+
+#[test]
+fn epoch_scheme() {
+    let packages = vec![Package {
+        name: "tool".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        version_scheme: VersionScheme::Epoch,
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+
+    let announcing = parser.parse_tag(&packages, "tool-v2!1.0.0").unwrap();
+    assert_eq!(announcing.epoch, Some(2));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(1, 0, 0)
+        }
+    );
+    assert_eq!(
+        parser.parse_tag(&packages, "tool-v1.0.0").unwrap().epoch,
+        None
+    );
+
+    // A higher epoch wins whatever the version
+    let packages = vec![Package {
+        version: None,
+        ..packages[0].clone()
+    }];
+    let old = parser.parse_tag(&packages, "tool-v9.0.0").unwrap();
+    let new = parser.parse_tag(&packages, "tool-v1!1.0.0").unwrap();
+    assert_eq!(
+        parser.cmp_newest_first(&new, &old),
+        std::cmp::Ordering::Less
+    );
+    assert!(parser
+        .check_not_regression(&packages, "tool-v1!1.0.0", ["tool-v9.0.0"])
+        .is_ok());
+}
+
+#[cfg(feature = "pep440")]
+#[test]
+fn pep440_versions() {
+    use crate::pep440::{Pep440Version, PreReleaseKind};
+
+    let parse = |input: &str| input.parse::<Pep440Version>().unwrap();
+    let version = parse("1.0-RC.1");
+    assert_eq!(version.pre, Some((PreReleaseKind::Rc, 1)));
+    assert_eq!(version.to_string(), "1.0rc1");
+    assert_eq!(parse("1.0-1").to_string(), "1.0.post1");
+    assert_eq!(parse("1.0.preview_2").to_string(), "1.0rc2");
+    assert_eq!(
+        parse("2!1.0a.dev+Ubuntu-1").to_string(),
+        "2!1.0a0.dev0+ubuntu.1"
+    );
+    assert!("1.0.x".parse::<Pep440Version>().is_err());
+    assert!("1.0+".parse::<Pep440Version>().is_err());
+
+    let ordered = [
+        "1.0.dev1",
+        "1.0a1",
+        "1.0a2.dev1",
+        "1.0a2",
+        "1.0b1",
+        "1.0rc1",
+        "1.0",
+        "1.0+abc",
+        "1.0+5",
+        "1.0.post1.dev1",
+        "1.0.post1",
+        "1.1",
+        "1!0.1",
+    ];
+    for pair in ordered.windows(2) {
+        assert!(parse(pair[0]) < parse(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+    assert_eq!(parse("1.2"), parse("1.2.0"));
+
+    assert_eq!(
+        parse("1.2rc1.post2.dev3+local.7").to_semver().unwrap(),
+        "1.2.0-rc.1.dev.3+post.2.local.local.7".parse().unwrap()
+    );
+    assert!(parse("1.2.3.4").to_semver().is_err());
+}
+
+#[cfg(feature = "pep440")]
+#[test]
+fn pep440_scheme() {
+    let packages = vec![Package {
+        name: "pytool".to_owned(),
+        version: Some("1.2.3+post.1".parse().unwrap()),
+        version_scheme: VersionScheme::Pep440,
+        ..Default::default()
+    }];
+    let parser = TagParser::default();
+
+    let announcing = parser.parse_tag(&packages, "pytool-v1.2.3.post1").unwrap();
+    assert_eq!(
+        announcing.pep440.as_ref().map(ToString::to_string),
+        Some("1.2.3.post1".to_owned())
+    );
+    assert!(!announcing.prerelease);
+    assert_eq!(
+        announcing.release.version(),
+        Some(&"1.2.3+post.1".parse().unwrap())
+    );
+    let err = parser.parse_tag(&packages, "pytool-v1.2.3").unwrap_err();
+    assert!(matches!(err, TagError::ContradictoryTagVersion { .. }));
+    let err = parser.parse_tag(&packages, "pytool-v1.2.3.4").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::Pep440Parse);
+
+    // Releases are ordered like PEP 440 orders them
+    let packages = vec![Package {
+        version: None,
+        ..packages[0].clone()
+    }];
+    let announcing = parser.parse_tag(&packages, "pytool-1.2rc1").unwrap();
+    assert!(announcing.prerelease);
+    let post = parser.parse_tag(&packages, "pytool-1.2.post1").unwrap();
+    let dev = parser.parse_tag(&packages, "pytool-1.2.dev1").unwrap();
+    assert_eq!(
+        parser.cmp_newest_first(&post, &dev),
+        std::cmp::Ordering::Less
+    );
+    assert!(parser
+        .check_not_regression(&packages, "pytool-1.2.dev1", ["pytool-1.2.post1"])
+        .is_err());
+}
+
+#[test]
+fn release_package_idx() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        channels: vec!["latest".to_owned()],
+        ..TagParser::default()
+    };
+    let package_idx = |tag: &str| {
+        parser
+            .parse_tag(&packages, tag)
+            .unwrap()
+            .release
+            .package_idx()
+    };
+    assert_eq!(package_idx("otherapp-v1.0.0"), Some(1));
+    assert_eq!(package_idx("axolotlsay-latest"), Some(0));
+    assert_eq!(package_idx("latest"), None);
+    assert_eq!(package_idx("v1.0.0"), None);
+}
+
+#[test]
+fn infer_tags() {
+    use crate::infer::InferScope;
+
+    let mut packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "docs".to_owned(),
+            version: None,
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    let announcing = parser.infer_tag(&packages, InferScope::All).unwrap();
+    assert_eq!(announcing.tag, "v1.2.3");
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 2, 3))
+    );
+    let announcing = parser.infer_tag(&packages, InferScope::Package(1)).unwrap();
+    assert_eq!(announcing.tag, "otherapp-v1.2.3");
+    assert_eq!(announcing.release.package_idx(), Some(1));
+
+    let err = parser
+        .infer_tag(&packages, InferScope::Package(2))
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::NoVersionToInfer);
+
+    packages[1].version = Some(Version::new(0, 9, 0));
+    let err = parser.infer_tag(&packages, InferScope::All).unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::HeterogeneousVersions);
+
+    // Excluded packages don't get a say
+    packages[1].excluded = true;
+    assert_eq!(
+        parser.infer_tag(&packages, InferScope::All).unwrap().tag,
+        "v1.2.3"
+    );
+}
+
+#[test]
+fn heterogeneous_version_report() {
+    let package = |name: &str, version: Option<Version>| Package {
+        name: name.to_owned(),
+        version,
+        ..Default::default()
+    };
+    let packages = vec![
+        package("old", Some(Version::new(0, 9, 0))),
+        package("axolotlsay", Some(Version::new(1, 2, 3))),
+        package("otherapp", Some(Version::new(1, 2, 3))),
+        package("docs", None),
+        package("older", Some(Version::new(0, 9, 0))),
+        package("newest", Some(Version::new(2, 0, 0))),
+        package("lib", Some(Version::new(1, 2, 3))),
+    ];
+    let parser = TagParser::default();
+
+    let report = parser.version_report(&packages);
+    assert!(!report.is_unified());
+    assert_eq!(
+        report.to_string(),
+        "3 packages at 1.2.3, 2 at 0.9.0, 1 at 2.0.0"
+    );
+    assert_eq!(
+        report.clusters[1].suggested_tags,
+        vec!["old-v0.9.0".to_owned(), "older-v0.9.0".to_owned()]
+    );
+
+    let err = parser
+        .infer_tag(&packages, crate::infer::InferScope::All)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Couldn't infer a unified tag, because the packages have different versions \
+         (3 packages at 1.2.3, 2 at 0.9.0, 1 at 2.0.0)"
+    );
+    assert_eq!(
+        miette::Diagnostic::help(&err).unwrap().to_string(),
+        "tag the packages separately instead: axolotlsay-v1.2.3, otherapp-v1.2.3, lib-v1.2.3, \
+         old-v0.9.0, older-v0.9.0, newest-v2.0.0"
+    );
+
+    assert!(parser.version_report(&packages[1..3]).is_unified());
+}
+
+#[test]
+fn error_codes() {
+    use miette::Diagnostic;
+
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let errors = [
+        parse_tag(&packages, "axolotlsay-v2.0.0").unwrap_err(),
+        parse_tag(&packages, "v1.0.0.0.0").unwrap_err(),
+        parse_tag(&packages, "bad tag").unwrap_err(),
+    ];
+    let codes = errors.iter().map(TagError::code).collect::<Vec<_>>();
+    assert_eq!(codes, vec!["AXOTAG_0001", "AXOTAG_0004", "AXOTAG_0026"]);
+    for err in &errors {
+        assert_eq!(err.code(), err.kind().code());
+        assert_eq!(Diagnostic::code(err).unwrap().to_string(), err.code());
+    }
+}
+
+/// An error of every kind, to check things every error should have
+fn sample_error(kind: TagErrorKind) -> TagError {
+    let tag = || "axolotlsay-v1.0.0".to_owned();
+    let package_name = || "axolotlsay".to_owned();
+    let version = || Version::new(1, 0, 0);
+    match kind {
+        TagErrorKind::ContradictoryTagVersion => TagError::ContradictoryTagVersion {
+            tag: tag(),
+            package_name: package_name(),
+            tag_version: version(),
+            real_version: Version::new(2, 0, 0),
+        },
+        TagErrorKind::StaleTagVersion => TagError::StaleTagVersion {
+            tag: tag(),
+            package_name: package_name(),
+            tag_version: version(),
+            real_version: Version::new(2, 0, 0),
+        },
+        TagErrorKind::TagVersionAhead => TagError::TagVersionAhead {
+            tag: tag(),
+            package_name: package_name(),
+            tag_version: Version::new(2, 0, 0),
+            real_version: version(),
+        },
+        TagErrorKind::TagVersionParse => TagError::TagVersionParse {
+            tag: tag(),
+            details: "nope".parse::<Version>().unwrap_err(),
+        },
+        #[cfg(feature = "pep440")]
+        TagErrorKind::Pep440Parse => TagError::Pep440Parse {
+            tag: tag(),
+            details: "nope".parse::<crate::pep440::Pep440Version>().unwrap_err(),
+        },
+        TagErrorKind::MissingVPrefix => TagError::MissingVPrefix {
+            tag: "axolotlsay-1.0.0".to_owned(),
+            version_start: 11,
+        },
+        TagErrorKind::ForbiddenVPrefix => TagError::ForbiddenVPrefix {
+            tag: tag(),
+            version_start: 11,
+        },
+        TagErrorKind::LegacyTagFormat => TagError::LegacyTagFormat {
+            tag: "axolotlsay/v1.0.0".to_owned(),
+            format: TagFormat::Slash,
+            canonical: tag(),
+        },
+        TagErrorKind::UnexpectedPrefix => TagError::UnexpectedPrefix {
+            tag: "nightly/axolotlsay-v1.0.0".to_owned(),
+            prefix: "nightly".to_owned(),
+            allowed: vec!["releases".to_owned()],
+        },
+        TagErrorKind::MissingPackageComponent => TagError::MissingPackageComponent {
+            tag: "v1.0.0".to_owned(),
+            example: tag(),
+        },
+        TagErrorKind::AmbiguousPackageName => TagError::AmbiguousPackageName {
+            tag: "v1-v1.0.0".to_owned(),
+            package_name: "v1".to_owned(),
+        },
+        TagErrorKind::AmbiguousScope => TagError::AmbiguousScope {
+            tag: tag(),
+            package_name: package_name(),
+            version: version(),
+        },
+        TagErrorKind::NoVersionToBump => TagError::NoVersionToBump {
+            package_name: Some(package_name()),
+        },
+        TagErrorKind::MsiVersionOverflow => TagError::MsiVersionOverflow {
+            version: Version::new(256, 0, 0),
+            component: "major",
+            max: 255,
+        },
+        TagErrorKind::UnifiedVersionMismatch => TagError::UnifiedVersionMismatch {
+            tag: "v2.0.0".to_owned(),
+            version: Version::new(2, 0, 0),
+            require_all: true,
+            packages: vec![crate::errors::PackageVersion {
+                package_name: package_name(),
+                version: Some(version()),
+            }],
+        },
+        TagErrorKind::UnversionedPackage => TagError::UnversionedPackage {
+            tag: tag(),
+            package_name: package_name(),
+        },
+        TagErrorKind::PackageNotReleasable => TagError::PackageNotReleasable {
+            tag: tag(),
+            package_name: package_name(),
+        },
+        TagErrorKind::PlaceholderVersion => TagError::PlaceholderVersion {
+            tag: "v0.0.0".to_owned(),
+            package_name: None,
+        },
+        TagErrorKind::VersionBelowFloor => TagError::VersionBelowFloor {
+            tag: tag(),
+            package_name: Some(package_name()),
+            version: version(),
+            floor: Version::new(2, 0, 0),
+        },
+        TagErrorKind::VersionAboveCeiling => TagError::VersionAboveCeiling {
+            tag: tag(),
+            package_name: None,
+            version: version(),
+            ceiling: Version::new(1, 0, 0),
+        },
+        TagErrorKind::VersionRegression => TagError::VersionRegression {
+            tag: tag(),
+            version: version(),
+            existing_tag: "axolotlsay-v2.0.0".to_owned(),
+            existing_version: Version::new(2, 0, 0),
+        },
+        TagErrorKind::MissingPrerelease => TagError::MissingPrerelease {
+            tag: tag(),
+            version: version(),
+        },
+        #[cfg(feature = "regex")]
+        TagErrorKind::InvalidTagPattern => TagError::InvalidTagPattern {
+            pattern: "^release-(.+)$".to_owned(),
+            reason: "it doesn't have a `version` group".to_owned(),
+        },
+        TagErrorKind::UnrecognizedTagUrl => TagError::UnrecognizedTagUrl {
+            url: "https://example.com".to_owned(),
+        },
+        TagErrorKind::NoPackages => TagError::NoPackages { tag: tag() },
+        TagErrorKind::InvalidGitTag => TagError::InvalidGitTag {
+            tag: "bad tag".to_owned(),
+            position: 3,
+            reason: "it has a space".to_owned(),
+        },
+        TagErrorKind::NoVersionToInfer => TagError::NoVersionToInfer { package_name: None },
+        TagErrorKind::HeterogeneousVersions => TagError::HeterogeneousVersions {
+            report: crate::infer::VersionReport { clusters: vec![] },
+        },
+        TagErrorKind::NotAPrerelease => TagError::NotAPrerelease { tag: tag() },
+        TagErrorKind::AlreadyReleased => TagError::AlreadyReleased {
+            tag: "axolotlsay-v1.0.0-rc.1".to_owned(),
+            version: version(),
+            existing_tag: tag(),
+        },
+        TagErrorKind::OutsideBranchSeries => TagError::OutsideBranchSeries {
+            tag: "release-1.x/axolotlsay-v2.0.0".to_owned(),
+            branch: "release-1.x".to_owned(),
+            version: Version::new(2, 0, 0),
+        },
+        TagErrorKind::OverlappingPackageNames => TagError::OverlappingPackageNames {
+            tag: "axolotlsay-cli-v1.0.0".to_owned(),
+            package_name: "axolotlsay-cli".to_owned(),
+            other_package_name: package_name(),
+        },
+        TagErrorKind::InvalidDockerTag => TagError::InvalidDockerTag {
+            tag: String::new(),
+            problem: DockerTagProblem::Empty,
+        },
+        TagErrorKind::AmbiguousSegment => TagError::AmbiguousSegment {
+            tag: "releases/v1.0.0".to_owned(),
+            segment: "releases".to_owned(),
+            kinds: vec![SegmentKind::Package, SegmentKind::Prefix],
+        },
+        TagErrorKind::NotSameSeries => TagError::NotSameSeries {
+            old_tag: tag(),
+            new_tag: "v2.0.0".to_owned(),
+        },
+        TagErrorKind::DeniedTag => TagError::DeniedTag {
+            tag: "nightly".to_owned(),
+            pattern: "nightly*".to_owned(),
+        },
+        TagErrorKind::SignatureRequired => TagError::SignatureRequired {
+            tag: tag(),
+            status: crate::metadata::SignatureStatus::Unsigned,
+            policy: crate::metadata::SignaturePolicy::Signed,
+        },
+        TagErrorKind::InvalidPrereleaseLabel => TagError::InvalidPrereleaseLabel {
+            label: "r c".to_owned(),
+            reason: "it has a space".to_owned(),
+        },
+        TagErrorKind::PrereleaseCounterOverflow => {
+            TagError::PrereleaseCounterOverflow { version: version() }
+        }
+        TagErrorKind::VersionOverflow => TagError::VersionOverflow {
+            version: Version::new(u64::MAX, 0, 0),
+            component: "major",
+        },
+        #[allow(deprecated)]
+        TagErrorKind::NoTagMatch => TagError::NoTagMatch { tag: tag() },
+    }
+}
+
+#[test]
+fn every_error_code() {
+    use miette::Diagnostic;
+
+    // sample_error can't miss a kind, but this list can, so it has to cover every code
+    let kinds = [
+        TagErrorKind::ContradictoryTagVersion,
+        TagErrorKind::StaleTagVersion,
+        TagErrorKind::TagVersionAhead,
+        TagErrorKind::TagVersionParse,
+        #[cfg(feature = "pep440")]
+        TagErrorKind::Pep440Parse,
+        TagErrorKind::MissingVPrefix,
+        TagErrorKind::ForbiddenVPrefix,
+        TagErrorKind::LegacyTagFormat,
+        TagErrorKind::UnexpectedPrefix,
+        TagErrorKind::MissingPackageComponent,
+        TagErrorKind::AmbiguousPackageName,
+        TagErrorKind::AmbiguousScope,
+        TagErrorKind::NoVersionToBump,
+        TagErrorKind::MsiVersionOverflow,
+        TagErrorKind::UnifiedVersionMismatch,
+        TagErrorKind::UnversionedPackage,
+        TagErrorKind::PackageNotReleasable,
+        TagErrorKind::PlaceholderVersion,
+        TagErrorKind::VersionBelowFloor,
+        TagErrorKind::VersionAboveCeiling,
+        TagErrorKind::VersionRegression,
+        TagErrorKind::MissingPrerelease,
+        #[cfg(feature = "regex")]
+        TagErrorKind::InvalidTagPattern,
+        TagErrorKind::UnrecognizedTagUrl,
+        TagErrorKind::NoPackages,
+        TagErrorKind::InvalidGitTag,
+        TagErrorKind::NoVersionToInfer,
+        TagErrorKind::HeterogeneousVersions,
+        #[allow(deprecated)]
+        TagErrorKind::NoTagMatch,
+        TagErrorKind::NotAPrerelease,
+        TagErrorKind::AlreadyReleased,
+        TagErrorKind::OutsideBranchSeries,
+        TagErrorKind::OverlappingPackageNames,
+        TagErrorKind::InvalidDockerTag,
+        TagErrorKind::AmbiguousSegment,
+        TagErrorKind::NotSameSeries,
+        TagErrorKind::DeniedTag,
+        TagErrorKind::SignatureRequired,
+        TagErrorKind::InvalidPrereleaseLabel,
+        TagErrorKind::PrereleaseCounterOverflow,
+        TagErrorKind::VersionOverflow,
+    ];
+    let mut codes = kinds.iter().map(TagErrorKind::code).collect::<Vec<_>>();
+    codes.sort();
+    let last = codes.last().unwrap()["AXOTAG_".len()..]
+        .parse::<u32>()
+        .unwrap();
+    let expected = (1..=last)
+        .filter(|n| cfg!(feature = "pep440") || *n != 5)
+        .filter(|n| cfg!(feature = "regex") || *n != 23)
+        .map(|n| format!("AXOTAG_{n:04}"))
+        .collect::<Vec<_>>();
+    assert_eq!(codes, expected);
+
+    for kind in kinds {
+        let err = sample_error(kind);
+        assert_eq!(err.kind(), kind);
+        assert_eq!(
+            Diagnostic::code(&err).unwrap().to_string(),
+            kind.code(),
+            "{kind:?}"
+        );
+        assert!(Diagnostic::help(&err).is_some(), "{kind:?} has no help");
+    }
+}
+
+#[test]
+fn latest_releases() {
+    use crate::PrereleasePolicy;
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "docs".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "internal".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            excluded: true,
+            ..Default::default()
+        },
+    ];
+    let tags = [
+        "v0.9.0",
+        "axolotlsay-v1.0.0",
+        "axolotlsay-v1.1.0",
+        "axolotlsay-v2.0.0-rc.1",
+        "otherapp-v0.5.0",
+        "latest",
+        "not a tag",
+    ];
+    let parser = TagParser::default();
+
+    let latest = parser.latest_releases(&packages, tags, PrereleasePolicy::Skip);
+    assert_eq!(
+        latest.into_iter().collect::<Vec<_>>(),
+        vec![
+            (0, Version::new(1, 1, 0)),
+            (1, Version::new(0, 9, 0)),
+            (2, Version::new(0, 9, 0)),
+        ]
+    );
+    let latest = parser.latest_releases(&packages, tags, PrereleasePolicy::Include);
+    assert_eq!(latest[&0], "2.0.0-rc.1".parse().unwrap());
+    assert!(parser
+        .latest_releases(&packages, ["v1.0.0-rc.1"], PrereleasePolicy::Skip)
+        .is_empty());
+}
+
+#[test]
+fn latest_releases_unified_history_requiring_package_component() {
+    use crate::PrereleasePolicy;
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(2, 1, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(2, 1, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        require_package_component: true,
+        ..TagParser::default()
+    };
+
+    // The repo switched to package tags after the unified 2.0.0
+    let latest = parser.latest_releases(
+        &packages,
+        ["v2.0.0", "axolotlsay-v0.5.0"],
+        PrereleasePolicy::Skip,
+    );
+    assert_eq!(
+        latest.into_iter().collect::<Vec<_>>(),
+        vec![(0, Version::new(2, 0, 0)), (1, Version::new(2, 0, 0))]
+    );
+}
+
+#[test]
+fn graduate_prereleases() {
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+    let existing = ["axolotlsay-v1.2.2", "otherapp-v1.2.3+build.7"];
+
+    let next = parser
+        .graduate_tag(&packages, "releases/axolotlsay-v1.2.3-rc.2", existing)
+        .unwrap();
+    assert_eq!(next.tag, "releases/axolotlsay-v1.2.3");
+    assert_eq!(next.version, Version::new(1, 2, 3));
+    assert_eq!(
+        next.previous_tag.as_deref(),
+        Some("releases/axolotlsay-v1.2.3-rc.2")
+    );
+    assert_eq!(
+        parser
+            .graduate_tag(&packages, "v1.3.0-beta.1+abc", existing)
+            .unwrap()
+            .tag,
+        "v1.3.0"
+    );
+
+    let err = parser
+        .graduate_tag(&packages, "otherapp-v1.2.3-rc.1", existing)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The prerelease otherapp-v1.2.3-rc.1 can't graduate to 1.2.3, \
+         otherapp-v1.2.3+build.7 already released it"
+    );
+    let err = parser
+        .graduate_tag(&packages, "axolotlsay-v1.2.3", existing)
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::NotAPrerelease);
+}
+
+#[test]
+fn maintenance_branches() {
+    use crate::actions::MovingAlias;
+    use crate::branches::MaintenanceBranch;
+
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: Some(Version::new(1, 2, 4)),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        branch_qualifiers: vec!["lts".to_owned()],
+        series_branches: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "v1.2.4-lts").unwrap();
+    assert!(!announcing.prerelease);
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 2, 4))
+    );
+    assert_eq!(
+        announcing.branch,
+        Some(MaintenanceBranch {
+            name: "lts".to_owned(),
+            series: None,
+        })
+    );
+    let unversioned = vec![Package {
+        version: None,
+        ..packages[0].clone()
+    }];
+    let announcing = parser
+        .parse_tag(&unversioned, "my-app-v1.2.4-rc.1.lts")
+        .unwrap();
+    assert!(announcing.prerelease);
+    assert_eq!(announcing.release.version().unwrap().pre.as_str(), "rc.1");
+    // Qualifiers have to be whole identifiers
+    assert!(parser
+        .parse_tag(&unversioned, "my-app-v1.2.4-notlts")
+        .unwrap()
+        .branch
+        .is_none());
+
+    let announcing = parser
+        .parse_tag(&packages, "release-1.x/my-app-v1.2.4")
+        .unwrap();
+    assert_eq!(
+        announcing.branch,
+        Some(MaintenanceBranch {
+            name: "release-1.x".to_owned(),
+            series: Some(MovingAlias {
+                major: 1,
+                minor: None
+            }),
+        })
+    );
+    assert_eq!(announcing.warnings, vec![]);
+    let err = parser.parse_tag(&packages, "1.3.x/v1.2.4").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The provided announcement tag (1.3.x/v1.2.4) announces 1.2.4, \
+         which isn't in the series of its branch (1.3.x)"
+    );
+
+    // Without the settings, these are just prereleases and prefixes
+    let parser = TagParser::default();
+    let announcing = parser.parse_tag(&unversioned, "my-app-v1.2.4-lts").unwrap();
+    assert!(announcing.prerelease);
+    assert_eq!(announcing.branch, None);
+    let announcing = parser
+        .parse_tag(&packages, "release-1.x/my-app-v1.2.4")
+        .unwrap();
+    assert_eq!(announcing.branch, None);
+    assert_eq!(announcing.prefix(), Some("release-1.x"));
+}
+
+#[test]
+fn package_separators() {
+    let packages = vec![
+        Package {
+            name: "my_app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "other".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        package_separators: vec!['_', '@', '-'],
+        ..TagParser::default()
+    };
+
+    for tag in ["my_app_1.2.3", "my_app@v1.2.3", "my_app-v1.2.3"] {
+        let announcing = parser.parse_tag(&packages, tag).unwrap();
+        assert_eq!(announcing.release.package_idx(), Some(0), "{tag}");
+        assert_eq!(announcing.format, Some(TagFormat::Dash), "{tag}");
+    }
+    assert_eq!(
+        parser.format_tag(Some(&packages[1]), &Version::new(1, 2, 3)),
+        "other_v1.2.3"
+    );
+    assert!(parser.verify_roundtrip(&packages, TagFormat::Dash).is_ok());
+
+    // Other separators are still wrong
+    let err = parser.parse_tag(&packages, "other.1.2.3").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+    let err = TagParser::default()
+        .parse_tag(&packages, "my_app_1.2.3")
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::TagVersionParse);
+}
+
+#[test]
+fn parse_version_like_name_suffixes() {
+    let packages = vec![
+        Package {
+            name: "sdk".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "sdk-v2".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "app2".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "app".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+    ];
+
+    // The longest name isn't followed by a separator, so the shorter one wins
+    let announcing = parse_tag(&packages, "sdk-v2.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 0,
+            version: Version::new(2, 0, 0)
+        }
+    );
+    assert!(announcing.warnings.iter().any(|w| matches!(
+        w,
+        TagWarning::RejectedCandidate {
+            package_name,
+            reason: RejectionReason::WrongSeparator { found: '.' },
+        } if package_name == "sdk-v2"
+    )));
+
+    // Otherwise the longest name wins
+    for tag in ["sdk-v2-v1.0.0", "sdk-v2-1.0.0", "sdk-v2/v1.0.0"] {
+        let announcing = parse_tag(&packages, tag).unwrap();
+        assert_eq!(announcing.release.package_idx(), Some(1), "{tag}");
+    }
+    for (tag, idx) in [("app2-v1.0.0", 2), ("app-v2.0.0", 3), ("sdk/v2.0.0", 0)] {
+        let announcing = parse_tag(&packages, tag).unwrap();
+        assert_eq!(announcing.release.package_idx(), Some(idx), "{tag}");
+    }
+}
+
+#[test]
+fn parse_overlapping_package_names() {
+    let packages = vec![
+        Package {
+            name: "app".to_owned(),
+            version: Some(Version::parse("1.0.0-2.0.0").unwrap()),
+            ..Default::default()
+        },
+        Package {
+            name: "app-1".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let mut parser = TagParser {
+        lenient_versions: true,
+        ..Default::default()
+    };
+
+    // Both readings work, the longest name wins
+    let announcing = parser.parse_tag(&packages, "app-1-2.0.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(1));
+
+    parser.require_unambiguous_package_names = true;
+    let err = parser.parse_tag(&packages, "app-1-2.0.0").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::OverlappingPackageNames);
+    assert_eq!(err.code(), "AXOTAG_0033");
+
+    // Only one reading works without lenient versions
+    parser.lenient_versions = false;
+    let announcing = parser.parse_tag(&packages, "app-1-2.0.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(1));
+}
+
+#[test]
+fn match_package_prefixes() {
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            aliases: vec!["app".to_owned()],
+            ..Default::default()
+        },
+        Package {
+            name: "my-app-helper".to_owned(),
+            ..Default::default()
+        },
+        Package {
+            name: "my_app".to_owned(),
+            ..Default::default()
+        },
+    ];
+    let input = "my-app-helper-x86_64-unknown-linux-gnu.tar.gz";
+
+    let found = match_package_prefix(input, &packages, MatchOptions::default());
+    let summary: Vec<_> = found
+        .iter()
+        .map(|m| (m.idx, m.match_len(), m.rest))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (1, 13, "-x86_64-unknown-linux-gnu.tar.gz"),
+            (0, 6, "-helper-x86_64-unknown-linux-gnu.tar.gz"),
+        ]
+    );
+    assert_eq!(found[0].matched, "my-app-helper");
+
+    // Equally long matches keep their order, and say which alias matched
+    let options = TagParser {
+        separator_insensitive_names: true,
+        ..Default::default()
+    }
+    .match_options();
+    let found = match_package_prefix("MY_APP.zip", &packages, options);
+    assert!(found.is_empty());
+    let found = match_package_prefix("my_app.zip", &packages, options);
+    assert_eq!(found.iter().map(|m| m.idx).collect::<Vec<_>>(), vec![0, 2]);
+    let found = match_package_prefix("app.zip", &packages, options);
+    assert_eq!(found[0].alias, Some(0));
+    assert_eq!(found[0].matched, "app");
+}
+
+#[test]
+fn sanitize_tags() {
+    assert_eq!(sanitize_for_filename("v1.2.3"), "v1.2.3");
+    assert_eq!(sanitize_for_filename("my-app/v1.2.3"), "my-app%2Fv1.2.3");
+    assert_eq!(
+        sanitize_for_filename("@my-org/my-app@1.0.0+build.5"),
+        "%40my-org%2Fmy-app%401.0.0%2Bbuild.5"
+    );
+    assert_eq!(sanitize_for_filename("release:1.0"), "release%3A1.0");
+    assert_eq!(sanitize_for_filename("nul.1.0.0"), "%6Eul.1.0.0");
+    assert_eq!(sanitize_for_filename("com1"), "%63om1");
+    assert_eq!(sanitize_for_filename("common-v1.0.0"), "common-v1.0.0");
+    assert_eq!(sanitize_for_filename("café-v1.0.0"), "café-v1.0.0");
+
+    assert_eq!(sanitize_for_url("my_app-v1.2.3~1"), "my_app-v1.2.3~1");
+    assert_eq!(sanitize_for_url("my-app/v1.2.3+5"), "my-app%2Fv1.2.3%2B5");
+    assert_eq!(sanitize_for_url("café"), "caf%C3%A9");
+    assert_eq!(sanitize_for_url("50%"), "50%25");
+
+    for tag in [
+        "v1.2.3",
+        "my-app/v1.2.3",
+        "@my-org/my-app@1.0.0+build.5",
+        "nul",
+        "café-v1%",
+    ] {
+        assert_eq!(
+            tag_from_filename(&sanitize_for_filename(tag)).as_deref(),
+            Some(tag)
+        );
+        assert_eq!(tag_from_url(&sanitize_for_url(tag)).as_deref(), Some(tag));
+    }
+
+    // Malformed escapes
+    assert_eq!(tag_from_url("v1%2"), None);
+    assert_eq!(tag_from_url("v1%+1"), None);
+    assert_eq!(tag_from_filename("%FF"), None);
+}
+
+#[test]
+fn docker_tags() {
+    for (version, docker) in [
+        ("1.2.3", "1.2.3"),
+        ("1.2.3-rc.1", "1.2.3-rc.1"),
+        ("1.2.3+build.4", "1.2.3_build.4"),
+        ("1.2.3-rc.1+build-4.5", "1.2.3-rc.1_build-4.5"),
+    ] {
+        let version = Version::parse(version).unwrap();
+        assert_eq!(to_docker_tag(&version).unwrap(), docker, "{version}");
+        assert_eq!(from_docker_tag(docker), Some(version));
+    }
+    assert_eq!(from_docker_tag("latest"), None);
+
+    let long = Version::parse(&format!("1.2.3-{}", "a".repeat(200))).unwrap();
+    let err = to_docker_tag(&long).unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::InvalidDockerTag);
+    assert!(matches!(
+        err,
+        TagError::InvalidDockerTag {
+            problem: DockerTagProblem::TooLong { len: 206 },
+            ..
+        }
+    ));
+
+    check_docker_tag("v1.2.3_build.4").unwrap();
+    check_docker_tag("latest").unwrap();
+    for (tag, problem) in [
+        ("", DockerTagProblem::Empty),
+        ("v1.2.3+4", DockerTagProblem::InvalidChar { found: '+' }),
+        (
+            "my-app/v1.2.3",
+            DockerTagProblem::InvalidChar { found: '/' },
+        ),
+        ("-v1", DockerTagProblem::InvalidStart { found: '-' }),
+    ] {
+        match check_docker_tag(tag) {
+            Err(TagError::InvalidDockerTag { problem: found, .. }) => {
+                assert_eq!(found, problem, "{tag}")
+            }
+            other => panic!("{tag}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn diff_announcement_plans() {
+    let mut packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "my-lib".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "other".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+    let at_pr = parser.plan_tag(&packages, "v1.2.3").unwrap();
+    assert_eq!(at_pr.kind, PlanKind::Unified);
+    assert_eq!(at_pr.format, Some(TagFormat::Dash));
+    assert_eq!(
+        at_pr.packages,
+        vec![
+            PlannedPackage {
+                name: "my-app".to_owned(),
+                version: Some(Version::new(1, 2, 3)),
+            },
+            PlannedPackage {
+                name: "my-lib".to_owned(),
+                version: Some(Version::new(1, 2, 3)),
+            },
+        ]
+    );
+    assert!(at_pr.diff(&at_pr).is_empty());
+    assert_eq!(at_pr.diff(&at_pr).to_string(), "");
+
+    // Someone bumped my-lib and synced other before the tag got pushed
+    packages[1].version = Some(Version::new(1, 3, 0));
+    packages[2].version = Some(Version::new(1, 2, 3));
+    let at_tag = parser.plan_tag(&packages, "v1.2.3").unwrap();
+    let diff = at_pr.diff(&at_tag);
+    assert!(!diff.is_empty());
+    assert_eq!(diff.tag, None);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.removed[0].name, "my-lib");
+    assert_eq!(diff.to_string(), "- my-lib 1.2.3\n+ other 1.2.3");
+
+    let at_pr = parser.plan_tag(&packages, "my-lib-v1.3.0").unwrap();
+    packages[1].version = Some(Version::parse("1.3.0-rc.1").unwrap());
+    let at_tag = parser.plan_tag(&packages, "my-lib-v1.3.0-rc.1").unwrap();
+    let diff = at_pr.diff(&at_tag);
+    assert_eq!(
+        diff.prerelease,
+        Some(Change {
+            before: false,
+            after: true
+        })
+    );
+    assert_eq!(
+        diff.changed,
+        vec![PackageChange {
+            name: "my-lib".to_owned(),
+            before: Some(Version::new(1, 3, 0)),
+            after: Some(Version::parse("1.3.0-rc.1").unwrap()),
+        }]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "tag: my-lib-v1.3.0 -> my-lib-v1.3.0-rc.1\n\
+         version: 1.3.0 -> 1.3.0-rc.1\n\
+         prerelease: false -> true\n\
+         ~ my-lib 1.3.0 -> 1.3.0-rc.1"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn announcement_plans_round_trip() {
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: Some(Version::new(1, 2, 3)),
+        ..Default::default()
+    }];
+    let plan = TagParser::default()
+        .plan_tag(&packages, "my-app/v1.2.3")
+        .unwrap();
+    let stored = serde_json::to_string(&plan).unwrap();
+    let loaded: crate::plan::AnnouncementPlan = serde_json::from_str(&stored).unwrap();
+    assert_eq!(loaded, plan);
+    assert_eq!(loaded.format, Some(TagFormat::Slash));
+}
+
+#[test]
+fn migration_presets() {
+    let packages = vec![
+        Package {
+            name: "@axo/app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "helper".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+    ];
+
+    let changesets = TagParser::from_preset(TagPreset::Changesets);
+    let announcing = changesets.parse_tag(&packages, "@axo/app@1.2.3").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    assert_eq!(
+        announcing.style,
+        Some(TagStyle::Custom {
+            name: "changesets".to_owned()
+        })
+    );
+    let announcing = changesets.parse_tag(&packages, "v1.2.3").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 2, 3))
+    );
+    // New tags are spelled the cargo-dist way, and still parse
+    let tag = changesets.format_tag(Some(&packages[1]), &Version::new(1, 2, 3));
+    assert_eq!(tag, "helper-v1.2.3");
+    assert_eq!(
+        changesets.parse_tag(&packages, &tag).unwrap().format,
+        Some(TagFormat::Dash)
+    );
+
+    let lerna = TagParser::from_preset(TagPreset::Lerna);
+    let announcing = lerna.parse_tag(&packages, "helper@1.2.3").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(1));
+    assert_eq!(
+        announcing.style,
+        Some(TagStyle::Custom {
+            name: "lerna".to_owned()
+        })
+    );
+
+    let release_please = TagParser::from_preset(TagPreset::ReleasePlease);
+    let announcing = release_please.parse_tag(&packages, "app-v1.2.3").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    assert!(release_please.parse_tag(&packages, "helper@1.2.3").is_err());
+
+    for preset in TagPreset::ALL {
+        assert_eq!(preset.name().parse::<TagPreset>(), Ok(preset));
+    }
+    assert_eq!("release-please".parse(), Ok(TagPreset::ReleasePlease));
+    assert!("semantic-release".parse::<TagPreset>().is_err());
+}
+
+#[test]
+fn ambiguous_segments() {
+    let packages = vec![
+        Package {
+            name: "nightly".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "release-1.x".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let mut parser = TagParser {
+        allowed_prefixes: Some(vec!["nightly/".to_owned(), "release-2.x".to_owned()]),
+        series_branches: true,
+        ..Default::default()
+    };
+    let kinds = |parser: &TagParser, tag| match parser.parse_tag(&packages, tag) {
+        Err(TagError::AmbiguousSegment { segment, kinds, .. }) => (segment, kinds),
+        other => panic!("{tag}: {other:?}"),
+    };
+
+    // Package or prefix
+    assert_eq!(
+        kinds(&parser, "nightly/v1.0.0"),
+        (
+            "nightly".to_owned(),
+            vec![SegmentKind::Package, SegmentKind::Prefix]
+        )
+    );
+    // Package or branch
+    assert_eq!(
+        kinds(&parser, "release-1.x/v1.0.0"),
+        (
+            "release-1.x".to_owned(),
+            vec![SegmentKind::Package, SegmentKind::Branch]
+        )
+    );
+    // Branch or prefix
+    assert_eq!(
+        kinds(&parser, "release-2.x/v2.0.0"),
+        (
+            "release-2.x".to_owned(),
+            vec![SegmentKind::Branch, SegmentKind::Prefix]
+        )
+    );
+    let err = parser.parse_tag(&packages, "nightly/v1.0.0").unwrap_err();
+    assert_eq!(err.code(), "AXOTAG_0035");
+    assert_eq!(
+        err.to_string(),
+        "The provided announcement tag (nightly/v1.0.0) is ambiguous, nightly could be a package or an allowed prefix"
+    );
+
+    // Anything that isn't ambiguous is unaffected
+    let announcing = parser.parse_tag(&packages, "nightly-v1.0.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    let announcing = parser
+        .parse_tag(&packages, "nightly/nightly/v1.0.0")
+        .unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    assert_eq!(announcing.prefix(), Some("nightly"));
+
+    // The first kind in the order wins
+    parser.segment_order = SegmentKind::DEFAULT_ORDER.to_vec();
+    let announcing = parser.parse_tag(&packages, "nightly/v1.0.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    assert!(announcing.warnings.contains(&TagWarning::AmbiguousSegment {
+        segment: "nightly".to_owned(),
+        chosen: SegmentKind::Package,
+    }));
+    let announcing = parser.parse_tag(&packages, "release-2.x/v2.0.0").unwrap();
+    assert_eq!(
+        announcing.branch.map(|b| b.name),
+        Some("release-2.x".to_owned())
+    );
+    let announcing = parser.parse_tag(&packages, "release-1.x/v1.0.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(1));
+    assert_eq!(announcing.branch, None);
+
+    parser.segment_order = vec![SegmentKind::Prefix, SegmentKind::Branch];
+    let announcing = parser.parse_tag(&packages, "nightly/v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 0, 0))
+    );
+    assert_eq!(announcing.prefix(), Some("nightly"));
+    assert!(announcing.warnings.contains(&TagWarning::AmbiguousSegment {
+        segment: "nightly".to_owned(),
+        chosen: SegmentKind::Prefix,
+    }));
+    let announcing = parser.parse_tag(&packages, "release-1.x/v1.0.0").unwrap();
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Unified(Version::new(1, 0, 0))
+    );
+    assert_eq!(
+        announcing.branch.map(|b| b.name),
+        Some("release-1.x".to_owned())
+    );
+    let announcing = parser.parse_tag(&packages, "release-2.x/v2.0.0").unwrap();
+    assert_eq!(announcing.branch, None);
+    assert_eq!(announcing.prefix(), Some("release-2.x"));
+
+    // Kinds left out of the order can't win
+    parser.segment_order = vec![SegmentKind::Branch];
+    assert_eq!(kinds(&parser, "nightly/v1.0.0").0, "nightly");
+}
+
+#[test]
+fn compiled_workspace_parses_the_same() {
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            aliases: vec!["app".to_owned()],
+            ..Default::default()
+        },
+        Package {
+            name: "my-app-helper".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "@axo/my_lib".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            path: Some("crates/my-lib".into()),
+            ..Default::default()
+        },
+        Package {
+            name: "sdk".to_owned(),
+            version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        },
+        Package {
+            name: "sdk-v2".to_owned(),
+            version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        },
+    ];
+    let tags = [
+        "v1.2.3",
+        "my-app-v1.2.3",
+        "app-v1.2.3",
+        "MY-APP-v1.2.3",
+        "my-app-helper-v0.1.0",
+        "my-app-helper/v0.1.0",
+        "my-app-v0.1.0",
+        "my_lib-v1.2.3",
+        "@axo/my_lib@1.2.3",
+        "@axo/my-lib-v1.2.3",
+        "crates/my-lib/v1.2.3",
+        "releases/my-app-v1.2.3",
+        "sdk-v2.0.0",
+        "sdk-v2-v1.0.0",
+        "my-ap-v1.2.3",
+        "garbage",
+    ];
+    let parsers = [
+        TagParser::default(),
+        TagParser {
+            case_insensitive_names: true,
+            separator_insensitive_names: true,
+            strip_npm_scopes: true,
+            ..Default::default()
+        },
+        TagParser {
+            require_unambiguous_package_names: true,
+            lenient_versions: true,
+            ..TagParser::changesets()
+        },
+    ];
+    for parser in parsers {
+        let workspace = parser.compile(&packages);
+        assert_eq!(workspace.packages(), packages.as_slice());
+        for tag in tags {
+            let expected = parser.parse_tag(&packages, tag);
+            let compiled = workspace.parse_tag(tag);
+            match (expected, compiled) {
+                (Ok(expected), Ok(compiled)) => assert_eq!(compiled, expected, "{tag}"),
+                (Err(expected), Err(compiled)) => {
+                    assert_eq!(compiled.to_string(), expected.to_string(), "{tag}")
+                }
+                (expected, compiled) => panic!("{tag}: {expected:?} != {compiled:?}"),
+            }
+        }
+        let bulk = workspace.parse_tags(tags);
+        assert_eq!(bulk.stats.parsed, tags.len());
+        assert_eq!(
+            bulk.stats,
+            crate::stats::ParseStats {
+                elapsed: bulk.stats.elapsed,
+                ..parser.parse_tags(&packages, tags).stats
+            }
+        );
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<crate::compiled::CompiledWorkspace>();
+}
+
+#[test]
+fn bumps_between_tags() {
+    use crate::bump::{bump_kind, BumpKind};
+
+    let v = |s: &str| Version::parse(s).unwrap();
+    for (old, new, kind) in [
+        ("1.2.3", "2.0.0", BumpKind::Major),
+        ("1.2.3", "2.0.0-rc.1", BumpKind::Major),
+        ("2.0.0-rc.2", "2.0.0", BumpKind::Major),
+        ("1.2.3", "1.3.0-rc.1", BumpKind::Minor),
+        ("1.3.0-rc.1", "1.3.0", BumpKind::Minor),
+        ("1.2.3-rc.1", "1.3.0-rc.1", BumpKind::Minor),
+        ("1.2.3", "1.2.4-rc.1", BumpKind::Patch),
+        ("1.2.4-rc.1", "1.2.4", BumpKind::Patch),
+        ("1.2.3-rc.1", "1.2.3-rc.2", BumpKind::Prerelease),
+        ("1.2.3-beta.2", "1.2.3-rc.1", BumpKind::Prerelease),
+        ("1.2.3", "1.2.3+build.5", BumpKind::None),
+        ("1.2.3", "1.2.3-rc.1", BumpKind::Downgrade),
+        ("1.2.3-rc.2", "1.2.3-rc.1", BumpKind::Downgrade),
+        ("2.0.0", "1.9.9", BumpKind::Downgrade),
+    ] {
+        assert_eq!(bump_kind(&v(old), &v(new)), kind, "{old} => {new}");
+    }
+
+    let packages = vec![
+        Package {
+            name: "axolotlsay".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "otherapp".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+    let bump = |old, new| parser.bump_between(&packages, old, new);
+    assert_eq!(
+        bump("axolotlsay-v0.9.0", "axolotlsay-v1.0.0-rc.1").unwrap(),
+        BumpKind::Major
+    );
+    assert_eq!(bump("v1.2.3", "v1.2.4").unwrap(), BumpKind::Patch);
+    assert_eq!(
+        bump("axolotlsay-v1.2.3", "releases/axolotlsay-v1.2.3").unwrap(),
+        BumpKind::None
+    );
+
+    let err = bump("axolotlsay-v1.2.3", "otherapp-v1.2.4").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::NotSameSeries);
+    assert_eq!(
+        err.to_string(),
+        "Can't compare axolotlsay-v1.2.3 and otherapp-v1.2.4, \
+         they aren't releases of the same package"
+    );
+    let err = bump("v1.2.3", "axolotlsay-v1.2.4").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::NotSameSeries);
+}
+
+#[test]
+fn namespaced_sub_projects() {
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            namespace: Some("rust".to_owned()),
+            ..Default::default()
+        },
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(0, 4, 0)),
+            namespace: Some("js".to_owned()),
+            ..Default::default()
+        },
+        Package {
+            name: "my-pkg".to_owned(),
+            version: Some(Version::new(0, 4, 0)),
+            namespace: Some("js".to_owned()),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        namespaces: vec![
+            Namespace {
+                name: "rust".to_owned(),
+                parser: None,
+            },
+            Namespace {
+                name: "js".to_owned(),
+                parser: Some(TagParser::changesets()),
+            },
+        ],
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "js/my-app@0.4.0").unwrap();
+    assert_eq!(announcing.tag, "js/my-app@0.4.0");
+    assert_eq!(announcing.namespace.as_deref(), Some("js"));
+    assert_eq!(
+        announcing.release,
+        AnnouncementKind::Package {
+            idx: 1,
+            version: Version::new(0, 4, 0)
+        }
+    );
+    let announcing = parser.parse_tag(&packages, "rust/my-app-v1.2.3").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    // Each namespace only has its own packages
+    assert!(parser.parse_tag(&packages, "rust/my-pkg-v0.4.0").is_err());
+
+    // Unified releases in a namespace only release its packages
+    let announcing = parser.parse_tag(&packages, "js/v0.4.0").unwrap();
+    assert_eq!(announcing.selected_packages(&packages), vec![1, 2]);
+
+    // Tags outside the namespaces still see every package
+    let announcing = parser.parse_tag(&packages, "my-pkg-v0.4.0").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(2));
+    assert_eq!(announcing.namespace, None);
+
+    assert_eq!(
+        parser.format_tag(Some(&packages[0]), &Version::new(1, 2, 4)),
+        "rust/my-app-v1.2.4"
+    );
+    assert_eq!(
+        parser.format_tag(Some(&packages[2]), &Version::new(0, 5, 0)),
+        "js/my-pkg-v0.5.0"
+    );
+    assert_eq!(parser.format_tag(None, &Version::new(2, 0, 0)), "v2.0.0");
+}
+
+#[test]
+fn denied_tags() {
+    let packages = vec![Package {
+        name: "deploy".to_owned(),
+        version: Some(Version::new(1, 2, 3)),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        denied_tags: vec![
+            "latest".to_owned(),
+            "deploy-*".to_owned(),
+            "*-rc.*".to_owned(),
+        ],
+        ..TagParser::default()
+    };
+
+    let err = parser.parse_tag(&packages, "deploy-v1.2.3").unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::DeniedTag);
+    assert_eq!(
+        err.to_string(),
+        "The tag deploy-v1.2.3 belongs to other automation (it matches deploy-*)"
+    );
+    // Channels and prereleases are denied like anything else
+    assert_eq!(
+        parser.parse_tag(&packages, "latest").unwrap_err().kind(),
+        TagErrorKind::DeniedTag
+    );
+    assert_eq!(
+        parser
+            .parse_tag(&packages, "v1.2.3-rc.1")
+            .unwrap_err()
+            .kind(),
+        TagErrorKind::DeniedTag
+    );
+    // Patterns have to match the whole tag
+    assert!(parser.parse_tag(&packages, "deploy/v1.2.3").is_ok());
+    assert!(parser.check_not_denied("latest-deploy").is_ok());
+
+    assert_eq!(
+        parser
+            .try_format_tag(Some(&packages[0]), &Version::new(1, 2, 4))
+            .unwrap_err()
+            .kind(),
+        TagErrorKind::DeniedTag
+    );
+    assert_eq!(
+        parser.try_format_tag(None, &Version::new(1, 2, 4)).unwrap(),
+        "v1.2.4"
+    );
+    let err = parser
+        .suggest_next_tag(&packages, Some(0), ["deploy/v1.2.3"], &BumpLevel::Patch)
+        .unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::DeniedTag);
+}
+
+#[test]
+fn deprecated_tag_formats() {
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 2, 3)),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        formats: vec![TagFormat::Dash, TagFormat::Slash],
+        deprecate_legacy_formats: true,
+        ..TagParser::default()
+    };
+
+    let announcing = parser.parse_tag(&packages, "axolotlsay/v1.2.3").unwrap();
+    assert_eq!(announcing.release.package_idx(), Some(0));
+    assert_eq!(
+        announcing.warnings,
+        vec![TagWarning::DeprecatedTagFormat {
+            format: TagFormat::Slash,
+            canonical: "axolotlsay-v1.2.3".to_owned(),
+        }]
+    );
+    assert_eq!(
+        announcing.warnings[0].to_string(),
+        "This tag uses the deprecated Slash format, it's axolotlsay-v1.2.3 in the current one"
+    );
+    assert!(parser
+        .parse_tag(&packages, "axolotlsay-v1.2.3")
+        .unwrap()
+        .warnings
+        .is_empty());
+
+    // Rejecting wins over deprecating
+    let parser = TagParser {
+        reject_legacy_formats: true,
+        ..parser
+    };
+    assert_eq!(
+        parser
+            .parse_tag(&packages, "axolotlsay/v1.2.3")
+            .unwrap_err()
+            .kind(),
+        TagErrorKind::LegacyTagFormat
+    );
+}
+
+#[test]
+fn traced_parses() {
+    use crate::explain::{AttemptOutcome, Candidate};
+    use crate::grammar::PipelineStage;
+
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "my-app-helper".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser::default();
+
+    let (result, trace) = parser.parse_tag_traced(&packages, "my-app-v1.2.3");
+    assert_eq!(result.unwrap().release.package_idx(), Some(0));
+    assert_eq!(trace.attempts.len(), 1);
+    assert_eq!(
+        trace.attempts[0].stage,
+        PipelineStage::Format(TagFormat::Dash)
+    );
+    assert_eq!(
+        trace.attempts[0].outcome,
+        AttemptOutcome::Accepted {
+            package_name: Some("my-app".to_owned()),
+            version: "v1.2.3".to_owned(),
+        }
+    );
+    assert_eq!(
+        trace.candidates,
+        vec![Candidate {
+            package_name: "my-app".to_owned(),
+            rejected: None,
+        }]
+    );
+
+    let (_, trace) = parser.parse_tag_traced(&packages, "my-app-helper-v0.1.0");
+    assert_eq!(
+        trace.candidates[0].rejected,
+        Some(RejectionReason::LongerMatch {
+            package_name: "my-app-helper".to_owned(),
+        })
+    );
+    assert_eq!(trace.candidates[1].rejected, None);
+
+    // Every reading gets tried before the tag fails
+    let (result, trace) = parser.parse_tag_traced(&packages, "my-app-helper-vnope");
+    assert!(result.is_err());
+    let stages = trace.attempts.iter().map(|a| a.stage).collect::<Vec<_>>();
+    assert_eq!(
+        stages,
+        vec![
+            PipelineStage::Format(TagFormat::Dash),
+            PipelineStage::Format(TagFormat::Slash),
+            PipelineStage::Format(TagFormat::Path),
+            PipelineStage::Unified,
+        ]
+    );
+    assert!(matches!(
+        &trace.attempts[0].outcome,
+        AttemptOutcome::Rejected { package_name: Some(name), kind: TagErrorKind::TagVersionParse, .. }
+            if name == "my-app-helper"
+    ));
+    assert_eq!(trace.attempts[1].outcome, AttemptOutcome::NoMatch);
+    assert_eq!(
+        trace.candidates[0],
+        Candidate {
+            package_name: "my-app".to_owned(),
+            rejected: Some(RejectionReason::InvalidVersion {
+                version: "helper-vnope".to_owned(),
+            }),
+        }
+    );
+    let explained = trace.to_string();
+    assert!(explained.starts_with("Dash format: my-app-helper at vnope, rejected: "));
+    assert!(explained.contains("\nSlash format: no match\n"));
+    assert!(explained.ends_with(
+        "\ncandidate my-app: helper-vnope isn't a valid version\n\
+         candidate my-app-helper: vnope isn't a valid version"
+    ));
+}
+
+#[test]
+fn traces_follow_the_parse() {
+    use crate::explain::AttemptOutcome;
+    use crate::grammar::PipelineStage;
+
+    let packages = vec![Package {
+        name: "nightly".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+
+    // The package segment turns out to be a prefix, so the tag is read again as unified
+    let parser = TagParser {
+        allowed_prefixes: Some(vec!["nightly".to_owned()]),
+        segment_order: vec![SegmentKind::Prefix],
+        ..TagParser::default()
+    };
+    let (result, trace) = parser.parse_tag_traced(&packages, "nightly/v1.0.0");
+    assert_eq!(result.unwrap().release.package_idx(), None);
+    let last = trace.attempts.len() - 1;
+    assert_eq!(
+        trace.attempts[last - 1].outcome,
+        AttemptOutcome::Reinterpreted {
+            package_name: Some("nightly".to_owned()),
+            version: "v1.0.0".to_owned(),
+            segment: "nightly".to_owned(),
+            kind: SegmentKind::Prefix,
+        }
+    );
+    assert_eq!(trace.attempts[last].stage, PipelineStage::Unified);
+    assert_eq!(
+        trace.attempts[last].outcome,
+        AttemptOutcome::Accepted {
+            package_name: None,
+            version: "v1.0.0".to_owned(),
+        }
+    );
+
+    // Checks after the reading fail it
+    let (result, trace) = parser.parse_tag_traced(&packages, "releases/nightly-v1.0.0");
+    assert_eq!(result.unwrap_err().kind(), TagErrorKind::UnexpectedPrefix);
+    assert!(matches!(
+        &trace.attempts.last().unwrap().outcome,
+        AttemptOutcome::Failed {
+            kind: TagErrorKind::UnexpectedPrefix,
+            ..
+        }
+    ));
+
+    // Typo tolerance records how the corrected tag went
+    let packages = vec![Package {
+        name: "axolotlsay".to_owned(),
+        version: Some(Version::new(1, 0, 0)),
+        ..Default::default()
+    }];
+    let parser = TagParser {
+        typo_tolerance: true,
+        ..TagParser::default()
+    };
+    let (result, trace) = parser.parse_tag_traced(&packages, "axolotlasy-v1.0.0");
+    assert_eq!(result.unwrap().release.package_idx(), Some(0));
+    let typo = trace.typo.as_ref().unwrap();
+    assert_eq!(typo.tag, "axolotlsay-v1.0.0");
+    assert_eq!(typo.package_name, "axolotlsay");
+    assert_eq!(
+        typo.trace.attempts[0].outcome,
+        AttemptOutcome::Accepted {
+            package_name: Some("axolotlsay".to_owned()),
+            version: "v1.0.0".to_owned(),
+        }
+    );
+    assert!(trace.to_string().ends_with(
+        "corrected to axolotlsay-v1.0.0:\n  \
+         Dash format: axolotlsay at v1.0.0, accepted\n  \
+         candidate axolotlsay: fine"
+    ));
+}
+
+#[test]
+fn signed_tag_policies() {
+    use crate::metadata::SignatureStatus;
+    use crate::{SignaturePolicy, TagMetadata};
+
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: Some(Version::new(1, 2, 3)),
+        ..Default::default()
+    }];
+    let signed = |signature| TagMetadata {
+        tagger: Some("Jane Doe <jane@example.com>".to_owned()),
+        date: Some(1_700_000_000),
+        message: Some("my-app 1.2.3".to_owned()),
+        signature,
+    };
+    let good = signed(SignatureStatus::Good {
+        signer: Some("Jane Doe".to_owned()),
+    });
+    let tag = "my-app-v1.2.3";
+
+    // Anything goes by default
+    let parser = TagParser::default();
+    assert!(parser.check_tag_metadata(tag, None).is_ok());
+    assert!(parser
+        .check_tag_metadata(tag, Some(&signed(SignatureStatus::Bad)))
+        .is_ok());
+
+    let parser = TagParser {
+        signatures: SignaturePolicy::Signed,
+        ..TagParser::default()
+    };
+    let err = parser.check_tag_metadata(tag, None).unwrap_err();
+    assert_eq!(err.kind(), TagErrorKind::SignatureRequired);
+    assert_eq!(
+        err.to_string(),
+        "The tag my-app-v1.2.3 isn't signed, but release tags need a signature"
+    );
+    assert!(parser
+        .check_tag_metadata(tag, Some(&signed(SignatureStatus::Unverified)))
+        .is_ok());
+    assert!(parser
+        .check_tag_metadata(tag, Some(&signed(SignatureStatus::Bad)))
+        .is_err());
+
+    let parser = TagParser {
+        signatures: SignaturePolicy::Verified,
+        ..TagParser::default()
+    };
+    let err = parser
+        .check_tag_metadata(tag, Some(&signed(SignatureStatus::Unverified)))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The tag my-app-v1.2.3 has an unverified signature, but release tags need a verified signature"
+    );
+    let plan = parser
+        .plan_tag_with_metadata(&packages, tag, Some(good.clone()))
+        .unwrap();
+    assert_eq!(plan.metadata, Some(good));
+    // Plans made before tagging don't have metadata, which isn't drift
+    assert!(parser
+        .plan_tag(&packages, tag)
+        .unwrap()
+        .diff(&plan)
+        .is_empty());
+    assert!(parser.plan_tag_with_metadata(&packages, tag, None).is_err());
+}
+
+#[test]
+fn relevant_tags() {
+    let packages = vec![
+        Package {
+            name: "my-app".to_owned(),
+            version: Some(Version::new(1, 2, 3)),
+            ..Default::default()
+        },
+        Package {
+            name: "internal".to_owned(),
+            version: Some(Version::new(0, 1, 0)),
+            excluded: true,
+            ..Default::default()
+        },
+    ];
+    let parser = TagParser {
+        reserved_tags: vec!["nightly".to_owned()],
+        denied_tags: vec!["deploy-*".to_owned()],
+        typo_tolerance: true,
+        ..TagParser::default()
+    };
+    let compiled = parser.compile(&packages);
+
+    for (tag, relevant) in [
+        ("my-app-v1.2.3", true),
+        ("releases/my-app/v1.2.3", true),
+        ("v1.2.3", true),
+        ("latest", true),
+        ("my-apq-v1.2.3", true),
+        // Doesn't match the package's version, but names it
+        ("my-app-v9.9.9", true),
+        ("v2.0.0", false),
+        ("internal-v0.1.0", false),
+        ("other-app-v1.2.3", false),
+        ("nightly", false),
+        ("deploy-v1.2.3", false),
+        ("my-app..v1", false),
+        ("not a tag", false),
+    ] {
+        assert_eq!(parser.is_relevant_tag(&packages, tag), relevant, "{tag}");
+        assert_eq!(compiled.is_relevant_tag(tag), relevant, "{tag}");
+        // Anything that announces something has to be relevant
+        if let Ok(announcing) = parser.parse_tag(&packages, tag) {
+            if !parser.selected_packages(&announcing, &packages).is_empty() {
+                assert!(relevant, "{tag}");
+            }
+        }
+    }
+    assert!(crate::is_relevant_tag(&packages, "my-app-v1.2.3"));
+    assert!(!crate::is_relevant_tag(&[], "v1.2.3"));
+
+    // Unified releases find their packages like parsing does
+    let parser = TagParser {
+        build_metadata: BuildMetadataPolicy::Ignore,
+        unified_versions: UnifiedVersionPolicy::All,
+        ..TagParser::default()
+    };
+    let packages = vec![Package {
+        name: "my-app".to_owned(),
+        version: "1.2.3".parse().ok(),
+        ..Default::default()
+    }];
+    assert!(parser.parse_tag(&packages, "v1.2.3+build.1").is_ok());
+    assert!(parser.is_relevant_tag(&packages, "v1.2.3+build.1"));
 }
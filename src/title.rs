@@ -0,0 +1,139 @@
+//! Human-facing titles and bodies for announcements, like "my-app 1.2.3".
+
+use crate::{AnnouncementKind, Package, PartialAnnouncementTag};
+
+/// Templates for announcement titles and bodies
+///
+/// Templates can use these placeholders:
+/// * `{tag}`: the full tag
+/// * `{version}`: the version being announced (or the channel, for channel tags)
+/// * `{package}`: the announced package's name (for package-scoped tags) or a single
+///   line's package (for [`AnnouncementTemplates::body_line`][])
+/// * `{packages}`: how many packages are being announced, like "3 packages"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnouncementTemplates {
+    /// The title of a package-scoped announcement
+    pub package_title: String,
+    /// The title of a unified announcement
+    pub unified_title: String,
+    /// A line of the body, repeated for each announced package
+    pub body_line: String,
+}
+
+impl Default for AnnouncementTemplates {
+    /// Constructs templates giving "my-app 1.2.3" and "Release 1.2.3 (3 packages)"
+    fn default() -> AnnouncementTemplates {
+        AnnouncementTemplates {
+            package_title: "{package} {version}".to_owned(),
+            unified_title: "Release {version} ({packages})".to_owned(),
+            body_line: "* {package} {version}".to_owned(),
+        }
+    }
+}
+
+impl AnnouncementTemplates {
+    /// Render the title of an announcement
+    ///
+    /// Tags that announce nothing are titled with the tag itself.
+    pub fn render_title(
+        &self,
+        announcing: &PartialAnnouncementTag,
+        packages: &[Package],
+    ) -> String {
+        let template = match &announcing.release {
+            AnnouncementKind::None | AnnouncementKind::Reserved { .. } => {
+                return announcing.tag.clone()
+            }
+            AnnouncementKind::Package { .. }
+            | AnnouncementKind::UnresolvedPackage { .. }
+            | AnnouncementKind::Channel { idx: Some(_), .. } => &self.package_title,
+            AnnouncementKind::Unified(_) | AnnouncementKind::Channel { idx: None, .. } => {
+                &self.unified_title
+            }
+        };
+        let package = match &announcing.release {
+            AnnouncementKind::UnresolvedPackage { name, .. } => Some(name.as_str()),
+            release => release
+                .package_idx()
+                .and_then(|idx| packages.get(idx))
+                .map(|p| p.name.as_str()),
+        };
+        render(template, announcing, packages, package)
+    }
+
+    /// Render the body of an announcement, with a line for each announced package
+    pub fn render_body(&self, announcing: &PartialAnnouncementTag, packages: &[Package]) -> String {
+        announcing
+            .selected_packages(packages)
+            .into_iter()
+            .map(|idx| {
+                render(
+                    &self.body_line,
+                    announcing,
+                    packages,
+                    Some(&packages[idx].name),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Fill in a template's placeholders
+fn render(
+    template: &str,
+    announcing: &PartialAnnouncementTag,
+    packages: &[Package],
+    package: Option<&str>,
+) -> String {
+    let version = match &announcing.release {
+        AnnouncementKind::Channel { channel, .. } => channel.clone(),
+        release => release
+            .version()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+    };
+    let count = match announcing.selected_packages(packages).len() {
+        1 => "1 package".to_owned(),
+        n => format!("{n} packages"),
+    };
+    // One pass, so placeholders in what gets filled in (a tag can have braces) stay as is
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let value = match &rest[1..end] {
+                "tag" => &announcing.tag,
+                "version" => &version,
+                "package" => package.unwrap_or_default(),
+                "packages" => &count,
+                _ => return None,
+            };
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Render the title of an announcement with the default [`AnnouncementTemplates`][]
+///
+/// Gives something like "my-app 1.2.3" or "Release 1.2.3 (3 packages)".
+pub fn render_announcement_title(
+    announcing: &PartialAnnouncementTag,
+    packages: &[Package],
+) -> String {
+    AnnouncementTemplates::default().render_title(announcing, packages)
+}
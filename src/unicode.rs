@@ -0,0 +1,44 @@
+//! Unicode normalization of tags and package names, see `TagParser::normalize_unicode`.
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::Package;
+
+/// NFC-normalize a tag and the names (and aliases) of some packages, if any of them need it
+pub(crate) fn nfc_normalize(packages: &[Package], tag: &str) -> Option<(Vec<Package>, String)> {
+    match (nfc_packages(packages), nfc_tag(tag)) {
+        (None, None) => None,
+        (packages_nfc, tag_nfc) => Some((
+            packages_nfc.unwrap_or_else(|| packages.to_vec()),
+            tag_nfc.unwrap_or_else(|| tag.to_owned()),
+        )),
+    }
+}
+
+/// NFC-normalize the names (and aliases) of some packages, if any of them need it
+pub(crate) fn nfc_packages(packages: &[Package]) -> Option<Vec<Package>> {
+    let names_normalized = packages
+        .iter()
+        .all(|p| is_nfc(&p.name) && p.aliases.iter().all(|alias| is_nfc(alias)));
+    if names_normalized {
+        return None;
+    }
+    let packages = packages
+        .iter()
+        .map(|package| Package {
+            name: nfc(&package.name),
+            aliases: package.aliases.iter().map(|alias| nfc(alias)).collect(),
+            ..package.clone()
+        })
+        .collect();
+    Some(packages)
+}
+
+/// NFC-normalize a tag, if it needs it
+pub(crate) fn nfc_tag(tag: &str) -> Option<String> {
+    (!is_nfc(tag)).then(|| nfc(tag))
+}
+
+fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
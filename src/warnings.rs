@@ -0,0 +1,158 @@
+//! This module contains the warnings axotag can produce while parsing.
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+use crate::lenient::VersionFixup;
+use crate::{SegmentKind, TagFormat};
+
+/// Recoverable oddities noticed while parsing a tag
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[diagnostic(severity(Warning))]
+pub enum TagWarning {
+    /// The tag claimed a different version than its package has, but we were told to accept it
+    #[error("The tag claims we're releasing {package_name} {tag_version}, but that package is version {real_version}")]
+    ContradictoryTagVersion {
+        /// The package name
+        package_name: String,
+        /// The version the tag claimed
+        tag_version: semver::Version,
+        /// The version the package actually has
+        real_version: semver::Version,
+    },
+
+    /// The tag's version wasn't valid semver, but we were told to be lenient
+    #[error("The tag's version ({original}) isn't valid semver, it was read as {version}")]
+    LenientVersion {
+        /// The version as it was written in the tag
+        original: String,
+        /// The version we read it as
+        version: semver::Version,
+        /// What we had to fix
+        fixups: Vec<VersionFixup>,
+    },
+
+    /// The tag named a package whose name looks like a version, so it could also be a unified release
+    #[error("The package name {package_name} looks like a version, so this tag could also be a unified release")]
+    VersionLikePackageName {
+        /// The package name
+        package_name: String,
+    },
+
+    /// The tag or the package it names has a placeholder version (`0.0.0`)
+    #[error("{}", match package_name {
+        Some(package_name) => format!("The package {package_name} has a placeholder version"),
+        None => "The tag announces a placeholder version".to_owned(),
+    })]
+    PlaceholderVersion {
+        /// The package with the placeholder version, if it wasn't the tag's own version
+        package_name: Option<String>,
+    },
+
+    /// The tag doesn't announce a newer version than an existing tag, but we were told to accept it
+    #[error(
+        "The tag announces {version}, but {existing_tag} already announced {existing_version}"
+    )]
+    VersionRegression {
+        /// The version the tag announces
+        version: semver::Version,
+        /// The existing tag with the same or a newer version
+        existing_tag: String,
+        /// The version of the existing tag
+        existing_version: semver::Version,
+    },
+
+    /// The tag had an arbitrary prefix before the package or version (like `releases/`)
+    #[error("The tag has an unusual prefix ({prefix}/)")]
+    UnusualPrefix {
+        /// The prefix, without its trailing slash
+        prefix: String,
+    },
+
+    /// The tag named a package by one of its aliases
+    #[error("The tag names {package_name} by its alias {alias}")]
+    AliasUsed {
+        /// The package name
+        package_name: String,
+        /// The alias the tag used
+        alias: String,
+    },
+
+    /// The tag only matched a package because names were compared loosely
+    ///
+    /// See [`TagParser::case_insensitive_names`][crate::TagParser::case_insensitive_names].
+    #[error("The tag spells {package_name} as {found}")]
+    InexactPackageName {
+        /// The package name
+        package_name: String,
+        /// How the tag spelled it
+        found: String,
+    },
+
+    /// The tag named a package we don't have, so we went with the most similar package
+    ///
+    /// See [`TagParser::typo_tolerance`][crate::TagParser::typo_tolerance].
+    #[error("The tag names {found}, which isn't a package, so we assumed it meant {package_name}")]
+    TypoCorrected {
+        /// The package name we went with
+        package_name: String,
+        /// The name the tag used
+        found: String,
+    },
+
+    /// A segment of the tag could be several kinds of thing, and TagParser::segment_order
+    /// picked one
+    #[error("The segment {segment} of this tag could be several things, it was read as {chosen}")]
+    AmbiguousSegment {
+        /// The segment (everything before the slash it ends at)
+        segment: String,
+        /// What it was read as
+        chosen: SegmentKind,
+    },
+
+    /// The tag uses one of the later (legacy) TagParser::formats, and we were told to
+    /// deprecate those
+    #[error("This tag uses the deprecated {format:?} format, it's {canonical} in the current one")]
+    DeprecatedTagFormat {
+        /// The legacy format it matched
+        format: TagFormat,
+        /// The tag spelled in the preferred format
+        canonical: String,
+    },
+
+    /// A package's name prefixed the tag, but we didn't pick it
+    #[error("The package {package_name} looked like a match for this tag, but {reason}")]
+    RejectedCandidate {
+        /// The package name
+        package_name: String,
+        /// Why it wasn't picked
+        reason: RejectionReason,
+    },
+}
+
+/// Why a package that looked like a match for a tag wasn't picked
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RejectionReason {
+    /// A package with a longer name also matched, and won
+    #[error("{package_name} is a longer match")]
+    LongerMatch {
+        /// The package name that won
+        package_name: String,
+    },
+    /// The package name wasn't followed by a separator we accept
+    #[error("it was followed by '{found}' instead of a separator")]
+    WrongSeparator {
+        /// The character found after the package name
+        found: char,
+    },
+    /// The rest of the tag wasn't a valid version
+    #[error("{version} isn't a valid version")]
+    InvalidVersion {
+        /// The rest of the tag after the package name and separator
+        version: String,
+    },
+}